@@ -18,11 +18,15 @@ pub fn is_module_path_public(path: &ModulePath) -> bool {
     match path {
         ModulePath::External(path) => path.components(),
         ModulePath::Internal(path) => path.components(),
+        // A library only ever exposes what it marks with `export`.
+        ModulePath::Library(_) => return true,
     }
     .iter()
     .all(|component| is_name_public(component))
 }
 
+/// The conditional `?:` is not a binary operator; it binds looser than `Or` and is parsed
+/// around a fully reduced binary expression.
 pub fn operator_priority(operator: BinaryOperator) -> usize {
     match operator {
         BinaryOperator::Or => 1,
@@ -1,9 +1,17 @@
 mod argument;
+mod assignment;
+mod assignment_operator;
 mod binary_operation;
 mod binary_operator;
+mod boolean;
 mod block;
 mod call;
 mod calling_convention;
+mod color;
+mod conditional;
+mod annotation;
+mod declaration;
+mod enum_definition;
 mod expression;
 mod external_module_path;
 mod foreign_export;
@@ -18,6 +26,7 @@ mod if_type_branch;
 mod import;
 mod internal_module_path;
 mod lambda;
+mod library_module_path;
 mod list;
 mod list_comprehension;
 mod list_comprehension_branch;
@@ -35,10 +44,13 @@ mod record_definition;
 mod record_field;
 mod statement;
 mod string;
+mod tuple;
+mod tuple_definition;
 mod type_alias;
 mod type_definition;
 mod unary_operation;
 mod unary_operator;
+mod user_type_definition;
 mod unqualified_name;
 mod variable;
 mod main;
@@ -50,11 +62,19 @@ pub mod utils;
 
 
 pub use argument::*;
+pub use assignment::*;
+pub use assignment_operator::*;
 pub use binary_operation::*;
 pub use binary_operator::*;
+pub use boolean::*;
 pub use block::*;
 pub use call::*;
 pub use calling_convention::*;
+pub use color::*;
+pub use conditional::*;
+pub use annotation::*;
+pub use declaration::*;
+pub use enum_definition::*;
 pub use expression::*;
 pub use external_module_path::*;
 pub use foreign_export::*;
@@ -69,6 +89,7 @@ pub use if_type_branch::*;
 pub use import::*;
 pub use internal_module_path::*;
 pub use lambda::*;
+pub use library_module_path::*;
 pub use list::*;
 pub use list_comprehension::*;
 pub use list_comprehension_branch::*;
@@ -86,10 +107,13 @@ pub use record_definition::*;
 pub use record_field::*;
 pub use statement::*;
 pub use string::*;
+pub use tuple::*;
+pub use tuple_definition::*;
 pub use type_alias::*;
 pub use type_definition::*;
 pub use unary_operation::*;
 pub use unary_operator::*;
+pub use user_type_definition::*;
 pub use unqualified_name::*;
 pub use variable::*;
 pub use main::*;
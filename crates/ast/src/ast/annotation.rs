@@ -0,0 +1,206 @@
+use super::declaration::Declaration;
+use super::enum_definition::EnumDefinition;
+use super::node::{FunctionDecl, Mod, TopLevel};
+use super::user_type_definition::UserTypeDefinition;
+use position::Position;
+
+/// A `//@name text` comment line. The version selects the language, the others document the
+/// declaration that follows them:
+///
+/// ```text
+/// //@version=6
+/// //@function Average of two numbers.
+/// //@param a First number.
+/// //@param b Second number.
+/// //@returns Their mean.
+/// mean(a, b) => (a + b) / 2
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Annotation {
+    kind: AnnotationKind,
+    position: Position,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AnnotationKind {
+    /// `//@version=6`
+    Version(u32),
+    /// `//@description`, for the library itself.
+    Description(String),
+    Function(String),
+    /// `//@param name text`
+    Param(String, String),
+    Returns(String),
+    Type(String),
+    /// `//@field name text`
+    Field(String, String),
+    Enum(String),
+    /// `//@example code`, repeated for each line of code.
+    Example(String),
+    /// Any other annotation, by name, such as a version that isn't a number.
+    Other(String, String),
+}
+
+impl Annotation {
+    pub fn new(kind: AnnotationKind, position: Position) -> Self {
+        Self { kind, position }
+    }
+
+    pub fn kind(&self) -> &AnnotationKind {
+        &self.kind
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+}
+
+/// What the annotations right before a declaration say about it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Docs {
+    pub description: Option<String>,
+    pub params: Vec<(String, String)>,
+    pub returns: Option<String>,
+    pub fields: Vec<(String, String)>,
+    pub examples: Vec<String>,
+}
+
+impl Docs {
+    /// The docs of the doc annotations among `annotations`, or `None` when there are none.
+    pub fn from_annotations<'a>(
+        annotations: impl IntoIterator<Item = &'a Annotation>,
+    ) -> Option<Self> {
+        let mut docs = None::<Self>;
+
+        for annotation in annotations {
+            if let AnnotationKind::Version(_) | AnnotationKind::Other(..) = annotation.kind() {
+                continue;
+            }
+
+            let entry = docs.get_or_insert_with(Self::default);
+
+            match annotation.kind() {
+                AnnotationKind::Param(name, text) => {
+                    entry.params.push((name.clone(), text.clone()))
+                }
+                AnnotationKind::Returns(text) => entry.returns = Some(text.clone()),
+                AnnotationKind::Field(name, text) => {
+                    entry.fields.push((name.clone(), text.clone()))
+                }
+                AnnotationKind::Example(code) => entry.examples.push(code.clone()),
+                AnnotationKind::Description(text)
+                | AnnotationKind::Function(text)
+                | AnnotationKind::Type(text)
+                | AnnotationKind::Enum(text) => entry.description = Some(text.clone()),
+                AnnotationKind::Version(_) | AnnotationKind::Other(..) => {}
+            }
+        }
+
+        docs
+    }
+
+    pub fn param(&self, name: &str) -> Option<&str> {
+        Self::find(&self.params, name)
+    }
+
+    pub fn field(&self, name: &str) -> Option<&str> {
+        Self::find(&self.fields, name)
+    }
+
+    fn find<'a>(entries: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        entries
+            .iter()
+            .find(|(entry, _)| entry == name)
+            .map(|(_, text)| text.as_str())
+    }
+}
+
+/// A declaration of a module that can carry docs.
+#[derive(Clone, Copy, Debug)]
+pub enum Documented<'a> {
+    Library(&'a Declaration),
+    Function(&'a FunctionDecl),
+    Method(&'a FunctionDecl),
+    Type(&'a UserTypeDefinition),
+    Enum(&'a EnumDefinition),
+}
+
+impl<'a> Documented<'a> {
+    pub fn name(&self) -> &'a str {
+        match self {
+            Self::Library(declaration) => declaration.kind().name(),
+            Self::Function(function) | Self::Method(function) => &function.name.name,
+            Self::Type(definition) => definition.name(),
+            Self::Enum(definition) => definition.name(),
+        }
+    }
+
+    pub fn docs(&self) -> Option<&'a Docs> {
+        match self {
+            Self::Library(declaration) => declaration.docs(),
+            Self::Function(function) | Self::Method(function) => function.docs.as_ref(),
+            Self::Type(definition) => definition.docs(),
+            Self::Enum(definition) => definition.docs(),
+        }
+    }
+}
+
+impl Mod {
+    /// The language version the module selects with `//@version=N`.
+    pub fn version(&self) -> Option<u32> {
+        self.annotations()
+            .find_map(|annotation| match annotation.kind() {
+                AnnotationKind::Version(version) => Some(*version),
+                _ => None,
+            })
+    }
+
+    pub fn annotations(&self) -> impl Iterator<Item = &Annotation> {
+        self.top_levels
+            .iter()
+            .filter_map(|top_level| match top_level {
+                TopLevel::Annotation(annotation) => Some(annotation),
+                _ => None,
+            })
+    }
+
+    /// The declarations of the module that can be documented, in source order, whether or not
+    /// they have docs.
+    pub fn documented(&self) -> impl Iterator<Item = Documented<'_>> {
+        self.top_levels
+            .iter()
+            .filter_map(|top_level| match top_level {
+                TopLevel::Declaration(declaration) => Some(Documented::Library(declaration)),
+                TopLevel::Function(function) => Some(Documented::Function(function)),
+                TopLevel::Method(method) => Some(Documented::Method(method)),
+                TopLevel::Type(definition) => Some(Documented::Type(definition)),
+                TopLevel::Enum(definition) => Some(Documented::Enum(definition)),
+                _ => None,
+            })
+    }
+
+    /// Gives each declaration the docs of the annotations right before it.
+    pub fn attach_docs(&mut self) {
+        let mut pending: Vec<Annotation> = vec![];
+
+        for top_level in &mut self.top_levels {
+            match top_level {
+                TopLevel::Annotation(annotation) => {
+                    pending.push(annotation.clone());
+                    continue;
+                }
+                TopLevel::Declaration(declaration) => {
+                    declaration.set_docs(Docs::from_annotations(&pending))
+                }
+                TopLevel::Function(function) | TopLevel::Method(function) => {
+                    function.docs = Docs::from_annotations(&pending)
+                }
+                TopLevel::Type(definition) => definition.set_docs(Docs::from_annotations(&pending)),
+                TopLevel::Enum(definition) => definition.set_docs(Docs::from_annotations(&pending)),
+                _ => {}
+            }
+
+            pending.clear();
+        }
+    }
+}
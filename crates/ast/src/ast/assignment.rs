@@ -0,0 +1,53 @@
+use super::{expression::Expression, AssignmentOperator};
+use position::Position;
+use std::rc::Rc;
+
+/// Reassignment of an already declared variable or one of its fields: `name := value`,
+/// `name.field += value`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Assignment {
+    name: String,
+    fields: Vec<String>,
+    operator: AssignmentOperator,
+    value: Rc<Expression>,
+    position: Position,
+}
+
+impl Assignment {
+    pub fn new(
+        name: impl Into<String>,
+        fields: Vec<String>,
+        operator: AssignmentOperator,
+        value: impl Into<Expression>,
+        position: Position,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            fields,
+            operator,
+            value: Rc::new(value.into()),
+            position,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Field path below the variable, empty when the variable itself is reassigned.
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+
+    pub fn operator(&self) -> AssignmentOperator {
+        self.operator
+    }
+
+    pub fn value(&self) -> &Expression {
+        &self.value
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+}
@@ -0,0 +1,8 @@
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum AssignmentOperator {
+    Reassign,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
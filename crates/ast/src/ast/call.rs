@@ -6,6 +6,7 @@ use std::rc::Rc;
 pub struct Call {
     function: Rc<Expression>,
     arguments: Vec<Expression>,
+    named_arguments: Vec<NamedArgument>,
     position: Position,
 }
 
@@ -18,18 +19,60 @@ impl Call {
         Self {
             function: Rc::new(function.into()),
             arguments,
+            named_arguments: vec![],
             position,
         }
     }
 
+    pub fn with_named_arguments(mut self, named_arguments: Vec<NamedArgument>) -> Self {
+        self.named_arguments = named_arguments;
+        self
+    }
+
     pub fn function(&self) -> &Expression {
         &self.function
     }
 
+    /// The positional arguments, which come before the named ones.
     pub fn arguments(&self) -> &[Expression] {
         &self.arguments
     }
 
+    pub fn named_arguments(&self) -> &[NamedArgument] {
+        &self.named_arguments
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+}
+
+/// An argument given by the name of its parameter, as `minval = 1` in
+/// `input.int(14, "Length", minval = 1)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NamedArgument {
+    name: String,
+    value: Expression,
+    position: Position,
+}
+
+impl NamedArgument {
+    pub fn new(name: impl Into<String>, value: Expression, position: Position) -> Self {
+        Self {
+            name: name.into(),
+            value,
+            position,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &Expression {
+        &self.value
+    }
+
     pub fn position(&self) -> &Position {
         &self.position
     }
@@ -0,0 +1,43 @@
+use position::Position;
+
+/// A `#RRGGBB` or `#RRGGBBAA` literal; the alpha is `0xFF` when it's left out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Color {
+    red: u8,
+    green: u8,
+    blue: u8,
+    alpha: u8,
+    position: Position,
+}
+
+impl Color {
+    pub fn new(red: u8, green: u8, blue: u8, alpha: u8, position: Position) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+            position,
+        }
+    }
+
+    pub fn red(&self) -> u8 {
+        self.red
+    }
+
+    pub fn green(&self) -> u8 {
+        self.green
+    }
+
+    pub fn blue(&self) -> u8 {
+        self.blue
+    }
+
+    pub fn alpha(&self) -> u8 {
+        self.alpha
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+}
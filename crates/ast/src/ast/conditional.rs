@@ -0,0 +1,44 @@
+use super::expression::Expression;
+use position::Position;
+use std::rc::Rc;
+
+/// `condition ? then : else_`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conditional {
+    condition: Rc<Expression>,
+    then: Rc<Expression>,
+    else_: Rc<Expression>,
+    position: Position,
+}
+
+impl Conditional {
+    pub fn new(
+        condition: impl Into<Expression>,
+        then: impl Into<Expression>,
+        else_: impl Into<Expression>,
+        position: Position,
+    ) -> Self {
+        Self {
+            condition: Rc::new(condition.into()),
+            then: Rc::new(then.into()),
+            else_: Rc::new(else_.into()),
+            position,
+        }
+    }
+
+    pub fn condition(&self) -> &Expression {
+        &self.condition
+    }
+
+    pub fn then(&self) -> &Expression {
+        &self.then
+    }
+
+    pub fn else_(&self) -> &Expression {
+        &self.else_
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+}
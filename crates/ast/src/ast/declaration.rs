@@ -0,0 +1,186 @@
+use super::annotation::Docs;
+use super::expression::Expression;
+use position::Position;
+
+/// The kind of script a declaration statement starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DeclarationKind {
+    Indicator,
+    Strategy,
+    Library,
+}
+
+impl DeclarationKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "indicator" => Some(Self::Indicator),
+            "strategy" => Some(Self::Strategy),
+            "library" => Some(Self::Library),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Indicator => "indicator",
+            Self::Strategy => "strategy",
+            Self::Library => "library",
+        }
+    }
+
+    /// The parameters of the declaration, in the order it takes them positionally.
+    pub fn parameters(&self) -> &'static [&'static str] {
+        match self {
+            Self::Indicator => &[
+                "title",
+                "shorttitle",
+                "overlay",
+                "format",
+                "precision",
+                "scale",
+                "max_bars_back",
+                "timeframe",
+                "timeframe_gaps",
+                "explicit_plot_zorder",
+                "max_lines_count",
+                "max_labels_count",
+                "max_boxes_count",
+                "calc_bars_count",
+                "max_polylines_count",
+                "dynamic_requests",
+                "behind_chart",
+            ],
+            Self::Strategy => &[
+                "title",
+                "shorttitle",
+                "overlay",
+                "format",
+                "precision",
+                "scale",
+                "pyramiding",
+                "calc_on_order_fills",
+                "calc_on_every_tick",
+                "max_bars_back",
+                "backtest_fill_limits_assumption",
+                "default_qty_type",
+                "default_qty_value",
+                "initial_capital",
+                "currency",
+                "slippage",
+                "commission_type",
+                "commission_value",
+                "process_orders_on_close",
+                "close_entries_rule",
+                "margin_long",
+                "margin_short",
+                "explicit_plot_zorder",
+                "max_lines_count",
+                "max_labels_count",
+                "max_boxes_count",
+                "calc_bars_count",
+                "risk_free_rate",
+                "use_bar_magnifier",
+                "fill_orders_on_standard_ohlc",
+                "max_polylines_count",
+                "dynamic_requests",
+                "behind_chart",
+            ],
+            Self::Library => &["title", "overlay", "dynamic_requests"],
+        }
+    }
+}
+
+/// The statement that starts a script and describes it:
+///
+/// ```text
+/// indicator("RSI", overlay = false, precision = 2)
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Declaration {
+    kind: DeclarationKind,
+    arguments: Vec<DeclarationArgument>,
+    position: Position,
+    docs: Option<Docs>,
+}
+
+impl Declaration {
+    pub fn new(
+        kind: DeclarationKind,
+        arguments: Vec<DeclarationArgument>,
+        position: Position,
+    ) -> Self {
+        Self {
+            kind,
+            arguments,
+            position,
+            docs: None,
+        }
+    }
+
+    pub fn kind(&self) -> DeclarationKind {
+        self.kind
+    }
+
+    pub fn arguments(&self) -> &[DeclarationArgument] {
+        &self.arguments
+    }
+
+    /// Each argument with the parameter it's for, or `None` past the positional parameters.
+    pub fn parameters(&self) -> impl Iterator<Item = (Option<&str>, &DeclarationArgument)> {
+        let parameters = self.kind.parameters();
+        let mut positional = 0;
+
+        self.arguments
+            .iter()
+            .map(move |argument| match argument.name() {
+                Some(name) => (Some(name), argument),
+                None => {
+                    positional += 1;
+
+                    (parameters.get(positional - 1).copied(), argument)
+                }
+            })
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    pub fn docs(&self) -> Option<&Docs> {
+        self.docs.as_ref()
+    }
+
+    pub fn set_docs(&mut self, docs: Option<Docs>) {
+        self.docs = docs;
+    }
+}
+
+/// An argument of a declaration, positional or named as in `overlay = true`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeclarationArgument {
+    name: Option<String>,
+    value: Expression,
+    position: Position,
+}
+
+impl DeclarationArgument {
+    pub fn new(name: Option<String>, value: Expression, position: Position) -> Self {
+        Self {
+            name,
+            value,
+            position,
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn value(&self) -> &Expression {
+        &self.value
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+}
@@ -0,0 +1,94 @@
+use super::annotation::Docs;
+use super::string::ByteString;
+use crate::ast::identifier::Identifier;
+use position::Position;
+
+/// Enumeration with optional display titles:
+///
+/// ```text
+/// enum Signal
+///     buy = "Buy signal"
+///     sell = "Sell signal"
+///     neutral
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumDefinition {
+    name: Identifier,
+    members: Vec<EnumMemberDefinition>,
+    position: Position,
+    docs: Option<Docs>,
+    exported: bool,
+}
+
+impl EnumDefinition {
+    pub fn new(name: Identifier, members: Vec<EnumMemberDefinition>, position: Position) -> Self {
+        Self {
+            name,
+            members,
+            position,
+            docs: None,
+            exported: false,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name.name
+    }
+
+    pub fn members(&self) -> &[EnumMemberDefinition] {
+        &self.members
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    pub fn docs(&self) -> Option<&Docs> {
+        self.docs.as_ref()
+    }
+
+    pub fn set_docs(&mut self, docs: Option<Docs>) {
+        self.docs = docs;
+    }
+
+    /// Whether the enum is marked with `export`, for the scripts importing the library.
+    pub fn is_exported(&self) -> bool {
+        self.exported
+    }
+
+    pub fn set_exported(&mut self, exported: bool) {
+        self.exported = exported;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumMemberDefinition {
+    name: Identifier,
+    title: Option<ByteString>,
+    position: Position,
+}
+
+impl EnumMemberDefinition {
+    pub fn new(name: Identifier, title: Option<ByteString>, position: Position) -> Self {
+        Self {
+            name,
+            title,
+            position,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name.name
+    }
+
+    /// Display title; defaults to the member name.
+    pub fn title(&self) -> &str {
+        self.title
+            .as_ref()
+            .map_or(self.name(), |title| title.value())
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+}
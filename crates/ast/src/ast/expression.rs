@@ -1,14 +1,23 @@
 use super::{
-    BinaryOperation, ByteString, Call, If, IfList, IfMap, IfType, Lambda, List, ListComprehension,
-    Map, Number, Record, RecordDeconstruction, UnaryOperation, Variable,
+    Assignment, BinaryOperation, Boolean, ByteString, Call, Color, Conditional, For, If, IfDecl, IfList, IfMap, IfType, Lambda,
+    List, ListComprehension, Map, Number, Record, RecordDeconstruction, Switch, Tuple, TupleDefinition, UnaryOperation,
+    Variable,
 };
 use position::Position;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Expression {
+    Assignment(Assignment),
     BinaryOperation(BinaryOperation),
+    Boolean(Boolean),
+    Break(Position),
     Call(Call),
+    Color(Color),
+    Conditional(Conditional),
+    Continue(Position),
+    For(Box<For>),
     If(If),
+    IfDecl(Box<IfDecl>),
     IfList(IfList),
     IfMap(IfMap),
     IfType(IfType),
@@ -16,21 +25,34 @@ pub enum Expression {
     List(List),
     ListComprehension(ListComprehension),
     Map(Map),
+    /// `na` used as a value; `na(x)` is a call to the built-in function instead.
+    Na(Position),
     Number(Number),
     Record(Record),
     RecordDeconstruction(RecordDeconstruction),
     String(ByteString),
+    Switch(Box<Switch>),
+    Tuple(Tuple),
+    TupleDefinition(TupleDefinition),
     UnaryOperation(UnaryOperation),
     Variable(Variable),
-
 }
 
 impl Expression {
     pub fn position(&self) -> &Position {
         match self {
+            Self::Assignment(assignment) => assignment.position(),
             Self::BinaryOperation(operation) => operation.position(),
+            Self::Boolean(boolean) => boolean.position(),
+            Self::Break(position) => position,
+            Self::Na(position) => position,
             Self::Call(call) => call.position(),
+            Self::Color(color) => color.position(),
+            Self::Conditional(conditional) => conditional.position(),
+            Self::Continue(position) => position,
+            Self::For(for_) => for_.position(),
             Self::If(if_) => if_.position(),
+            Self::IfDecl(if_) => if_.position(),
             Self::IfList(if_) => if_.position(),
             Self::IfMap(if_) => if_.position(),
             Self::IfType(if_) => if_.position(),
@@ -42,6 +64,9 @@ impl Expression {
             Self::Record(record) => record.position(),
             Self::RecordDeconstruction(operation) => operation.position(),
             Self::String(string) => string.position(),
+            Self::Switch(switch) => switch.position(),
+            Self::Tuple(tuple) => tuple.position(),
+            Self::TupleDefinition(definition) => definition.position(),
             Self::UnaryOperation(operation) => operation.position(),
             Self::Variable(variable) => variable.position(),
             //Self::Na() => None,
@@ -49,18 +74,42 @@ impl Expression {
     }
 }
 
+impl From<Assignment> for Expression {
+    fn from(assignment: Assignment) -> Self {
+        Self::Assignment(assignment)
+    }
+}
+
 impl From<BinaryOperation> for Expression {
     fn from(operation: BinaryOperation) -> Self {
         Self::BinaryOperation(operation)
     }
 }
 
+impl From<Boolean> for Expression {
+    fn from(boolean: Boolean) -> Self {
+        Self::Boolean(boolean)
+    }
+}
+
 impl From<Call> for Expression {
     fn from(call: Call) -> Self {
         Self::Call(call)
     }
 }
 
+impl From<Color> for Expression {
+    fn from(color: Color) -> Self {
+        Self::Color(color)
+    }
+}
+
+impl From<Conditional> for Expression {
+    fn from(conditional: Conditional) -> Self {
+        Self::Conditional(conditional)
+    }
+}
+
 impl From<IfType> for Expression {
     fn from(if_: IfType) -> Self {
         Self::IfType(if_)
@@ -73,12 +122,24 @@ impl From<ByteString> for Expression {
     }
 }
 
+impl From<For> for Expression {
+    fn from(for_: For) -> Self {
+        Self::For(Box::new(for_))
+    }
+}
+
 impl From<If> for Expression {
     fn from(if_: If) -> Self {
         Self::If(if_)
     }
 }
 
+impl From<IfDecl> for Expression {
+    fn from(if_: IfDecl) -> Self {
+        Self::IfDecl(Box::new(if_))
+    }
+}
+
 impl From<IfList> for Expression {
     fn from(if_: IfList) -> Self {
         Self::IfList(if_)
@@ -133,6 +194,24 @@ impl From<RecordDeconstruction> for Expression {
     }
 }
 
+impl From<Tuple> for Expression {
+    fn from(tuple: Tuple) -> Self {
+        Self::Tuple(tuple)
+    }
+}
+
+impl From<TupleDefinition> for Expression {
+    fn from(definition: TupleDefinition) -> Self {
+        Self::TupleDefinition(definition)
+    }
+}
+
+impl From<Switch> for Expression {
+    fn from(switch: Switch) -> Self {
+        Self::Switch(Box::new(switch))
+    }
+}
+
 impl From<UnaryOperation> for Expression {
     fn from(operation: UnaryOperation) -> Self {
         Self::UnaryOperation(operation)
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// A published library pinned to a version, as in `import alice/Geometry/2 as geo`.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+pub struct LibraryModulePath {
+    owner: String,
+    name: String,
+    version: u32,
+}
+
+impl LibraryModulePath {
+    pub fn new(owner: impl Into<String>, name: impl Into<String>, version: u32) -> Self {
+        Self {
+            owner: owner.into(),
+            name: name.into(),
+            version,
+        }
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+}
+
+impl Display for LibraryModulePath {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}/{}/{}", self.owner, self.name, self.version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            LibraryModulePath::new("alice", "Geometry", 2).to_string(),
+            "alice/Geometry/2"
+        );
+    }
+}
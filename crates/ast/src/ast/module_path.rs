@@ -1,4 +1,7 @@
-use super::{external_module_path::ExternalModulePath, internal_module_path::InternalModulePath};
+use super::{
+    external_module_path::ExternalModulePath, internal_module_path::InternalModulePath,
+    library_module_path::LibraryModulePath,
+};
 use serde::{Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
@@ -6,6 +9,7 @@ use std::fmt::{self, Display, Formatter};
 pub enum ModulePath {
     External(ExternalModulePath),
     Internal(InternalModulePath),
+    Library(LibraryModulePath),
 }
 
 impl From<ExternalModulePath> for ModulePath {
@@ -20,11 +24,18 @@ impl From<InternalModulePath> for ModulePath {
     }
 }
 
+impl From<LibraryModulePath> for ModulePath {
+    fn from(path: LibraryModulePath) -> Self {
+        Self::Library(path)
+    }
+}
+
 impl Display for ModulePath {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         match self {
             Self::External(path) => write!(formatter, "{path}"),
             Self::Internal(path) => write!(formatter, "{path}"),
+            Self::Library(path) => write!(formatter, "{path}"),
         }
     }
 }
@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 use position::Position;
-use crate::{Block, Comment, Expression, ForeignExport, FunctionDefinition, Identifier, Import, Lambda, NodeId, Statement, VariableDefinition};
+use crate::{Annotation, Assignment, Docs, Comment, Declaration, DeclarationKind, EnumDefinition, Expression, Identifier, Import, NodeId, Statement, TupleDefinition, UserTypeDefinition, VariableDefinition};
 use crate::ast::utils::{ResolutionMap, TraitSolver};
+use crate::datatype::DataType;
 
 
 #[derive(Debug, Clone)]
@@ -13,18 +14,62 @@ impl Mod {
     pub fn new(top_levels: Vec<TopLevel>) -> Self {
         Self { top_levels }
     }
+
+    /// Whether the module is declared with `library(...)`.
+    pub fn is_library(&self) -> bool {
+        self.top_levels.iter().any(|top_level| {
+            matches!(
+                top_level,
+                TopLevel::Declaration(declaration) if declaration.kind() == DeclarationKind::Library
+            )
+        })
+    }
+
+    /// Names and positions of the functions, methods, types and enums marked with `export`, in
+    /// source order.
+    pub fn exports(&self) -> impl Iterator<Item = (&str, &Position)> {
+        self.top_levels
+            .iter()
+            .filter_map(|top_level| match top_level {
+                TopLevel::Function(function) | TopLevel::Method(function)
+                    if function.exported =>
+                {
+                    Some((function.name.name.as_str(), &function.position))
+                }
+                TopLevel::Type(definition) if definition.is_exported() => {
+                    Some((definition.name(), definition.position()))
+                }
+                TopLevel::Enum(definition) if definition.is_exported() => {
+                    Some((definition.name(), definition.position()))
+                }
+                _ => None,
+            })
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum TopLevel {
     Variable(VariableDefinition),
+    Assignment(Assignment),
+    TupleDefinition(TupleDefinition),
     Function(FunctionDecl),
+    /// `method name(Type this, ...) =>`; the first parameter is the receiver.
+    Method(FunctionDecl),
+    Type(UserTypeDefinition),
+    Enum(EnumDefinition),
+    /// `indicator(...)`, `strategy(...)` or `library(...)`.
+    Declaration(Declaration),
     Comment(Comment),
+    /// A `//@name text` line, see [`Annotation`].
+    Annotation(Annotation),
     Import(Import),
     If(IfDecl),
     While(While),
     For(For),
-    Mod(Identifier, Mod),
+    Switch(Switch),
+    /// A bare expression such as `array.push(a, x)`, evaluated for its effects.
+    Expression(Expression),
+    Mod(Identifier, Mod, Position),
 
 }
 
@@ -32,6 +77,24 @@ impl TopLevel {
     pub fn new_function(f: FunctionDecl) -> Self {
         Self::Function(f)
     }
+    pub fn new_method(f: FunctionDecl) -> Self {
+        Self::Method(f)
+    }
+    pub fn new_type(f: UserTypeDefinition) -> Self {
+        Self::Type(f)
+    }
+    pub fn new_enum(f: EnumDefinition) -> Self {
+        Self::Enum(f)
+    }
+    pub fn new_declaration(f: Declaration) -> Self {
+        Self::Declaration(f)
+    }
+    pub fn new_annotation(f: Annotation) -> Self {
+        Self::Annotation(f)
+    }
+    pub fn new_import(f: Import) -> Self {
+        Self::Import(f)
+    }
     pub fn new_if(f: IfDecl) -> Self {
         Self::If(f)
     }
@@ -42,12 +105,28 @@ impl TopLevel {
     pub fn new_for(f: For) -> Self {
         Self::For(f)
     }
+    pub fn new_switch(f: Switch) -> Self {
+        Self::Switch(f)
+    }
+
+    pub fn new_assignment(f: Assignment) -> Self {
+        Self::Assignment(f)
+    }
+
+    pub fn new_tuple_definition(f: TupleDefinition) -> Self {
+        Self::TupleDefinition(f)
+    }
+
+    pub fn new_expression(f: Expression) -> Self {
+        Self::Expression(f)
+    }
+
     pub fn new_var(f: VariableDefinition) -> Self {
         Self::Variable(f)
     }
 
-    pub fn new_mod(ident: Identifier, mod_: Mod) -> Self {
-        Self::Mod(ident, mod_)
+    pub fn new_mod(ident: Identifier, mod_: Mod, position: Position) -> Self {
+        Self::Mod(ident, mod_, position)
     }
 }
 
@@ -72,7 +151,10 @@ pub struct FunctionDecl {
     pub node_id: NodeId,
 
     pub position: Position,
-
+    /// From the doc annotations right before the function.
+    pub docs: Option<Docs>,
+    /// Marked with `export`, for the scripts importing the library.
+    pub exported: bool,
 }
 
 impl FunctionDecl {
@@ -94,6 +176,8 @@ impl FunctionDecl {
             body,
             node_id,
             position,
+            docs: None,
+            exported: false,
         }
     }
 
@@ -110,6 +194,7 @@ impl FunctionDecl {
 #[derive(Debug, PartialEq, Clone)]
 pub struct Parameter {
     pub name: Identifier,                        // 参数名称
+    pub data_type: Option<DataType>,
     pub default_value: Option<Expression>,         // 可选的默认值
     position: Position,
 }
@@ -122,11 +207,17 @@ impl Parameter {
     ) -> Self {
         Self {
             name,
+            data_type: None,
             default_value,
             position,
         }
     }
 
+    pub fn with_data_type(mut self, data_type: Option<DataType>) -> Self {
+        self.data_type = data_type;
+        self
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -138,12 +229,13 @@ impl Parameter {
 }
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct IfDecl {
     pub node_id: NodeId,
     pub predicat: Expression,
     pub body: Body,
     pub else_: Option<Box<Else>>,
+    pub position: Position,
 }
 
 impl IfDecl {
@@ -152,12 +244,14 @@ impl IfDecl {
         predicat: Expression,
         body: Body,
         else_: Option<Box<Else>>,
+        position: Position,
     ) -> Self {
         Self {
             node_id,
             predicat,
             body,
             else_,
+            position,
         }
     }
 
@@ -180,9 +274,13 @@ impl IfDecl {
             None
         }
     }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Else {
     If(IfDecl),
     Body(Body),
@@ -205,37 +303,131 @@ impl Else {
 }
 
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum For {
     In(ForIn),
+    Range(Box<ForRange>),
     While(While),
 }
 
-#[derive(Debug, Clone)]
+impl For {
+    pub fn position(&self) -> &Position {
+        match self {
+            For::In(for_in) => &for_in.position,
+            For::Range(for_range) => &for_range.position,
+            For::While(while_) => &while_.position,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct While {
     pub predicat: Expression,
     pub body: Body,
+    pub position: Position,
 }
 
 impl While {
-    pub fn new(predicat: Expression, body: Body) -> Self {
-        Self { predicat, body }
+    pub fn new(predicat: Expression, body: Body, position: Position) -> Self {
+        Self {
+            predicat,
+            body,
+            position,
+        }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ForIn {
     pub value: Identifier,
     pub expr: Expression,
     pub body: Body,
+    pub position: Position,
 }
 
 impl ForIn {
-    pub fn new(value: Identifier, expr: Expression, body: Body) -> Self {
-        Self { value, expr, body }
+    pub fn new(value: Identifier, expr: Expression, body: Body, position: Position) -> Self {
+        Self {
+            value,
+            expr,
+            body,
+            position,
+        }
+    }
+}
+
+/// `for <value> = <start> to <end> [by <step>]`, with both bounds inclusive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForRange {
+    pub value: Identifier,
+    pub start: Expression,
+    pub end: Expression,
+    pub step: Option<Expression>,
+    pub body: Body,
+    pub position: Position,
+}
+
+impl ForRange {
+    pub fn new(
+        value: Identifier,
+        start: Expression,
+        end: Expression,
+        step: Option<Expression>,
+        body: Body,
+        position: Position,
+    ) -> Self {
+        Self {
+            value,
+            start,
+            end,
+            step,
+            body,
+            position,
+        }
     }
 }
 
+/// `switch [subject]` with `pattern => body` arms and an optional trailing `=> body` default.
+/// Without a subject each pattern is a condition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Switch {
+    pub subject: Option<Expression>,
+    pub arms: Vec<SwitchArm>,
+    pub default: Option<Body>,
+    pub position: Position,
+}
+
+impl Switch {
+    pub fn new(
+        subject: Option<Expression>,
+        arms: Vec<SwitchArm>,
+        default: Option<Body>,
+        position: Position,
+    ) -> Self {
+        Self {
+            subject,
+            arms,
+            default,
+            position,
+        }
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchArm {
+    pub pattern: Expression,
+    pub body: Body,
+}
+
+impl SwitchArm {
+    pub fn new(pattern: Expression, body: Body) -> Self {
+        Self { pattern, body }
+    }
+}
 
 #[derive(PartialEq, Debug, Clone)]
 pub struct VariableStatement {
@@ -2,5 +2,8 @@
 pub enum NumberRepresentation {
     Binary(String),
     Hexadecimal(String),
+    /// A decimal literal without a fraction or exponent, such as `1`.
+    Integer(String),
+    /// A decimal literal with a fraction or exponent, such as `1.0` or `1e-3`.
     FloatingPoint(String),
 }
@@ -1,4 +1,5 @@
 use super::expression::Expression;
+use crate::datatype::DataType;
 use position::Position;
 use std::rc::Rc;
 
@@ -6,6 +7,8 @@ use std::rc::Rc;
 pub struct RecordDeconstruction {
     expression: Rc<Expression>,
     name: String,
+    /// `<float>` in `array.new<float>(...)`.
+    type_arguments: Vec<DataType>,
     position: Position,
 }
 
@@ -18,10 +21,16 @@ impl RecordDeconstruction {
         Self {
             expression: expression.into().into(),
             name: name.into(),
+            type_arguments: vec![],
             position,
         }
     }
 
+    pub fn with_type_arguments(mut self, type_arguments: Vec<DataType>) -> Self {
+        self.type_arguments = type_arguments;
+        self
+    }
+
     pub fn expression(&self) -> &Expression {
         &self.expression
     }
@@ -30,6 +39,10 @@ impl RecordDeconstruction {
         &self.name
     }
 
+    pub fn type_arguments(&self) -> &[DataType] {
+        &self.type_arguments
+    }
+
     pub fn position(&self) -> &Position {
         &self.position
     }
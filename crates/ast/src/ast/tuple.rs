@@ -0,0 +1,23 @@
+use super::expression::Expression;
+use position::Position;
+
+/// `[a, b, ...]` with at least two elements, as returned by functions yielding several values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tuple {
+    elements: Vec<Expression>,
+    position: Position,
+}
+
+impl Tuple {
+    pub fn new(elements: Vec<Expression>, position: Position) -> Self {
+        Self { elements, position }
+    }
+
+    pub fn elements(&self) -> &[Expression] {
+        &self.elements
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+}
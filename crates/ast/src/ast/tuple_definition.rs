@@ -0,0 +1,34 @@
+use super::expression::Expression;
+use crate::ast::identifier::Identifier;
+use position::Position;
+use std::rc::Rc;
+
+/// Destructuring declaration: `[a, b, c] = value`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TupleDefinition {
+    names: Vec<Identifier>,
+    value: Rc<Expression>,
+    position: Position,
+}
+
+impl TupleDefinition {
+    pub fn new(names: Vec<Identifier>, value: impl Into<Expression>, position: Position) -> Self {
+        Self {
+            names,
+            value: Rc::new(value.into()),
+            position,
+        }
+    }
+
+    pub fn names(&self) -> &[Identifier] {
+        &self.names
+    }
+
+    pub fn value(&self) -> &Expression {
+        &self.value
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+}
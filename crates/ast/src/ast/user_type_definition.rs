@@ -0,0 +1,102 @@
+use super::annotation::Docs;
+use super::expression::Expression;
+use crate::ast::identifier::Identifier;
+use crate::datatype::DataType;
+use position::Position;
+
+/// User-defined type:
+///
+/// ```text
+/// type Pivot
+///     float price = 0.0
+///     int bar
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserTypeDefinition {
+    name: Identifier,
+    fields: Vec<UserTypeField>,
+    position: Position,
+    docs: Option<Docs>,
+    exported: bool,
+}
+
+impl UserTypeDefinition {
+    pub fn new(name: Identifier, fields: Vec<UserTypeField>, position: Position) -> Self {
+        Self {
+            name,
+            fields,
+            position,
+            docs: None,
+            exported: false,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name.name
+    }
+
+    pub fn fields(&self) -> &[UserTypeField] {
+        &self.fields
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+
+    pub fn docs(&self) -> Option<&Docs> {
+        self.docs.as_ref()
+    }
+
+    pub fn set_docs(&mut self, docs: Option<Docs>) {
+        self.docs = docs;
+    }
+
+    /// Whether the type is marked with `export`, for the scripts importing the library.
+    pub fn is_exported(&self) -> bool {
+        self.exported
+    }
+
+    pub fn set_exported(&mut self, exported: bool) {
+        self.exported = exported;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserTypeField {
+    data_type: DataType,
+    name: Identifier,
+    default: Option<Expression>,
+    position: Position,
+}
+
+impl UserTypeField {
+    pub fn new(
+        data_type: DataType,
+        name: Identifier,
+        default: Option<Expression>,
+        position: Position,
+    ) -> Self {
+        Self {
+            data_type,
+            name,
+            default,
+            position,
+        }
+    }
+
+    pub fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name.name
+    }
+
+    pub fn default(&self) -> Option<&Expression> {
+        self.default.as_ref()
+    }
+
+    pub fn position(&self) -> &Position {
+        &self.position
+    }
+}
@@ -20,6 +20,38 @@ pub enum DataType {
     Table,
     Array(Box<DataType>),
     Matrix(Box<DataType>),
+    /// `map<key, value>`
+    Map(Box<DataType>, Box<DataType>),
     UDF,
+    /// A user-defined type declared with `type`.
+    Custom(String),
+    /// `[a, b, ...]`, the values a function returns together.
+    Tuple(Vec<DataType>),
     // 其他类型
-}
\ No newline at end of file
+}
+impl std::fmt::Display for DataType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataType::Int => write!(f, "int"),
+            DataType::Float => write!(f, "float"),
+            DataType::Bool => write!(f, "bool"),
+            DataType::Color => write!(f, "color"),
+            DataType::String => write!(f, "string"),
+            DataType::Line => write!(f, "line"),
+            DataType::LineFill => write!(f, "linefill"),
+            DataType::Label => write!(f, "label"),
+            DataType::Box => write!(f, "box"),
+            DataType::Table => write!(f, "table"),
+            DataType::Array(element) => write!(f, "array<{element}>"),
+            DataType::Matrix(element) => write!(f, "matrix<{element}>"),
+            DataType::Map(key, value) => write!(f, "map<{key}, {value}>"),
+            DataType::UDF => write!(f, "UDF"),
+            DataType::Custom(name) => write!(f, "{name}"),
+            DataType::Tuple(elements) => {
+                let elements = elements.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+                write!(f, "[{}]", elements.join(", "))
+            }
+        }
+    }
+}
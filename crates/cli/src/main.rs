@@ -0,0 +1,87 @@
+//! `pine <script> [--input name=value]...` runs a script with its inputs set from the command
+//! line, as in `pine rsi.ps --input length=21`, and prints its plots.
+
+use std::process::ExitCode;
+
+use parse::config::Config;
+use parse::eval_ast::parse_str;
+use parse::{DiagnosticType, ParsingCtx, SourceFile};
+use runtime::Runtime;
+
+const USAGE: &str = "usage: pine <script> [--input name=value]...";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{message}");
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let path = script_path(args)?;
+    let source = SourceFile::from_file(path.clone())
+        .map_err(|_| format!("can't read the script `{path}`"))?;
+    let mut runtime = Runtime::default();
+    let config = Config {
+        quiet: true,
+        overloads: runtime.overloads(),
+        ..Config::default()
+    };
+    let mut parsing_ctx = ParsingCtx::new(&config);
+
+    parsing_ctx.add_file(&source);
+
+    let root = parse_str(&mut parsing_ctx, &config).map_err(|diagnostic| {
+        diagnostic.print(&source, &DiagnosticType::Error);
+
+        format!("`{path}` doesn't compile")
+    })?;
+    let mut inputs = Runtime::script_inputs(&root);
+
+    inputs.apply_args(args).map_err(|error| error.to_string())?;
+    runtime.set_inputs(inputs);
+
+    let value = runtime.eval_app(root);
+
+    if value.is_error() {
+        return Err(value.format(path, source.content));
+    }
+
+    for plot in runtime.plots() {
+        let points: Vec<_> = plot
+            .points
+            .iter()
+            .map(|point| point.value.to_string())
+            .collect();
+
+        println!("{}: {}", plot.title, points.join(", "));
+    }
+
+    Ok(())
+}
+
+/// The one argument that isn't an option, nor the value of `--input`.
+fn script_path(args: &[String]) -> Result<String, String> {
+    let mut args = args.iter();
+    let mut path = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => {
+                args.next();
+            }
+            arg if arg.starts_with("--input=") => {}
+            arg if arg.starts_with('-') => return Err(format!("unknown option `{arg}`\n{USAGE}")),
+            arg if path.is_none() => path = Some(arg.to_string()),
+            arg => return Err(format!("unexpected argument `{arg}`\n{USAGE}")),
+        }
+    }
+
+    path.ok_or_else(|| USAGE.to_string())
+}
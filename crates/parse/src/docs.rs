@@ -0,0 +1,762 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use ast::datatype::DataType;
+use ast::{Docs, Documented, Expression, FunctionDecl, Mod};
+
+use crate::eval_ast::parse_str;
+use crate::parser::config::Config;
+use crate::parser::{Diagnostic, ParsingCtx, SourceFile};
+use crate::Span;
+
+/// The extension of script files.
+const EXTENSION: &str = "ps";
+
+/// Reference docs of a set of scripts, made from their declarations and the doc annotations
+/// before them, see [`ast::Annotation`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Documentation {
+    pub scripts: Vec<ScriptDocs>,
+}
+
+/// The docs of a script, or of the library it declares.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScriptDocs {
+    /// The name of the file, without its extension.
+    pub name: String,
+    /// The title given to `library(...)`, `indicator(...)` or `strategy(...)`.
+    pub title: Option<String>,
+    pub version: Option<u32>,
+    pub description: Option<String>,
+    pub items: Vec<ItemDocs>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ItemKind {
+    Function,
+    Method,
+    Type,
+    Enum,
+}
+
+impl ItemKind {
+    fn heading(&self) -> &'static str {
+        match self {
+            ItemKind::Function => "Functions",
+            ItemKind::Method => "Methods",
+            ItemKind::Type => "Types",
+            ItemKind::Enum => "Enums",
+        }
+    }
+}
+
+/// A function, method, type or enum of a script.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ItemDocs {
+    pub kind: ItemKind,
+    pub name: String,
+    pub signature: String,
+    pub description: Option<String>,
+    /// The parameters of a function, the fields of a type or the members of an enum.
+    pub params: Vec<ParamDocs>,
+    pub returns: Option<String>,
+    pub examples: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParamDocs {
+    pub name: String,
+    pub ty: Option<DataType>,
+    /// The source of the default value, or the title of an enum member.
+    pub default: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Documentation {
+    /// Documents the script at `path`.
+    pub fn from_file(path: &Path, config: &Config) -> Result<Self, Diagnostic> {
+        Ok(Self {
+            scripts: vec![ScriptDocs::from_file(path, config)?],
+        })
+    }
+
+    /// Documents every script of `dir` and its subdirectories, in path order.
+    pub fn from_dir(dir: &Path, config: &Config) -> Result<Self, Diagnostic> {
+        let mut paths = vec![];
+
+        collect_scripts(dir, &mut paths).map_err(|_| {
+            Diagnostic::new_file_not_found(Span::empty(), dir.display().to_string())
+        })?;
+        paths.sort();
+
+        Ok(Self {
+            scripts: paths
+                .iter()
+                .map(|path| ScriptDocs::from_file(path, config))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// A single Markdown document, with a table of contents and links between the items.
+    pub fn markdown(&self) -> String {
+        let links = self.links();
+        let mut markdown = String::new();
+
+        for script in &self.scripts {
+            script.write_markdown(&mut markdown, &links);
+        }
+
+        markdown
+    }
+
+    /// A standalone HTML page, with a navigation bar and links between the items.
+    pub fn html(&self) -> String {
+        let links = self.links();
+        let mut nav = String::new();
+        let mut body = String::new();
+
+        for script in &self.scripts {
+            let _ = write!(
+                nav,
+                "<li><a href=\"#{}\">{}</a><ul>",
+                anchor(&script.name, ""),
+                escape(script.heading())
+            );
+
+            for item in &script.items {
+                let _ = write!(
+                    nav,
+                    "<li><a href=\"#{}\">{}</a></li>",
+                    anchor(&script.name, &item.name),
+                    escape(&item.name)
+                );
+            }
+
+            nav.push_str("</ul></li>");
+            script.write_html(&mut body, &links);
+        }
+
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<nav><ul>{nav}</ul></nav>\n<main>\n{body}</main>\n</body>\n</html>\n",
+            escape(&self.title())
+        )
+    }
+
+    fn title(&self) -> String {
+        match self.scripts.as_slice() {
+            [script] => script.heading().to_string(),
+            _ => "Reference".to_string(),
+        }
+    }
+
+    /// The anchor of each item by name; a name used in several scripts links to the first.
+    fn links(&self) -> HashMap<&str, String> {
+        let mut links = HashMap::new();
+
+        for script in &self.scripts {
+            for item in &script.items {
+                links
+                    .entry(item.name.as_str())
+                    .or_insert_with(|| anchor(&script.name, &item.name));
+            }
+        }
+
+        links
+    }
+}
+
+impl ScriptDocs {
+    pub fn from_file(path: &Path, config: &Config) -> Result<Self, Diagnostic> {
+        let source = SourceFile::from_file(path.display().to_string())?;
+        let mut parsing_ctx = ParsingCtx::new(config);
+
+        parsing_ctx.add_file(&source);
+
+        let root = parse_str(&mut parsing_ctx, config)?;
+        let name = path
+            .file_stem()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+
+        Ok(Self::new(name, &root.r#mod))
+    }
+
+    pub fn new(name: impl Into<String>, r#mod: &Mod) -> Self {
+        let mut script = Self {
+            name: name.into(),
+            version: r#mod.version(),
+            ..Self::default()
+        };
+
+        for documented in r#mod.documented() {
+            let docs = documented.docs();
+            let description = docs.and_then(|docs| docs.description.clone());
+            let field = |name: &str| docs.and_then(|docs| docs.field(name)).map(String::from);
+            let item = match documented {
+                Documented::Library(declaration) => {
+                    script.title =
+                        declaration.parameters().find_map(|(parameter, argument)| {
+                            match (parameter, argument.value()) {
+                                (Some("title"), Expression::String(title)) => {
+                                    Some(title.value().to_string())
+                                }
+                                _ => None,
+                            }
+                        });
+                    script.description = description;
+                    continue;
+                }
+                Documented::Function(function) => function_docs(ItemKind::Function, function),
+                Documented::Method(method) => function_docs(ItemKind::Method, method),
+                Documented::Type(definition) => ItemDocs {
+                    kind: ItemKind::Type,
+                    name: definition.name().to_string(),
+                    signature: format!("type {}", definition.name()),
+                    description,
+                    params: definition
+                        .fields()
+                        .iter()
+                        .map(|definition| ParamDocs {
+                            name: definition.name().to_string(),
+                            ty: Some(definition.data_type().clone()),
+                            default: definition.default().map(source),
+                            description: field(definition.name()),
+                        })
+                        .collect(),
+                    returns: None,
+                    examples: vec![],
+                },
+                Documented::Enum(definition) => ItemDocs {
+                    kind: ItemKind::Enum,
+                    name: definition.name().to_string(),
+                    signature: format!("enum {}", definition.name()),
+                    description,
+                    params: definition
+                        .members()
+                        .iter()
+                        .map(|member| ParamDocs {
+                            name: member.name().to_string(),
+                            ty: None,
+                            default: Some(member.title().to_string())
+                                .filter(|title| title != member.name()),
+                            description: field(member.name()),
+                        })
+                        .collect(),
+                    returns: None,
+                    examples: vec![],
+                },
+            };
+
+            script.items.push(ItemDocs {
+                examples: docs.map_or_else(Vec::new, |docs| docs.examples.clone()),
+                ..item
+            });
+        }
+
+        script
+    }
+
+    fn heading(&self) -> &str {
+        self.title.as_deref().unwrap_or(&self.name)
+    }
+
+    /// The items of the script by kind, in the order of [`ItemKind`].
+    fn sections(&self) -> Vec<(ItemKind, Vec<&ItemDocs>)> {
+        [
+            ItemKind::Function,
+            ItemKind::Method,
+            ItemKind::Type,
+            ItemKind::Enum,
+        ]
+        .into_iter()
+        .map(|kind| {
+            let items = self.items.iter().filter(|item| item.kind == kind).collect();
+
+            (kind, items)
+        })
+        .filter(|(_, items): &(_, Vec<_>)| !items.is_empty())
+        .collect()
+    }
+
+    fn write_markdown(&self, markdown: &mut String, links: &HashMap<&str, String>) {
+        let _ = writeln!(
+            markdown,
+            "# <a id=\"{}\"></a>{}\n",
+            anchor(&self.name, ""),
+            self.heading()
+        );
+
+        if let Some(version) = self.version {
+            let _ = writeln!(markdown, "Version {version}\n");
+        }
+
+        if let Some(description) = &self.description {
+            let _ = writeln!(markdown, "{}\n", link_markdown(description, links));
+        }
+
+        for (_, items) in self.sections() {
+            for item in items {
+                let _ = writeln!(
+                    markdown,
+                    "- [{}](#{})",
+                    item.name,
+                    anchor(&self.name, &item.name)
+                );
+            }
+        }
+
+        for (kind, items) in self.sections() {
+            let _ = writeln!(markdown, "\n## {}", kind.heading());
+
+            for item in items {
+                let _ = writeln!(
+                    markdown,
+                    "\n### <a id=\"{}\"></a>{}\n\n```pine\n{}\n```\n",
+                    anchor(&self.name, &item.name),
+                    item.name,
+                    item.signature
+                );
+
+                if let Some(description) = &item.description {
+                    let _ = writeln!(markdown, "{}\n", link_markdown(description, links));
+                }
+
+                if !item.params.is_empty() {
+                    let _ = writeln!(
+                        markdown,
+                        "| {} | Type | Default | Description |\n| --- | --- | --- | --- |",
+                        column(item.kind)
+                    );
+
+                    for param in &item.params {
+                        let ty =
+                            param.ty.as_ref().map_or_else(String::new, |ty| {
+                                match linked_type(ty).and_then(|name| links.get(name)) {
+                                    Some(anchor) => format!("[`{ty}`](#{anchor})"),
+                                    None => format!("`{ty}`"),
+                                }
+                            });
+                        let default = param
+                            .default
+                            .as_ref()
+                            .map_or_else(String::new, |default| format!("`{default}`"));
+                        let description = param.description.as_deref().unwrap_or_default();
+
+                        let _ = writeln!(
+                            markdown,
+                            "| `{}` | {} | {} | {} |",
+                            param.name,
+                            ty,
+                            default.replace('|', "\\|"),
+                            link_markdown(description, links).replace('|', "\\|")
+                        );
+                    }
+
+                    markdown.push('\n');
+                }
+
+                if let Some(returns) = &item.returns {
+                    let _ = writeln!(markdown, "**Returns:** {}\n", link_markdown(returns, links));
+                }
+
+                if !item.examples.is_empty() {
+                    let _ = writeln!(
+                        markdown,
+                        "**Example:**\n\n```pine\n{}\n```\n",
+                        item.examples.join("\n")
+                    );
+                }
+            }
+        }
+
+        markdown.push('\n');
+    }
+
+    fn write_html(&self, html: &mut String, links: &HashMap<&str, String>) {
+        let _ = writeln!(
+            html,
+            "<section id=\"{}\">\n<h1>{}</h1>",
+            anchor(&self.name, ""),
+            escape(self.heading())
+        );
+
+        if let Some(version) = self.version {
+            let _ = writeln!(html, "<p class=\"version\">Version {version}</p>");
+        }
+
+        if let Some(description) = &self.description {
+            let _ = writeln!(html, "<p>{}</p>", link_html(description, links));
+        }
+
+        for (kind, items) in self.sections() {
+            let _ = writeln!(html, "<h2>{}</h2>", kind.heading());
+
+            for item in items {
+                let _ = writeln!(
+                    html,
+                    "<article id=\"{}\">\n<h3>{}</h3>\n<pre><code>{}</code></pre>",
+                    anchor(&self.name, &item.name),
+                    escape(&item.name),
+                    escape(&item.signature)
+                );
+
+                if let Some(description) = &item.description {
+                    let _ = writeln!(html, "<p>{}</p>", link_html(description, links));
+                }
+
+                if !item.params.is_empty() {
+                    let _ = writeln!(
+                        html,
+                        "<table>\n<tr><th>{}</th><th>Type</th><th>Default</th><th>Description</th></tr>",
+                        column(item.kind)
+                    );
+
+                    for param in &item.params {
+                        let ty = param.ty.as_ref().map_or_else(String::new, |ty| {
+                            let code = format!("<code>{}</code>", escape(&ty.to_string()));
+
+                            match linked_type(ty).and_then(|name| links.get(name)) {
+                                Some(anchor) => format!("<a href=\"#{anchor}\">{code}</a>"),
+                                None => code,
+                            }
+                        });
+                        let default = param.default.as_ref().map_or_else(String::new, |default| {
+                            format!("<code>{}</code>", escape(default))
+                        });
+                        let description = param.description.as_deref().unwrap_or_default();
+
+                        let _ = writeln!(
+                            html,
+                            "<tr><td><code>{}</code></td><td>{ty}</td><td>{default}</td><td>{}</td></tr>",
+                            escape(&param.name),
+                            link_html(description, links)
+                        );
+                    }
+
+                    html.push_str("</table>\n");
+                }
+
+                if let Some(returns) = &item.returns {
+                    let _ = writeln!(
+                        html,
+                        "<p><strong>Returns:</strong> {}</p>",
+                        link_html(returns, links)
+                    );
+                }
+
+                if !item.examples.is_empty() {
+                    let _ = writeln!(
+                        html,
+                        "<p><strong>Example:</strong></p>\n<pre><code>{}</code></pre>",
+                        escape(&item.examples.join("\n"))
+                    );
+                }
+
+                html.push_str("</article>\n");
+            }
+        }
+
+        html.push_str("</section>\n");
+    }
+}
+
+const STYLE: &str = "body{display:flex;font-family:sans-serif;margin:0}nav{min-width:14em;padding:1em;border-right:1px solid #ddd}nav ul{list-style:none;padding-left:1em}main{padding:1em 2em;max-width:60em}pre{background:#f5f5f5;padding:.5em}table{border-collapse:collapse}td,th{border:1px solid #ddd;padding:.25em .5em;text-align:left}";
+
+fn function_docs(kind: ItemKind, function: &FunctionDecl) -> ItemDocs {
+    let docs = function.docs.as_ref();
+    let parameters = function
+        .arguments
+        .iter()
+        .map(|parameter| {
+            let mut text = String::new();
+
+            if let Some(ty) = &parameter.data_type {
+                let _ = write!(text, "{ty} ");
+            }
+
+            text.push_str(parameter.name());
+
+            if let Some(default) = &parameter.default_value {
+                let _ = write!(text, " = {}", source(default));
+            }
+
+            text
+        })
+        .collect::<Vec<_>>();
+    let prefix = match kind {
+        ItemKind::Method => "method ",
+        _ => "",
+    };
+
+    ItemDocs {
+        kind,
+        name: function.name.name.clone(),
+        signature: format!("{prefix}{}({})", function.name.name, parameters.join(", ")),
+        description: docs.and_then(|docs| docs.description.clone()),
+        params: function
+            .arguments
+            .iter()
+            .map(|parameter| ParamDocs {
+                name: parameter.name().to_string(),
+                ty: parameter.data_type.clone(),
+                default: parameter.default_value.as_ref().map(source),
+                description: docs
+                    .and_then(|docs: &Docs| docs.param(parameter.name()))
+                    .map(String::from),
+            })
+            .collect(),
+        returns: docs.and_then(|docs| docs.returns.clone()),
+        examples: vec![],
+    }
+}
+
+/// The header of the first column of the table of an item.
+fn column(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::Function | ItemKind::Method => "Parameter",
+        ItemKind::Type => "Field",
+        ItemKind::Enum => "Member",
+    }
+}
+
+/// The source of `expression`, read from its line up to the end of the argument or field it's
+/// the value of.
+fn source(expression: &Expression) -> String {
+    let position = expression.position();
+    let text = position
+        .line()
+        .get(position.column_number().saturating_sub(1)..)
+        .unwrap_or_default();
+    let mut depth = 0;
+    let mut quote = None;
+
+    for (index, character) in text.char_indices() {
+        match (quote, character) {
+            (Some(open), _) if open == character => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(character),
+            (None, '(' | '[' | '{') => depth += 1,
+            (None, ')' | ']' | '}' | ',') if depth == 0 => return text[..index].trim().into(),
+            (None, ')' | ']' | '}') => depth -= 1,
+            _ => {}
+        }
+    }
+
+    text.trim().to_string()
+}
+
+/// The user-defined type `ty` is or holds, which its docs link to.
+fn linked_type(ty: &DataType) -> Option<&str> {
+    match ty {
+        DataType::Custom(name) => Some(name),
+        DataType::Array(element) | DataType::Matrix(element) => linked_type(element),
+        DataType::Map(key, value) => linked_type(value).or_else(|| linked_type(key)),
+        _ => None,
+    }
+}
+
+/// `text` with each `` `name` `` of an item turned into a link to it.
+fn link_markdown(text: &str, links: &HashMap<&str, String>) -> String {
+    link(
+        text,
+        |code| match links.get(code) {
+            Some(anchor) => format!("[`{code}`](#{anchor})"),
+            None => format!("`{code}`"),
+        },
+        str::to_string,
+    )
+}
+
+fn link_html(text: &str, links: &HashMap<&str, String>) -> String {
+    link(
+        text,
+        |code| {
+            let code_html = format!("<code>{}</code>", escape(code));
+
+            match links.get(code) {
+                Some(anchor) => format!("<a href=\"#{anchor}\">{code_html}</a>"),
+                None => code_html,
+            }
+        },
+        escape,
+    )
+}
+
+/// Renders the code spans of `text` with `code`, and the rest with `plain`. An unclosed
+/// backtick is kept as text.
+fn link(text: &str, code: impl Fn(&str) -> String, plain: impl Fn(&str) -> String) -> String {
+    let parts = text.split('`').collect::<Vec<_>>();
+    let closed = parts.len() % 2 == 1;
+
+    parts
+        .iter()
+        .enumerate()
+        .map(|(index, part)| match index % 2 {
+            1 if closed || index + 1 < parts.len() => code(part),
+            1 => plain(&format!("`{part}")),
+            _ => plain(part),
+        })
+        .collect()
+}
+
+/// The id of `item` of `script`, or of the script itself when `item` is empty.
+fn anchor(script: &str, item: &str) -> String {
+    let id = match item {
+        "" => script.to_string(),
+        item => format!("{script}-{item}"),
+    };
+
+    id.chars()
+        .map(|character| match character.is_ascii_alphanumeric() {
+            true => character.to_ascii_lowercase(),
+            false => '-',
+        })
+        .collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn collect_scripts(dir: &Path, paths: &mut Vec<std::path::PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        if path.is_dir() {
+            collect_scripts(&path, paths)?;
+        } else if path
+            .extension()
+            .is_some_and(|extension| extension == EXTENSION)
+        {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TempDir;
+
+    const LIBRARY: &str = "//@version=6\n//@description Helpers for `Point` values.\nlibrary(\"Geometry\")\n\n//@type A point on the chart.\n//@field x Bar index.\n//@field y Price.\ntype Point\n    int x\n    float y = 0.0\n\n//@function Moves `p` up by `offset`.\n//@param p The point, see `Point`.\n//@param offset How much to add | subtract.\n//@returns The moved point.\n//@example q = lift(Point.new(1, 2.0))\nlift(Point p, float offset = 1.5) =>\n    Point.new(p.x, p.y + offset)\n\nenum Side\n    up = \"Up\"\n    down\n";
+
+    fn docs() -> Documentation {
+        let config = Config {
+            quiet: true,
+            ..Config::default()
+        };
+        let mut parsing_ctx = ParsingCtx::new(&config);
+
+        parsing_ctx.add_file(&SourceFile::from_str("geometry.ps", LIBRARY).unwrap());
+
+        let root = parse_str(&mut parsing_ctx, &config).unwrap();
+
+        Documentation {
+            scripts: vec![ScriptDocs::new("geometry", &root.r#mod)],
+        }
+    }
+
+    #[test]
+    fn document_items() {
+        let script = docs().scripts.remove(0);
+
+        assert_eq!(script.title.as_deref(), Some("Geometry"));
+        assert_eq!(script.version, Some(6));
+        assert_eq!(
+            script
+                .items
+                .iter()
+                .map(|item| (item.kind, item.signature.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (ItemKind::Type, "type Point"),
+                (ItemKind::Function, "lift(Point p, float offset = 1.5)"),
+                (ItemKind::Enum, "enum Side"),
+            ]
+        );
+
+        let point = &script.items[0];
+
+        assert_eq!(point.params[1].default.as_deref(), Some("0.0"));
+        assert_eq!(point.params[1].description.as_deref(), Some("Price."));
+
+        let side = &script.items[2];
+
+        assert_eq!(side.description, None);
+        assert_eq!(
+            side.params
+                .iter()
+                .map(|member| member.default.as_deref())
+                .collect::<Vec<_>>(),
+            [Some("Up"), None]
+        );
+    }
+
+    #[test]
+    fn render_markdown_with_links() {
+        let markdown = docs().markdown();
+
+        assert!(markdown.starts_with("# <a id=\"geometry\"></a>Geometry\n"));
+        assert!(markdown.contains("Helpers for [`Point`](#geometry-point) values."));
+        assert!(markdown.contains("- [lift](#geometry-lift)"));
+        assert!(markdown.contains("```pine\nlift(Point p, float offset = 1.5)\n```"));
+        assert!(markdown.contains(
+            "| `p` | [`Point`](#geometry-point) |  | The point, see [`Point`](#geometry-point). |"
+        ));
+        assert!(markdown.contains("| `offset` | `float` | `1.5` | How much to add \\| subtract. |"));
+        assert!(markdown.contains("**Returns:** The moved point."));
+        assert!(markdown.contains("```pine\nq = lift(Point.new(1, 2.0))\n```"));
+    }
+
+    #[test]
+    fn render_html_with_links() {
+        let html = docs().html();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Geometry</title>"));
+        assert!(html.contains("<li><a href=\"#geometry-side\">Side</a></li>"));
+        assert!(html.contains("<article id=\"geometry-lift\">"));
+        assert!(html.contains("<td><a href=\"#geometry-point\"><code>Point</code></a></td>"));
+        assert!(html.contains("Moves <code>p</code> up by <code>offset</code>."));
+    }
+
+    #[test]
+    fn document_directory() {
+        let dir = TempDir::new(
+            "docs",
+            &[
+                ("geometry.ps", LIBRARY),
+                (
+                    "nested/main.ps",
+                    "indicator(\"Main\")\n//@function Uses `Point`.\nf(x) => x\n",
+                ),
+                ("notes.txt", "not a script"),
+            ],
+        );
+
+        let config = Config {
+            quiet: true,
+            ..Config::default()
+        };
+        let documentation = Documentation::from_dir(dir.path(), &config).unwrap();
+
+        assert_eq!(
+            documentation
+                .scripts
+                .iter()
+                .map(|script| script.heading())
+                .collect::<Vec<_>>(),
+            ["Geometry", "Main"]
+        );
+        assert!(documentation
+            .markdown()
+            .contains("Uses [`Point`](#geometry-point)."));
+        assert!(Documentation::from_dir(Path::new("/missing/dir"), &config).is_err());
+    }
+}
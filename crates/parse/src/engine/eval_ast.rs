@@ -1,7 +1,7 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 use log::debug;
-use crate::parser;
+use crate::{parser, resolver};
 use crate::parser::config::Config;
 use crate::parser::{Diagnostic, ParsingCtx, Root};
 use crate::parser::source_file::SourceFile;
@@ -10,25 +10,16 @@ use crate::parser::source_file::SourceFile;
 pub fn compile_file(in_name: String, config: &Config) -> Result<(), Diagnostic> {
     let mut source_file = SourceFile::from_file(in_name)?;
 
-    if config.std {
-        source_file.content = "mod std\nuse std::prelude::(*)\n".to_owned() + &source_file.content;
-    }
-
     source_file.mod_path = PathBuf::from("root");
-    println!("1");
+
     compile_str(&source_file, config)
 }
 
 pub fn compile_str(input: &SourceFile, config: &Config) -> Result<(), Diagnostic> {
     let mut parsing_ctx = ParsingCtx::new(config);
-    //println!("2:parsing_ctx {:?}",parsing_ctx.get_current_file());
     parsing_ctx.add_file(input);
-    println!("2:parsing_ctx {:?}",parsing_ctx.get_current_file());
-
-    println!("3");
 
     let hir = parse_str(&mut parsing_ctx, config)?;
-    println!("4");
 
     println!("{:#?}",hir);
     //generate_ir(hir, config)?;
@@ -42,13 +33,11 @@ pub fn parse_str(parsing_ctx: &mut ParsingCtx, config: &Config) -> Result<Root,
     // Text to Ast
     debug!("    -> Parsing");
 
-    println!("5");
     let mut ast = parser::parse(parsing_ctx)?;
-    println!("6{:#?}",ast);
 
     // Name resolving
     debug!("    -> Resolving");
-    //resolver::resolve(&mut ast, parsing_ctx)?;
+    resolver::resolve(&mut ast, parsing_ctx)?;
     //
     //  // Lowering to HIR
     //  debug!("    -> Lowering to HIR");
@@ -211,6 +211,17 @@ impl From<Input<'_>> for Span {
     }
 }
 
+impl From<&Position> for Span {
+    fn from(position: &Position) -> Self {
+        Span {
+            start: position.offset(),
+            end: position.offset() + 1,
+            line: position.line_number() as u32,
+            column: position.column_number(),
+        }
+    }
+}
+
 
 
 pub fn input<'a>(source: &'a str) -> Input<'a> {
@@ -238,6 +249,7 @@ pub fn position(input: Input) -> Position {
         input_clone.get_column(),
         str::from_utf8(input_clone.get_line_beginning()).unwrap(),
     )
+    .with_offset(input_clone.location_offset())
 }
 
 
@@ -5,8 +5,12 @@ mod operations;
 
 pub mod parsing;
 mod parser;
+mod resolver;
 mod ty;
 mod engine;
+pub mod docs;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test;
 
 pub mod util;
 pub use self::parsing::*;
@@ -24,9 +28,12 @@ use crate::parse_util::{comments, module};
 
 
 const KEYWORDS: &[&str] = &[
-    "as", "else", "export", "for", "foreign", "if", "in", "import", "type",
+    "as", "break", "by", "continue", "else", "enum", "export", "false", "for", "foreign", "if", "in",
+    "import", "method", "switch", "to", "true", "type", "while",
 ];
-const OPERATOR_CHARACTERS: &str = "+-*/=<>&|!?";
+/// The language versions `//@version=N` can select.
+pub const SUPPORTED_VERSIONS: &[u32] = &[5, 6];
+const OPERATOR_CHARACTERS: &str = "+-*/=<>&|!?:";
 const OPERATOR_MODIFIERS: &str = "=";
 
 pub type PineResult<'a, T> = nom::IResult<Input<'a>, T, NomError<'a>>;
@@ -1,10 +1,10 @@
-use ast::{analysis::operator_priority, *};
+use ast::{analysis::operator_priority, datatype::DataType, *};
 use position::Position;
 
 #[derive(Clone, Debug)]
 pub enum SuffixOperator {
-    Call(Vec<Expression>, Position),
-    RecordField(String, Position),
+    Call(Vec<Expression>, Vec<NamedArgument>, Position),
+    RecordField(String, Vec<DataType>, Position),
     Try(Position),
 }
 
@@ -1,34 +1,39 @@
 pub mod config;
 mod diagnostic;
 mod parsing_context;
-mod scopes;
+pub(crate) mod scopes;
 pub(crate) mod source_file;
 mod diagnostics_list;
+mod library;
+mod module_loader;
+mod stdlib;
 
 pub use config::*;
 pub use source_file::*;
 pub use diagnostic::*;
 pub use diagnostics_list::*;
 pub use parsing_context::*;
+pub use module_loader::*;
+pub use stdlib::*;
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::{line_ending, space1};
 use nom::combinator::{eof, map};
-use nom::error::{ErrorKind, FromExternalError, VerboseError};
 use nom::multi::{many0, many1};
-use nom::sequence::{preceded, terminated};
+use nom::sequence::{preceded, terminated, tuple};
 use nom_locate::LocatedSpan;
-use ast::{Identifier, Mod, NodeId, ResolutionMap, TopLevel, TraitSolver};
+use ast::{Identifier, LibraryModulePath, Mod, NodeId, ResolutionMap, TopLevel, TraitSolver};
 use crate::input::{Input, Span};
+use crate::parse_util::position;
 use crate::parser::config::Config;
 use crate::parser::diagnostics_list::Diagnostics;
-use crate::parser::source_file::SourceFile;
-use crate::{parse_for, parse_function, parse_identifier, parse_if, parse_variable, parse_while, PineResult};
+use crate::{expression, parse_annotation, parse_assignment, parse_declaration, parse_enum, parse_export, parse_import, parse_for, parse_function, parse_identifier, parse_if, parse_method, parse_switch, parse_tuple_definition, parse_user_type, parse_variable, parse_while, PineResult};
 use crate::ty::Type;
+use position::Position;
 
 #[derive(Debug, Clone)]
 pub struct ParserCtx {
@@ -43,12 +48,14 @@ pub struct ParserCtx {
     structs: HashMap<String, Type>,
     pub config: Config,
     allow_newline_dot: Vec<()>,
+    modules: ModuleLoader,
 }
 
 impl ParserCtx {
     pub fn new(file_path: PathBuf, config: Config) -> Self {
         Self {
             files: HashMap::new(),
+            modules: ModuleLoader::new(&file_path),
             cur_file_path: file_path,
             identities: BTreeMap::new(),
             operators_list: HashMap::new(),
@@ -70,6 +77,7 @@ impl ParserCtx {
     ) -> Self {
         Self {
             files: HashMap::new(),
+            modules: ModuleLoader::new(&file_path),
             cur_file_path: file_path,
             identities: BTreeMap::new(),
             operators_list: operators,
@@ -83,40 +91,33 @@ impl ParserCtx {
         }
     }
 
-    pub fn new_from(&self, name: &str, config: Config) -> Self {
+    /// The context of module `name`, declared in the current file and found in `file_path`.
+    pub fn new_module(&self, name: &str, file_path: PathBuf) -> Self {
         Self {
             files: HashMap::new(),
-            cur_file_path: self
-                .cur_file_path
-                .parent()
-                .unwrap()
-                .join(name.to_owned() + ".rk"),
+            modules: self.modules.enter(name, &file_path),
+            cur_file_path: file_path,
             identities: BTreeMap::new(),
             operators_list: HashMap::new(),
             block_indent: 0,
             first_indent: None,
             next_node_id: self.next_node_id,
             structs: HashMap::new(),
-            diagnostics: Diagnostics::default(), // FIXME
-            config,
+            diagnostics: Diagnostics::default(),
+            config: self.config.clone(),
             allow_newline_dot: vec![],
         }
     }
 
-    pub fn new_std(&self, config: Config) -> Self {
-        Self {
-            files: HashMap::new(),
-            cur_file_path: PathBuf::from("/std/src/lib.rk"),
-            identities: BTreeMap::new(),
-            operators_list: HashMap::new(),
-            block_indent: 0,
-            first_indent: None,
-            next_node_id: self.next_node_id,
-            structs: HashMap::new(),
-            diagnostics: Diagnostics::default(),
-            config,
-            allow_newline_dot: vec![],
-        }
+    /// Takes in what parsing `r#mod`, from `file_path`, gathered in `module`.
+    fn merge_module(&mut self, file_path: &Path, r#mod: &Mod, module: ParserCtx) {
+        // TODO: handle duplicate operators
+        self.operators_list.extend(module.operators_list);
+        self.diagnostics.append(module.diagnostics);
+        self.next_node_id = module.next_node_id;
+        self.identities.extend(module.identities);
+        self.files.extend(module.files);
+        self.modules.insert(file_path, r#mod.clone(), module.modules);
     }
 
     pub fn new_identity(&mut self, span: Span) -> NodeId {
@@ -167,6 +168,27 @@ pub struct Root {
     pub operators_list: HashMap<String, u8>,
     pub unused: Vec<NodeId>,
     pub spans: HashMap<NodeId, Span>,
+    /// Modules of the libraries the script imports, directly or through other libraries.
+    pub libraries: BTreeMap<LibraryModulePath, Mod>,
+    /// How each `receiver.name(...)` call of the script and its libraries is dispatched, when
+    /// the type of the receiver is known statically.
+    pub method_calls: HashMap<CallSite, MethodCall>,
+}
+
+/// File and byte offset of a call.
+pub type CallSite = (String, usize);
+
+pub fn call_site(position: &Position) -> CallSite {
+    (position.path().to_string(), position.offset())
+}
+
+/// Target of `receiver.name(...)`, decided from the type of the receiver.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MethodCall {
+    /// The `method` declared for the type under that name.
+    Method,
+    /// The function of the built-in namespace for the type, such as `array.push`.
+    Namespace(&'static str),
 }
 
 pub struct AstPrintContext {
@@ -210,6 +232,8 @@ impl Root {
             unused: vec![],
             spans: HashMap::new(),
             trait_solver: TraitSolver::new(),
+            libraries: BTreeMap::new(),
+            method_calls: HashMap::new(),
         }
     }
 
@@ -229,7 +253,12 @@ pub fn parse_root(input: Input) -> PineResult<Root> {
 pub fn parse_mod(input: Input) -> PineResult< Mod> {
     map(
         terminated(many1(terminated(parse_top_level, many0(line_ending))), eof),
-        Mod::new,
+        |top_levels| {
+            let mut r#mod = Mod::new(top_levels);
+
+            r#mod.attach_docs();
+            r#mod
+        },
     )(input)
 }
 
@@ -240,121 +269,191 @@ pub fn parse_top_level(input: Input) -> PineResult< TopLevel> {
         //     map(parse_prototype, TopLevel::new_extern),
         // ),
         // parse_infix,
+        map(parse_annotation, TopLevel::new_annotation),
+        map(parse_import, TopLevel::new_import),
+        parse_export,
+        map(parse_user_type, TopLevel::new_type),
+        map(parse_enum, TopLevel::new_enum),
+        map(parse_declaration, TopLevel::new_declaration),
+        map(parse_method, TopLevel::new_method),
         map(parse_variable, TopLevel::new_var),
+        map(parse_assignment, TopLevel::new_assignment),
+        map(parse_tuple_definition, TopLevel::new_tuple_definition),
         map(parse_function, TopLevel::new_function),
         map(parse_if, TopLevel::new_if),
         map(parse_for, TopLevel::new_for),
         map(parse_while, TopLevel::new_while),
+        map(parse_switch, TopLevel::new_switch),
 
-        map(parse_mod_decl, |(name, mod_)| TopLevel::new_mod(name, mod_)),
+        map(parse_mod_decl, |(name, mod_, position)| {
+            TopLevel::new_mod(name, mod_, position)
+        }),
+        map(expression, TopLevel::new_expression),
     ))(input)
 }
 
-pub fn parse_mod_decl(input: Input) ->  PineResult< (Identifier, Mod)> {
-    let config = input.extra.config.clone();
+/**
+mod <identifier>
+ */
+pub fn parse_mod_decl(input: Input) -> PineResult<(Identifier, Mod, Position)> {
+    let (mut input, (position, mod_name)) = tuple((
+        position,
+        preceded(terminated(tag("mod"), space1), parse_identifier),
+    ))(input)?;
+
+    let position = position();
+    let span = Span::from(&position);
+    let empty = |input, mod_name| Ok((input, (mod_name, Mod::new(vec![]), position.clone())));
+
+    let file_path = match input.extra.modules.find(&mod_name.name, &input.extra.config) {
+        Ok(file_path) => file_path,
+        Err(searched) => {
+            input.extra.diagnostics.push_error(Diagnostic::new_module_not_found(
+                span,
+                mod_name.name.clone(),
+                searched.iter().map(|file| file.display().to_string()).collect(),
+            ));
+
+            return empty(input, mod_name);
+        }
+    };
 
-    let (mut input, mod_name) = preceded(terminated(tag("mod"), space1), parse_identifier)(input)?;
+    if let Some(chain) = input.extra.modules.cycle(&mod_name.name, &file_path) {
+        input
+            .extra
+            .diagnostics
+            .push_error(Diagnostic::new_import_cycle(span, chain));
 
-    let mut new_ctx = if mod_name.name == "std" {
-        input.extra.new_std(config.clone())
-    } else {
-        input.extra.new_from(&mod_name.name, config.clone())
-    };
+        return empty(input, mod_name);
+    }
+
+    if let Some(mod_) = input.extra.modules.get(&file_path).cloned() {
+        return Ok((input, (mod_name, mod_, position)));
+    }
 
-    let file_path = new_ctx.current_file_path().to_str().unwrap().to_string();
+    let file = match SourceFile::from_file(file_path.display().to_string()) {
+        Ok(file) => file,
+        Err(_) => {
+            input.extra.diagnostics.push_error(Diagnostic::new_file_not_found(
+                span,
+                file_path.display().to_string(),
+            ));
 
-    let mut file = SourceFile::from_file(file_path.clone()).unwrap(); // FIXME: ERRORS ARE swallowed HERE
+            return empty(input, mod_name);
+        }
+    };
 
-    // if config.std {
-    //     if STDLIB_FILES.get(&file_path).is_none() {
-    //         file.content = "use root::std::prelude::(*)\n".to_owned() + &file.content;
-    //     }
-    // }
+    let mut new_ctx = input.extra.new_module(&mod_name.name, file_path.clone());
 
-    new_ctx
-        .files
-        .insert(new_ctx.current_file_path().clone(), file.clone());
+    new_ctx.files.insert(file_path.clone(), file.clone());
 
-    input
-        .extra
-        .files
-        .insert(new_ctx.current_file_path().clone(), file.clone());
+    input.extra.files.insert(file_path.clone(), file.clone());
 
-    let new_parser = Input::new_extra(&file.content, new_ctx.clone());
+    let new_parser = Input::new_extra(&file.content, new_ctx);
 
     use nom::Finish;
 
-    let parsed_mod_opt = parse_mod(new_parser).map_err(|e| e.to_owned()).finish();
-
-    let (input2, mod_) = match parsed_mod_opt {
+    let (input2, mod_) = match parse_mod(new_parser).finish() {
         Ok((input2, mod_)) => (input2, mod_),
         Err(err) => {
-            // input
-            //     .extra
-            //     .diagnostics
-            //     .append(Diagnostics::from(err.clone()));
-            //
-            // input
-            //     .extra
-            //     .files
-            //     .extend(err.errors.get(0).unwrap().0.extra.files.clone());
-            //
-            return Err(nom::Err::Failure(VerboseError::from_external_error(
-                input,
-                ErrorKind::Fail,
-                err.to_owned(),
-            )));
+            let line = err
+                .errors
+                .first()
+                .map(|(input, _)| input.location_line())
+                .unwrap_or_default();
+
+            input.extra.diagnostics.push_error(Diagnostic::new_syntax_error(
+                span,
+                format!("in module `{}` ({}:{})", mod_name.name, file_path.display(), line),
+            ));
+
+            return empty(input, mod_name);
         }
     };
 
-    // hydrate `input` with the new parser's operators
-    // TODO: handle duplicate operators
-    input
-        .extra
-        .operators_list
-        .extend(input2.extra.operators_list);
-
-    input.extra.diagnostics.append(input2.extra.diagnostics);
+    input.extra.merge_module(&file_path, &mod_, input2.extra);
 
-    // extend identities
-    input.extra.next_node_id = input2.extra.next_node_id;
-    input.extra.identities.extend(input2.extra.identities);
-    input.extra.files.extend(input2.extra.files);
+    Ok((input, (mod_name, mod_, position)))
+}
 
-    Ok((input, (mod_name, mod_)))
+/// The names `top_levels` declare: functions, methods, variables, types and enums.
+fn declared_names(top_levels: &[TopLevel]) -> HashSet<String> {
+    top_levels
+        .iter()
+        .flat_map(|top_level| match top_level {
+            TopLevel::Function(function) | TopLevel::Method(function) => {
+                vec![function.name.name.clone()]
+            }
+            TopLevel::Variable(variable) => vec![variable.identifier().name.clone()],
+            TopLevel::TupleDefinition(tuple) => {
+                tuple.names().iter().map(|name| name.name.clone()).collect()
+            }
+            TopLevel::Type(definition) => vec![definition.name().to_string()],
+            TopLevel::Enum(definition) => vec![definition.name().to_string()],
+            _ => vec![],
+        })
+        .collect()
 }
 
+/// Parses the standard library's prelude, continuing the node ids of `ctx`.
+fn parse_prelude(ctx: &mut ParserCtx) -> Result<Mod, Diagnostic> {
+    use nom::Finish;
+
+    let file_path = PathBuf::from(PRELUDE);
+    let file = SourceFile::from_file(PRELUDE.to_string())?;
+    let mut prelude_ctx = ctx.new_module("prelude", file_path.clone());
+
+    prelude_ctx.files.insert(file_path.clone(), file.clone());
+
+    let (prelude_ctx, prelude) = parse_mod(Input::new_extra(&file.content, prelude_ctx))
+        .finish()
+        .map_err(|_| {
+            Diagnostic::new_syntax_error(Span::empty(), format!("in the prelude ({})", PRELUDE))
+        })?;
+
+    ctx.merge_module(&file_path, &prelude, prelude_ctx.extra);
 
+    Ok(prelude)
+}
 
 pub fn parse(parsing_ctx: &mut ParsingCtx) -> Result<Root, Diagnostic> {
     use nom::Finish;
 
     let content = &parsing_ctx.get_current_file().content;
-    println!("content===={:?}",content);
+    let file_path = parsing_ctx.get_current_file().file_path;
+
+    parsing_ctx.modules = parsing_ctx.modules.load(&file_path);
+
     let mut parser = LocatedSpan::new_extra(
         content.as_str(),
-        ParserCtx::new(
-            parsing_ctx.get_current_file().file_path.clone(),
-            parsing_ctx.config.clone(),
-        ),
+        ParserCtx::new(file_path.clone(), parsing_ctx.config.clone()),
     );
 
+    parser.extra.modules = parsing_ctx.modules.clone();
     parser.extra.files.insert(
-        parsing_ctx.get_current_file().file_path.clone(),
+        file_path,
         parsing_ctx.get_current_file().clone(),
     );
 
+    let prelude = if parsing_ctx.config.std {
+        Some(parse_prelude(&mut parser.extra)?)
+    } else {
+        None
+    };
+
     let ast = parse_root(parser).finish();
 
-    let ast = match ast {
+    let mut ast = match ast {
         Ok((ctx, mut ast)) => {
             //default_impl_populator::populate_default_impl(&mut ast);
 
             parsing_ctx.identities = ctx.extra.identities();
             parsing_ctx.files.extend(ctx.extra.files());
+            parsing_ctx.diagnostics.append(ctx.extra.diagnostics());
 
             ast.operators_list = ctx.extra.operators_list();
             ast.spans = ctx.extra.identities().into_iter().collect();
+            parsing_ctx.modules = ctx.extra.modules;
 
             // Debug ast
             if parsing_ctx.config.show_ast {
@@ -364,9 +463,11 @@ pub fn parse(parsing_ctx: &mut ParsingCtx) -> Result<Root, Diagnostic> {
             Ok(ast)
         }
         Err(e) => {
-            parsing_ctx
-                .files
-                .extend(e.errors.get(0).unwrap().clone().0.extra.files());
+            let failed_at = e.errors.first().map(|(input, _)| input.clone());
+
+            if let Some(input) = &failed_at {
+                parsing_ctx.files.extend(input.extra.files());
+            }
             //
             // let diagnostics = Diagnostics::from(e);
             //
@@ -376,10 +477,34 @@ pub fn parse(parsing_ctx: &mut ParsingCtx) -> Result<Root, Diagnostic> {
 
             parsing_ctx.return_if_error()?;
 
-            Err(parsing_ctx.diagnostics.list.get(0).unwrap().clone())
+            // Most parsers fail without reporting why; point at where parsing stopped.
+            let diagnostic = match failed_at {
+                Some(input) => {
+                    Diagnostic::new_syntax_error(Span::from(input), "can't parse from here".into())
+                }
+                None => Diagnostic::new_syntax_error(Span::empty(), "unexpected end".into()),
+            };
+
+            parsing_ctx.diagnostics.push_error(diagnostic.clone());
+
+            Err(diagnostic)
         }
     }?;
 
+    if let Some(prelude) = prelude {
+        // The prelude is an outer scope: what the script declares shadows it.
+        let declared = declared_names(&ast.r#mod.top_levels);
+        let prelude = prelude.top_levels.into_iter().filter(|top_level| {
+            declared_names(std::slice::from_ref(top_level)).is_disjoint(&declared)
+        });
+
+        ast.r#mod
+            .top_levels
+            .splice(0..0, prelude.collect::<Vec<_>>());
+    }
+
+    library::load_libraries(&mut ast, parsing_ctx);
+
     parsing_ctx.return_if_error()?;
 
     Ok(ast)
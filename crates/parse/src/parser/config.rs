@@ -1,5 +1,7 @@
 use std::{collections::HashMap, path::PathBuf};
 
+use ast::datatype::DataType;
+
 #[derive(Debug, Clone)]
 pub enum PackageType {
     Lib,
@@ -32,5 +34,23 @@ pub struct Config {
     pub quiet: bool,
     pub build_folder: PathBuf,
     pub no_optimize: bool,
+    /// Declares the standard library's prelude ahead of the script.
     pub std: bool,
+    /// Directory `import owner/name/version` loads libraries from, laid out as
+    /// `<owner>/<name>/<version>.ps`.
+    pub library_dir: PathBuf,
+    /// Directory of the standard library `mod std` loads, from its `lib.ps`.
+    pub std_path: Option<PathBuf>,
+    /// Result types the runtime declares for each built-in `namespace.function`, the first
+    /// overload the arguments match applying, for typing calls such as `str.length(s)` and
+    /// checking destructuring such as `[a, b] = ta.bb(...)`.
+    pub overloads: HashMap<String, Vec<Overload>>,
+}
+
+/// Result type of a built-in function called with arguments of the types `parameters`, the last
+/// of which takes any further arguments; empty `parameters` match every call.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overload {
+    pub parameters: Vec<DataType>,
+    pub returns: DataType,
 }
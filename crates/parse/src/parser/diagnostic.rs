@@ -42,12 +42,92 @@ impl Diagnostic {
         Self::new(span, DiagnosticKind::UnknownIdentifier)
     }
 
+    pub fn new_undeclared_assignment(span: Span, name: String) -> Self {
+        Self::new(span, DiagnosticKind::UndeclaredAssignment(name))
+    }
+
+    pub fn new_tuple_arity_mismatch(span: Span, expected: usize, got: usize) -> Self {
+        Self::new(span, DiagnosticKind::TupleArityMismatch(expected, got))
+    }
+
+    pub fn new_unknown_field(span: Span, ty: String, field: String) -> Self {
+        Self::new(span, DiagnosticKind::UnknownField(ty, field))
+    }
+
+    pub fn new_unknown_method(span: Span, ty: String, method: String) -> Self {
+        Self::new(span, DiagnosticKind::UnknownMethod(ty, method))
+    }
+
+    pub fn new_constructor_arity(span: Span, ty: String, fields: usize, got: usize) -> Self {
+        Self::new(span, DiagnosticKind::ConstructorArity(ty, fields, got))
+    }
+
+    pub fn new_type_mismatch(span: Span, expected: String, found: String) -> Self {
+        Self::new(span, DiagnosticKind::TypeMismatch(expected, found))
+    }
+
+    pub fn new_non_exhaustive_switch(span: Span, ty: String, missing: Vec<String>) -> Self {
+        Self::new(span, DiagnosticKind::NonExhaustiveSwitch(ty, missing))
+    }
+
+    pub fn new_duplicate_declaration(span: Span, kind: String) -> Self {
+        Self::new(span, DiagnosticKind::DuplicateDeclaration(kind))
+    }
+
+    pub fn new_unknown_declaration_argument(span: Span, kind: String, name: String) -> Self {
+        Self::new(span, DiagnosticKind::UnknownDeclarationArgument(kind, name))
+    }
+
+    pub fn new_duplicate_declaration_argument(span: Span, kind: String, name: String) -> Self {
+        Self::new(span, DiagnosticKind::DuplicateDeclarationArgument(kind, name))
+    }
+
+    pub fn new_declaration_arity(span: Span, kind: String, parameters: usize, got: usize) -> Self {
+        Self::new(span, DiagnosticKind::DeclarationArity(kind, parameters, got))
+    }
+
+    pub fn new_unsupported_version(span: Span, version: String) -> Self {
+        Self::new(span, DiagnosticKind::UnsupportedVersion(version))
+    }
+
+    pub fn new_library_not_found(span: Span, library: String, dir: String) -> Self {
+        Self::new(span, DiagnosticKind::LibraryNotFound(library, dir))
+    }
+
+    pub fn new_library_version_not_found(
+        span: Span,
+        library: String,
+        version: u32,
+        available: Vec<u32>,
+    ) -> Self {
+        Self::new(
+            span,
+            DiagnosticKind::LibraryVersionNotFound(library, version, available),
+        )
+    }
+
+    pub fn new_not_a_library(span: Span, library: String) -> Self {
+        Self::new(span, DiagnosticKind::NotALibrary(library))
+    }
+
+    pub fn new_missing_export(span: Span, library: String, name: String) -> Self {
+        Self::new(span, DiagnosticKind::MissingExport(library, name))
+    }
+
+    pub fn new_export_outside_library(span: Span, name: String) -> Self {
+        Self::new(span, DiagnosticKind::ExportOutsideLibrary(name))
+    }
+
+    pub fn new_import_cycle(span: Span, chain: Vec<String>) -> Self {
+        Self::new(span, DiagnosticKind::ImportCycle(chain))
+    }
+
     pub fn new_unused_function(span: Span) -> Self {
         Self::new(span, DiagnosticKind::UnusedFunction)
     }
 
-    pub fn new_module_not_found(span: Span, path: String) -> Self {
-        Self::new(span, DiagnosticKind::ModuleNotFound(path))
+    pub fn new_module_not_found(span: Span, path: String, searched: Vec<String>) -> Self {
+        Self::new(span, DiagnosticKind::ModuleNotFound(path, searched))
     }
 
     pub fn new_unresolved_type(span: Span, t: Type) -> Self {
@@ -90,13 +170,31 @@ impl Diagnostic {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum DiagnosticKind {
     FileNotFound(String),
     UnexpectedToken,
     SyntaxError(String),
     UnknownIdentifier,
-    ModuleNotFound(String),
+    UndeclaredAssignment(String),
+    TupleArityMismatch(usize, usize), // tuple size -> names
+    UnknownField(String, String),      // type, field
+    UnknownMethod(String, String),     // type, method
+    ConstructorArity(String, usize, usize), // type, fields -> arguments
+    NonExhaustiveSwitch(String, Vec<String>), // enum, missing members
+    TypeMismatch(String, String),      // expected, found
+    DuplicateDeclaration(String),      // declaration
+    UnknownDeclarationArgument(String, String), // declaration, argument
+    DuplicateDeclarationArgument(String, String), // declaration, argument
+    DeclarationArity(String, usize, usize), // declaration, parameters -> arguments
+    UnsupportedVersion(String),
+    LibraryNotFound(String, String),   // library, directory
+    LibraryVersionNotFound(String, u32, Vec<u32>), // library, version -> available versions
+    NotALibrary(String),               // library
+    MissingExport(String, String),     // library, name
+    ExportOutsideLibrary(String),      // name
+    ImportCycle(Vec<String>),          // libraries or modules, from the first one back to itself
+    ModuleNotFound(String, Vec<String>), // module, files searched
     NotAFunction,
     UnusedParameter,
 
@@ -112,6 +210,7 @@ pub enum DiagnosticKind {
     NoMain,
     NoError, //TODO: remove that
 }
+use std::ops::Range;
 use std::path::PathBuf;
 use serde::{Deserialize, Serialize};
 use crate::parser::DiagnosticKind::NoError;
@@ -152,111 +251,135 @@ impl Spaned {
 // }
 
 impl DiagnosticKind {
+    /// The label marking `span`, carrying the diagnostic's message.
+    fn label<'a>(
+        &self,
+        filename: &'a str,
+        span: &Spaned,
+        color: Color,
+    ) -> Label<(&'a str, Range<usize>)> {
+        Label::new((filename, span.start..span.end))
+            .with_message(self.to_string())
+            .with_color(color)
+    }
+
     pub fn report_builder<'a>(
         &self,
         file: &SourceFile,
         span: &'a Span,
         diag_type: &DiagnosticType,
     ) {
-        let filename = file.file_path.to_str().unwrap();
+        let filename = file.file_path.to_string_lossy();
 
         let (error_ty, color) = match diag_type {
             DiagnosticType::Error => (ReportKind::Error, Color::Red),
             DiagnosticType::Warning => (ReportKind::Warning, Color::Yellow),
         };
-        let builder = Report::build(error_ty, filename, span.start);
+        let builder = Report::build(error_ty, &*filename, span.start);
 
         let mut span1 = span.clone();
         if span1.start == span1.end {
             span1.end += 1;
         }
 
-        let mut span=Spaned::new(PathBuf::from(filename),span1.start,span1.end);
+        let span=Spaned::new(PathBuf::from(&*filename),span1.start,span1.end);
 
 
 
         match self {
             DiagnosticKind::FileNotFound(path) => builder
                 .with_message(format!("File not found: {}", path))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
+                .with_label(self.label(&filename, &span, color)),
             DiagnosticKind::UnexpectedToken => builder
                 .with_message("Unexpected token".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
+                .with_label(self.label(&filename, &span, color)),
             DiagnosticKind::SyntaxError(msg) => builder
                 .with_message(format!("Syntax error: {}", msg))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
+                .with_label(self.label(&filename, &span, color)),
             DiagnosticKind::UnknownIdentifier => builder
                 .with_message("Unknown identifier".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::ModuleNotFound(path) => builder
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::UndeclaredAssignment(name) => builder
+                .with_message(format!("Undeclared variable: {}", name))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::TupleArityMismatch(_expected, _got) => builder
+                .with_message("Tuple arity mismatch".to_string())
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::UnknownField(ty, field) => builder
+                .with_message(format!("Unknown field: {}.{}", ty, field))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::UnknownMethod(ty, method) => builder
+                .with_message(format!("Unknown method: {}.{}", ty, method))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::ConstructorArity(ty, _fields, _got) => builder
+                .with_message(format!("Too many arguments for {}.new", ty))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::NonExhaustiveSwitch(ty, _missing) => builder
+                .with_message(format!("Non-exhaustive switch over {}", ty))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::DuplicateDeclaration(kind) => builder
+                .with_message(format!("Duplicate declaration: {}", kind))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::UnknownDeclarationArgument(kind, name) => builder
+                .with_message(format!("Unknown argument: {}({} = ...)", kind, name))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::DuplicateDeclarationArgument(kind, name) => builder
+                .with_message(format!("Duplicate argument: {}({} = ...)", kind, name))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::DeclarationArity(kind, _parameters, _got) => builder
+                .with_message(format!("Too many arguments for {}", kind))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::UnsupportedVersion(version) => builder
+                .with_message(format!("Unsupported version: {}", version))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::TypeMismatch(_expected, _found) => builder
+                .with_message("Type mismatch".to_string())
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::LibraryNotFound(library, _dir) => builder
+                .with_message(format!("Library not found: {}", library))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::LibraryVersionNotFound(library, version, _available) => builder
+                .with_message(format!("Library version not found: {}/{}", library, version))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::NotALibrary(library) => builder
+                .with_message(format!("Not a library: {}", library))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::MissingExport(library, name) => builder
+                .with_message(format!("Missing export: {}.{}", library, name))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::ExportOutsideLibrary(name) => builder
+                .with_message(format!("Export outside of a library: {}", name))
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::ImportCycle(_chain) => builder
+                .with_message("Import cycle".to_string())
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::ModuleNotFound(path, _searched) => builder
                 .with_message(format!("Module not found: {}", path))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
+                .with_label(self.label(&filename, &span, color)),
             DiagnosticKind::UnusedFunction => builder
                 .with_message("Unused function".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
+                .with_label(self.label(&filename, &span, color)),
             DiagnosticKind::UnusedParameter => builder
                 .with_message("Unused parameter".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
+                .with_label(self.label(&filename, &span, color)),
 
             DiagnosticKind::UnresolvedType(t) => builder
                 .with_message(format!("Unresolved type: {}", t.to_string()))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
+                .with_label(self.label(&filename, &span, color)),
 
 
             DiagnosticKind::TypeConflict(_t1, _t2, _in1, _in2) => {
                 // add spans here
-                builder.with_message("Type conflict").with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                )
+                builder
+                    .with_message("Type conflict")
+                    .with_label(self.label(&filename, &span, color))
             }
             DiagnosticKind::OutOfBounds(got, expected) => builder
                 .with_message(format!("Out of bounds: got {}, expected {}", got, expected))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
+                .with_label(self.label(&filename, &span, color)),
             DiagnosticKind::OrphaneSignature(name) => builder
                 .with_message(format!("Orpheline signature: {}", name))
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
+                .with_label(self.label(&filename, &span, color)),
             // DiagnosticKind::SignatureMismatch(name, _got, _expected) => builder
             //     .with_message(format!("Signature mismatch: {}", name,))
             //     .with_label(
@@ -266,36 +389,22 @@ impl DiagnosticKind {
             //     ),
             DiagnosticKind::NoMain => builder
                 .with_message("No main function".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
-            DiagnosticKind::NoError => builder.with_message("No error".to_string()).with_label(
-                Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                    .with_message(format!("{}", self))
-                    .with_color(color),
-            ),
+                .with_label(self.label(&filename, &span, color)),
+            DiagnosticKind::NoError => builder
+                .with_message("No error".to_string())
+                .with_label(self.label(&filename, &span, color)),
             DiagnosticKind::DuplicatedOperator => builder
                 .with_message("Duplicated operator".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
+                .with_label(self.label(&filename, &span, color)),
             DiagnosticKind::NotAFunction => builder
                 .with_message("Not a function".to_string())
-                .with_label(
-                    Label::new((span.file_path.to_str().unwrap(), span.start..span.end))
-                        .with_message(format!("{}", self))
-                        .with_color(color),
-                ),
+                .with_label(self.label(&filename, &span, color)),
             _ => {
                 todo!()
             }
         }
         .finish()
-        .print((filename, Source::from(file.content.clone())))
+        .print((&*filename, Source::from(file.content.clone())))
         .unwrap();
     }
 }
@@ -306,7 +415,84 @@ impl Display for DiagnosticKind {
             Self::UnexpectedToken => "UnexpectedToken".to_string(),
             Self::SyntaxError(msg) => format!("SyntaxError: {}", msg),
             Self::UnknownIdentifier => "UnknownIdentifier".to_string(),
-            Self::ModuleNotFound(path) => format!("Module not found: {}", path),
+            Self::UndeclaredAssignment(name) => format!(
+                "Cannot reassign `{}` before it is declared; use `=` to declare it",
+                name
+            ),
+            Self::TupleArityMismatch(expected, got) => format!(
+                "Tuple of {} values can't be destructured into {} names",
+                expected, got
+            ),
+            Self::UnknownField(ty, field) => format!("Type {} has no field `{}`", ty, field),
+            Self::UnknownMethod(ty, method) => format!("Type {} has no method `{}`", ty, method),
+            Self::ConstructorArity(ty, fields, got) => format!(
+                "{}.new takes at most {} arguments, but {} were given",
+                ty, fields, got
+            ),
+            Self::TypeMismatch(expected, found) => {
+                format!("Expected a value of type {}, but found {}", expected, found)
+            }
+            Self::DuplicateDeclaration(kind) => format!(
+                "A script has a single declaration statement, but {}() comes after another one",
+                kind
+            ),
+            Self::UnknownDeclarationArgument(kind, name) => {
+                format!("{}() has no parameter `{}`", kind, name)
+            }
+            Self::DuplicateDeclarationArgument(kind, name) => {
+                format!("`{}` is given more than once to {}()", name, kind)
+            }
+            Self::DeclarationArity(kind, parameters, got) => format!(
+                "{}() takes at most {} arguments, but {} were given",
+                kind, parameters, got
+            ),
+            Self::UnsupportedVersion(version) => format!(
+                "Version {} is not supported; use //@version={}",
+                version,
+                crate::SUPPORTED_VERSIONS
+                    .iter()
+                    .map(|version| version.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" or //@version=")
+            ),
+            Self::NonExhaustiveSwitch(ty, missing) => format!(
+                "Switch over {} doesn't handle {}; add the missing arms or a default `=>` arm",
+                ty,
+                missing.join(", ")
+            ),
+            Self::LibraryNotFound(library, dir) => {
+                format!("Library {} is not in the library directory {}", library, dir)
+            }
+            Self::LibraryVersionNotFound(library, version, available) => format!(
+                "Library {} has no version {}; available versions: {}",
+                library,
+                version,
+                available
+                    .iter()
+                    .map(|version| version.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::NotALibrary(library) => format!(
+                "{} can't be imported because it isn't declared with library()",
+                library
+            ),
+            Self::MissingExport(library, name) => {
+                format!("Library {} doesn't export `{}`", library, name)
+            }
+            Self::ExportOutsideLibrary(name) => format!(
+                "`{}` is marked `export`, but only scripts declared with library() can export",
+                name
+            ),
+            Self::ImportCycle(chain) => format!("Import cycle: {}", chain.join(" -> ")),
+            Self::ModuleNotFound(path, searched) if searched.is_empty() => {
+                format!("Module not found: {}", path)
+            }
+            Self::ModuleNotFound(path, searched) => format!(
+                "Module not found: {} (searched {})",
+                path,
+                searched.join(", ")
+            ),
             Self::DuplicatedOperator => "DuplicatedOperator".to_string(),
             Self::TypeConflict(expected, got, _in1, _in2) => {
                 use colored::*;
@@ -0,0 +1,146 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ast::{LibraryModulePath, ModulePath, TopLevel};
+
+use crate::input::Span;
+use crate::parser::config::Config;
+use crate::parser::{parse, Diagnostic, ParsingCtx, Root, SourceFile};
+use crate::resolver;
+
+/// Parses and resolves the libraries `root` imports into `root.libraries`, along with the ones
+/// they import in turn. Libraries are looked up in `Config.library_dir`, and loaded once through
+/// the `ModuleLoader` of `parsing_ctx`, which also reports import cycles.
+pub(crate) fn load_libraries(root: &mut Root, parsing_ctx: &mut ParsingCtx) {
+    let imports = root
+        .r#mod
+        .top_levels
+        .iter()
+        .filter_map(|top_level| match top_level {
+            TopLevel::Import(import) => match import.module_path() {
+                ModulePath::Library(path) => Some((path.clone(), Span::from(import.position()))),
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    for (path, span) in imports {
+        if root.libraries.contains_key(&path) {
+            continue;
+        }
+
+        let file = match library_file(&parsing_ctx.config.library_dir, &path, span) {
+            Ok(file) => file,
+            Err(diagnostic) => {
+                parsing_ctx.diagnostics.push_error(diagnostic);
+
+                continue;
+            }
+        };
+
+        if let Some(chain) = parsing_ctx.modules.cycle(&path.to_string(), &file) {
+            parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_import_cycle(span, chain));
+
+            continue;
+        }
+
+        let library = match parsing_ctx.modules.library(&file) {
+            Some(library) => Some(library.clone()),
+            None => load_library(&path, &file, span, parsing_ctx),
+        };
+
+        if let Some(library) = library {
+            root.libraries.extend(library.libraries);
+            root.method_calls.extend(library.method_calls);
+            root.libraries.insert(path, library.r#mod);
+        }
+    }
+}
+
+fn load_library(
+    path: &LibraryModulePath,
+    file: &Path,
+    span: Span,
+    parsing_ctx: &mut ParsingCtx,
+) -> Option<Root> {
+    let source = match SourceFile::from_file(file.to_string_lossy().to_string()) {
+        Ok(source) => source,
+        Err(diagnostic) => {
+            parsing_ctx.diagnostics.push_error(diagnostic);
+
+            return None;
+        }
+    };
+
+    // The library's diagnostics are reported along with the script's.
+    let config = Config {
+        quiet: true,
+        ..parsing_ctx.config.clone()
+    };
+    let mut library_ctx = ParsingCtx::new(&config);
+
+    library_ctx.modules = parsing_ctx.modules.enter(&path.to_string(), file);
+    library_ctx.add_file(&source);
+
+    let library = parse(&mut library_ctx)
+        .and_then(|mut library| resolver::resolve(&mut library, &mut library_ctx).map(|_| library));
+
+    parsing_ctx.files.extend(library_ctx.files);
+    parsing_ctx.diagnostics.append(library_ctx.diagnostics);
+
+    let library = library.ok()?;
+
+    if !library.r#mod.is_library() {
+        parsing_ctx
+            .diagnostics
+            .push_error(Diagnostic::new_not_a_library(span, path.to_string()));
+
+        return None;
+    }
+
+    parsing_ctx
+        .modules
+        .insert_library(file, library.clone(), library_ctx.modules);
+
+    Some(library)
+}
+
+/// `<dir>/<owner>/<name>/<version>.ps`, or why it can't be imported: the library doesn't exist,
+/// or not in that version.
+fn library_file(dir: &Path, path: &LibraryModulePath, span: Span) -> Result<PathBuf, Diagnostic> {
+    let versions = dir.join(path.owner()).join(path.name());
+    let file = versions.join(format!("{}.ps", path.version()));
+
+    if file.is_file() {
+        return Ok(file);
+    }
+
+    let mut available = fs::read_dir(&versions)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|file| file.extension().is_some_and(|extension| extension == "ps"))
+        .filter_map(|file| file.file_stem()?.to_str()?.parse::<u32>().ok())
+        .collect::<Vec<_>>();
+
+    available.sort_unstable();
+
+    if available.is_empty() {
+        Err(Diagnostic::new_library_not_found(
+            span,
+            path.to_string(),
+            dir.display().to_string(),
+        ))
+    } else {
+        Err(Diagnostic::new_library_version_not_found(
+            span,
+            format!("{}/{}", path.owner(), path.name()),
+            path.version(),
+            available,
+        ))
+    }
+}
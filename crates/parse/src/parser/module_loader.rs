@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use ast::Mod;
+
+use crate::parser::config::Config;
+use crate::parser::{stdlib_file, Root, STD_ROOT};
+
+/// Finds the file `mod name` refers to, keeps the modules and libraries parsed so far and the
+/// ones being parsed, from the entry point down to the current one, to report cycles.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleLoader {
+    modules: HashMap<PathBuf, Mod>,
+    /// Resolved libraries, with the libraries they import.
+    libraries: HashMap<PathBuf, Root>,
+    loading: Vec<(String, PathBuf)>,
+}
+
+impl ModuleLoader {
+    pub fn new(entry_point: &Path) -> Self {
+        let name = entry_point
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        Self {
+            modules: HashMap::new(),
+            libraries: HashMap::new(),
+            loading: vec![(name, key(entry_point))],
+        }
+    }
+
+    /// The loader of `file`: this one when it's loading `file`, such as the one a library is
+    /// parsed with, or else a loader with `file` as its entry point.
+    pub fn load(&self, file: &Path) -> Self {
+        match self.loading.last() {
+            Some((_, loading)) if loading == &key(file) => self.clone(),
+            _ => Self::new(file),
+        }
+    }
+
+    /// The loader of module or library `name`, imported by the file being parsed and found in
+    /// `file`.
+    pub fn enter(&self, name: &str, file: &Path) -> Self {
+        let mut loading = self.loading.clone();
+
+        loading.push((name.to_string(), key(file)));
+
+        Self {
+            modules: self.modules.clone(),
+            libraries: self.libraries.clone(),
+            loading,
+        }
+    }
+
+    /// The files `mod name` may refer to, in search order: `<name>.ps` next to the file being
+    /// parsed, then the path `ProjectConfig.externs` gives for `name`, then for `std` the
+    /// `Config.std_path` or else the embedded standard library. A directory stands for its
+    /// `lib.ps`.
+    pub fn candidates(&self, name: &str, config: &Config) -> Vec<PathBuf> {
+        let mut candidates = vec![];
+
+        if let Some(dir) = self.loading.last().and_then(|(_, file)| file.parent()) {
+            candidates.push(dir.join(format!("{}.ps", name)));
+        }
+
+        if let Some(path) = config.project_config.externs.get(name) {
+            candidates.push(library_entry(&config.project_config.base_path.join(path)));
+        }
+
+        if name == "std" {
+            candidates.push(match &config.std_path {
+                Some(path) => library_entry(path),
+                None => PathBuf::from(STD_ROOT),
+            });
+        }
+
+        candidates
+    }
+
+    /// The first of the candidates that exists, or all of them when none does.
+    pub fn find(&self, name: &str, config: &Config) -> Result<PathBuf, Vec<PathBuf>> {
+        let candidates = self.candidates(name, config);
+
+        candidates
+            .iter()
+            .find(|candidate| candidate.is_file() || stdlib_file(candidate).is_some())
+            .cloned()
+            .ok_or(candidates)
+    }
+
+    /// The modules from the first one parsed from `file` down to `name`, found in `file` again,
+    /// if `file` is being parsed.
+    pub fn cycle(&self, name: &str, file: &Path) -> Option<Vec<String>> {
+        let file = key(file);
+        let start = self
+            .loading
+            .iter()
+            .position(|(_, loading)| loading == &file)?;
+
+        Some(
+            self.loading[start..]
+                .iter()
+                .map(|(name, _)| name.clone())
+                .chain([name.to_string()])
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, file: &Path) -> Option<&Mod> {
+        self.modules.get(&key(file))
+    }
+
+    /// Keeps `r#mod`, parsed from `file` by `loader`, along with the modules `loader` parsed on
+    /// the way.
+    pub fn insert(&mut self, file: &Path, r#mod: Mod, loader: ModuleLoader) {
+        self.modules.extend(loader.modules);
+        self.libraries.extend(loader.libraries);
+        self.modules.insert(key(file), r#mod);
+    }
+
+    pub fn library(&self, file: &Path) -> Option<&Root> {
+        self.libraries.get(&key(file))
+    }
+
+    /// Keeps `library`, resolved from `file` by `loader`, along with the modules and libraries
+    /// `loader` parsed on the way.
+    pub fn insert_library(&mut self, file: &Path, library: Root, loader: ModuleLoader) {
+        self.modules.extend(loader.modules);
+        self.libraries.extend(loader.libraries);
+        self.libraries.insert(key(file), library);
+    }
+}
+
+fn library_entry(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.join("lib.ps")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+// The same file may be reached through different relative paths. The embedded files of the
+// standard library are kept as they are, whatever is on the machine at their path.
+fn key(file: &Path) -> PathBuf {
+    if stdlib_file(file).is_some() {
+        return file.to_path_buf();
+    }
+
+    file.canonicalize().unwrap_or_else(|_| file.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ast::TopLevel;
+
+    use crate::parser::{self, Config, Diagnostic, DiagnosticKind, ParsingCtx, Root, SourceFile};
+    use crate::test::TempDir;
+
+    /// Parses `main.ps` among `files`, as `(path, source)`, in a directory of its own.
+    fn parse_modules(
+        test: &str,
+        files: &[(&str, &str)],
+        config: impl FnOnce(&std::path::Path) -> Config,
+    ) -> Result<Root, Diagnostic> {
+        let dir = TempDir::new(&format!("modules-{test}"), files);
+
+        let config = Config {
+            quiet: true,
+            ..config(dir.path())
+        };
+        let mut parsing_ctx = ParsingCtx::new(&config);
+        let main = dir.path().join("main.ps").display().to_string();
+
+        parsing_ctx.add_file(&SourceFile::from_file(main).unwrap());
+
+        parser::parse(&mut parsing_ctx)
+    }
+
+    fn module<'a>(top_levels: &'a [TopLevel], name: &str) -> &'a ast::Mod {
+        top_levels
+            .iter()
+            .find_map(|top_level| match top_level {
+                TopLevel::Mod(identifier, r#mod, _) if identifier.name == name => Some(r#mod),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn module_parsed_once() {
+        let root = parse_modules(
+            "parsed_once",
+            &[
+                ("main.ps", "mod a\nmod b\nx = 1\n"),
+                ("a.ps", "mod util\ny = 1\n"),
+                ("b.ps", "mod util\nz = 2\n"),
+                ("util.ps", "double(x) => x * 2\n"),
+            ],
+            |_| Config::default(),
+        )
+        .unwrap();
+
+        let top_levels = &root.r#mod.top_levels;
+        let util_from_a = module(&module(top_levels, "a").top_levels, "util");
+        let util_from_b = module(&module(top_levels, "b").top_levels, "util");
+
+        // The same nodes, rather than the file parsed again.
+        assert_eq!(format!("{util_from_a:?}"), format!("{util_from_b:?}"));
+    }
+
+    #[test]
+    fn module_from_externs_and_std_path() {
+        let result = parse_modules(
+            "externs",
+            &[
+                ("main.ps", "mod geometry\nmod std\nx = 1\n"),
+                ("vendor/geometry/lib.ps", "square(x) => x * x\n"),
+                ("stdlib/lib.ps", "identity(x) => x\n"),
+            ],
+            |dir| Config {
+                project_config: parser::ProjectConfig {
+                    base_path: dir.to_path_buf(),
+                    externs: HashMap::from([("geometry".into(), "vendor/geometry".into())]),
+                    ..Default::default()
+                },
+                std_path: Some(dir.join("stdlib")),
+                ..Config::default()
+            },
+        );
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn std_embedded_without_std_path() {
+        let root = parse_modules("std", &[("main.ps", "mod std\nx = 1\n")], |_| {
+            Config::default()
+        })
+        .unwrap();
+
+        let std = module(&root.r#mod.top_levels, "std");
+        let prelude = module(&std.top_levels, "prelude");
+
+        assert!(prelude.top_levels.iter().any(
+            |top_level| matches!(top_level, TopLevel::Function(function) if function.name.name == "clamp")
+        ));
+    }
+
+    #[test]
+    fn module_not_found() {
+        let diagnostic = parse_modules("not_found", &[("main.ps", "mod missing\nx = 1\n")], |_| {
+            Config::default()
+        })
+        .unwrap_err();
+
+        assert!(matches!(
+            diagnostic.get_kind(),
+            DiagnosticKind::ModuleNotFound(name, searched)
+                if name == "missing" && searched.len() == 1 && searched[0].ends_with("missing.ps")
+        ));
+    }
+
+    #[test]
+    fn modules_declaring_each_other() {
+        let diagnostic = parse_modules(
+            "cycle",
+            &[
+                ("main.ps", "mod a\nx = 1\n"),
+                ("a.ps", "mod b\ny = 1\n"),
+                ("b.ps", "mod a\nz = 2\n"),
+            ],
+            |_| Config::default(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            diagnostic.get_kind(),
+            DiagnosticKind::ImportCycle(chain) if chain == ["a", "b", "a"]
+        ));
+
+        let diagnostic = parse_modules(
+            "cycle_to_main",
+            &[("main.ps", "mod a\nx = 1\n"), ("a.ps", "mod main\ny = 1\n")],
+            |_| Config::default(),
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            diagnostic.get_kind(),
+            DiagnosticKind::ImportCycle(chain) if chain == ["main", "a", "main"]
+        ));
+    }
+}
@@ -10,7 +10,7 @@ use crate::parser::diagnostic::Diagnostic;
 use crate::parser::diagnostics_list::{Diagnostics, DiagnosticType};
 
 
-use super::SourceFile;
+use super::{ModuleLoader, SourceFile};
 
 #[derive(Default, Debug)]
 pub struct ParsingCtx {
@@ -20,6 +20,9 @@ pub struct ParsingCtx {
     pub diagnostics: Diagnostics,
     pub operators_list: HashMap<String, u8>,
     pub identities: BTreeMap<NodeId, Span>,
+    /// The modules and libraries loaded so far, and the ones being loaded down to the file this
+    /// context parses, shared with the contexts of the libraries it imports.
+    pub modules: ModuleLoader,
 }
 
 impl ParsingCtx {
@@ -129,7 +132,10 @@ impl ParsingCtx {
                 );
             }
 
-            return Err(Diagnostic::new_empty());
+            return Err(errors
+                .first()
+                .map(|(_, diagnostic)| (*diagnostic).clone())
+                .unwrap_or_else(Diagnostic::new_empty));
         }
 
         Ok(())
@@ -158,6 +164,7 @@ impl ParsingCtx {
                 }
                     .into(),
                 m,
+                vec![],
             )
         })?;
 
@@ -5,6 +5,7 @@ use std::{
 use regex::Regex;
 use crate::input::Span;
 use crate::parser::diagnostic::Diagnostic;
+use crate::parse_util::is_color_literal;
 
 
 #[derive(Default, Debug, Clone)]
@@ -16,17 +17,13 @@ pub struct SourceFile {
 
 impl SourceFile {
     pub fn from_file(in_name: String) -> Result<Self, Diagnostic> {
-        // let content = if let Some(content) = super::STDLIB_FILES.get(&in_name) {
-        //     content.to_string()
-        // } else {
-        //     fs::read_to_string(in_name.clone()).map_err(|_| {
-        //         Diagnostic::new_file_not_found(Span::new_placeholder(), in_name.clone())
-        //     })?
-        // };
-
-        let content =fs::read_to_string(in_name.clone()).map_err(|_| {
-            Diagnostic::new_file_not_found(Span::empty(), in_name.clone())
-        })?;
+        let content = if let Some(content) = super::stdlib_file(Path::new(&in_name)) {
+            content.to_string()
+        } else {
+            fs::read_to_string(in_name.clone()).map_err(|_| {
+                Diagnostic::new_file_not_found(Span::empty(), in_name.clone())
+            })?
+        };
 
         let content = Self::sanitize_content(&content);
 
@@ -119,7 +116,7 @@ custom =
     // And append a \n at the end of file to avoid out of bounds error as the parser requires
     // a newline at the end of the file
     fn sanitize_content(content: &str) -> String {
-        let without_comments = Regex::new(r#"#.*\n"#).unwrap().replace_all(content, "\n");
+        let without_comments = Self::strip_comments(content);
 
         Regex::new(r"[ \t]+\n")
             .unwrap()
@@ -127,4 +124,99 @@ custom =
             .to_string()
             + "\n"
     }
+
+    // Removes `#` and `//` comments up to the end of their line, but not a `#` inside a string
+    // literal such as the format mask "#.##", nor a color literal such as #FF0000 where an
+    // expression can start.
+    // Annotations such as `//@version=6` at the start of a line are kept for the parser.
+    fn strip_comments(content: &str) -> String {
+        let mut stripped = String::with_capacity(content.len());
+        let mut characters = content.char_indices();
+        let mut quote = None;
+
+        while let Some((index, character)) = characters.next() {
+            match (quote, character) {
+                (None, '#')
+                    if !is_color_literal(line_beginning(content, index), &content[index + 1..]) =>
+                {
+                    if characters.by_ref().any(|(_, character)| character == '\n') {
+                        stripped.push('\n');
+                    }
+
+                    continue;
+                }
+                (None, '/') if content[index..].starts_with("//") => {
+                    let line = &content[index..];
+                    let line = &line[..line.find('\n').unwrap_or(line.len())];
+
+                    if line.starts_with("//@") && (index == 0 || content[..index].ends_with('\n')) {
+                        stripped.push_str(line.trim_end_matches('\r'));
+                    }
+
+                    if characters.by_ref().any(|(_, character)| character == '\n') {
+                        stripped.push('\n');
+                    }
+
+                    continue;
+                }
+                (None, '"' | '\'') => quote = Some(character),
+                (Some(_), '\\') => {
+                    stripped.push(character);
+                    stripped.extend(characters.next().map(|(_, character)| character));
+
+                    continue;
+                }
+                (Some(open), _) if open == character || character == '\n' => quote = None,
+                _ => {}
+            }
+
+            stripped.push(character);
+        }
+
+        stripped
+    }
+}
+
+// The part of the line of `content` before `index`.
+fn line_beginning(content: &str, index: usize) -> &str {
+    let line = &content[..index];
+
+    &line[line.rfind('\n').map_or(0, |start| start + 1)..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_comments_outside_strings_and_colors() {
+        let file =
+            SourceFile::from_str("root.ps", "a = \"#.##\" # mask\nb = '#' #\nc = #FF0000 #red\n")
+                .unwrap();
+
+        assert_eq!(file.content, "a = \"#.##\"\n\nb = '#'\n\nc = #FF0000\n\n\n");
+    }
+
+    #[test]
+    fn strip_hex_comments_after_an_operand() {
+        let file =
+            SourceFile::from_str("root.ps", "x = 1 #facade comment\ny = f(#facade) #c0ffee\n")
+                .unwrap();
+
+        assert_eq!(file.content, "x = 1\n\ny = f(#facade)\n\n\n");
+    }
+
+    #[test]
+    fn strip_line_comments_but_keep_annotations() {
+        let file = SourceFile::from_str(
+            "root.ps",
+            "//@version=6\n// note\nx = a / b // ratio\nurl = \"http://x\"\n    //@param y\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            file.content,
+            "//@version=6\n\nx = a / b\n\nurl = \"http://x\"\n\n\n\n"
+        );
+    }
 }
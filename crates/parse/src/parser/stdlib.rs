@@ -0,0 +1,22 @@
+use std::path::Path;
+
+/// Root of the standard library, which `mod std` loads unless `Config.std_path` points at
+/// another copy. Its files are embedded, so no such path has to exist on the machine.
+pub const STD_ROOT: &str = "/std/lib.ps";
+
+/// The standard library's prelude, declared ahead of the script when `Config.std` is set. The
+/// script's own declarations shadow the prelude's.
+pub const PRELUDE: &str = "/std/prelude.ps";
+
+pub(crate) static STDLIB_FILES: &[(&str, &str)] = &[
+    (STD_ROOT, include_str!("../../std/lib.ps")),
+    (PRELUDE, include_str!("../../std/prelude.ps")),
+];
+
+/// The embedded content of `path`, if it is a file of the standard library.
+pub(crate) fn stdlib_file(path: &Path) -> Option<&'static str> {
+    STDLIB_FILES
+        .iter()
+        .find(|(file, _)| Path::new(file) == path)
+        .map(|(_, content)| *content)
+}
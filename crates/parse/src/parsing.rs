@@ -14,6 +14,12 @@ mod parse_function;
 mod parse_block;
 mod parse_parameter;
 mod parse_if;
+mod parse_switch;
+mod parse_user_type;
+mod parse_enum;
+mod parse_declaration;
+mod parse_annotation;
+mod parse_export;
 
 
 pub use parse_import::*;
@@ -22,8 +28,15 @@ pub use parse_map::*;
 pub use parse_util::*;
 pub use parse_function::*;
 pub use parse_if::*;
+pub use parse_switch::*;
+pub use parse_user_type::*;
+pub use parse_enum::*;
+pub use parse_declaration::*;
+pub use parse_annotation::*;
+pub use parse_export::*;
 pub use parse_identifier::*;
 pub use parse_variable::*;
+pub use parse_assign::*;
 
 use crate::parse;
 
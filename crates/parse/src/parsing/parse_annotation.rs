@@ -0,0 +1,98 @@
+use ast::{Annotation, AnnotationKind};
+use nom::bytes::complete::tag;
+use nom::character::complete::{alpha1, not_line_ending};
+use nom::combinator::map;
+use nom::error::context;
+use nom::sequence::tuple;
+
+use crate::input::Input;
+use crate::parse_util::position;
+use crate::PineResult;
+
+/**
+//@<name>[=]<text up to the end of the line>
+ */
+pub fn parse_annotation(input: Input) -> PineResult<Annotation> {
+    context(
+        "annotation",
+        map(
+            tuple((position, tag("//@"), alpha1, not_line_ending)),
+            |(position, _, name, text): (_, _, Input, Input)| {
+                Annotation::new(
+                    annotation_kind(name.fragment(), text.fragment()),
+                    position(),
+                )
+            },
+        ),
+    )(input)
+}
+
+fn annotation_kind(name: &str, text: &str) -> AnnotationKind {
+    // The text of an example is code, whose indentation is kept.
+    let code = text
+        .strip_prefix(' ')
+        .unwrap_or(text)
+        .trim_end()
+        .to_string();
+    let text = text.trim().to_string();
+    let named = || match text.split_once(char::is_whitespace) {
+        Some((name, text)) => (name.to_string(), text.trim().to_string()),
+        None => (text.clone(), String::new()),
+    };
+
+    match name {
+        "version" => match text.strip_prefix('=').map(|version| version.trim().parse()) {
+            Some(Ok(version)) => AnnotationKind::Version(version),
+            _ => AnnotationKind::Other(name.to_string(), text),
+        },
+        "description" => AnnotationKind::Description(text),
+        "function" => AnnotationKind::Function(text),
+        "param" => {
+            let (name, text) = named();
+
+            AnnotationKind::Param(name, text)
+        }
+        "returns" => AnnotationKind::Returns(text),
+        "type" => AnnotationKind::Type(text),
+        "field" => {
+            let (name, text) = named();
+
+            AnnotationKind::Field(name, text)
+        }
+        "enum" => AnnotationKind::Enum(text),
+        "example" => AnnotationKind::Example(code),
+        _ => AnnotationKind::Other(name.to_string(), text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::input;
+
+    fn kind(line: &str) -> AnnotationKind {
+        parse_annotation(input(line)).unwrap().1.kind().clone()
+    }
+
+    #[test]
+    fn parse_annotations() {
+        assert_eq!(kind("//@version=6\n"), AnnotationKind::Version(6));
+        assert_eq!(
+            kind("//@version=next\n"),
+            AnnotationKind::Other("version".into(), "=next".into())
+        );
+        assert_eq!(
+            kind("//@param length  Number of bars.\n"),
+            AnnotationKind::Param("length".into(), "Number of bars.".into())
+        );
+        assert_eq!(
+            kind("//@returns The average.\n"),
+            AnnotationKind::Returns("The average.".into())
+        );
+        assert_eq!(
+            kind("//@example     x = mean(1, 2)\n"),
+            AnnotationKind::Example("    x = mean(1, 2)".into())
+        );
+        assert!(parse_annotation(input("// plain comment\n")).is_err());
+    }
+}
@@ -1,52 +1,82 @@
-// use nom::{
-//     branch::alt,
-//     bytes::complete::{tag, take_while1},
-//     character::complete::{char, multispace0},
-//     combinator::{opt, recognize},
-//     sequence::{pair, preceded, tuple},
-//
-// };
-// use nom::bytes::complete::take_while;
-// use nom::character::complete::not_line_ending;
-// use nom::combinator::map;
-// use nom::sequence::terminated;
-// use crate::input::Input;
-// use crate::PineResult;
-//
-//
-// pub fn parse_assignment(input: Input) -> PineResult<Variable> {
-//     map(
-//         tuple((
-//             parse_variable_declaration,
-//
-//             preceded(multispace0, char('=')),
-//             preceded(multispace0, not_line_ending),
-//         )),
-//         |(variable, _, expr)| Variable {
-//             declaration_mode: variable.declaration_mode,
-//             identifier: variable.identifier,
-//             var_type: variable.var_type,
-//             //identifier: identifier.to_string(),
-//
-//             value: parse_expr(expr).unwrap().1, // 使用 parse_expr 解析表达式
-//         })(input)
-//
-//     //
-//     //  let (input,  variable) = parse_variable_declaration(input)?;
-//     //  let (input, _) = preceded(multispace0, char('='))(input)?;  // 解析等号
-//     //
-//     //  //let (input, expr) = preceded(multispace0, take_while1(|c| c != '\n'))(input)?;  // 解析表达式
-//     //  let (input, expr) = preceded(multispace0, not_line_ending)(input)?;  // 解析表达式
-//     //
-//     //  // Ok((input, (mode, var_type, identifier, expr)))
-//     // // variable.value=parse_expr(expr).unwrap().1;
-//     //  let variable1 = Stmt::VariableDeclaration  {
-//     //      declaration_mode: variable.declaration_mode,
-//     //      //identifier:variable.identifier,
-//     //      var_type:variable.var_type,
-//     //      //identifier: identifier.to_string(),
-//     //      value: parse_expr(expr).unwrap().1 // 使用 parse_expr 解析表达式
-//     //  };
-//     //  Ok((input, variable1))
-// }
-//
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::combinator::{cut, map, value};
+use nom::error::context;
+use nom::multi::many0;
+use nom::sequence::{preceded, tuple};
+use ast::{Assignment, AssignmentOperator};
+
+use crate::input::Input;
+use crate::parse_util::position;
+use crate::parsing::parse_identifier::parse_identifier;
+use crate::{expression, sign, PineResult};
+
+/**
+<identifier>[.<field>...] (:= | += | -= | *= | /=) <expression>
+ */
+pub fn parse_assignment(input: Input) -> PineResult<Assignment> {
+    context(
+        "assignment",
+        map(
+            tuple((
+                position,
+                parse_identifier,
+                many0(preceded(tag("."), parse_identifier)),
+                assignment_operator,
+                cut(expression),
+            )),
+            |(position, identifier, fields, operator, value)| {
+                let fields = fields.into_iter().map(|field| field.name).collect();
+
+                Assignment::new(identifier.name, fields, operator, value, position())
+            },
+        ),
+    )(input)
+}
+
+fn assignment_operator(input: Input) -> PineResult<AssignmentOperator> {
+    context(
+        "assignment operator",
+        alt((
+            value(AssignmentOperator::Reassign, sign(":=")),
+            value(AssignmentOperator::Add, sign("+=")),
+            value(AssignmentOperator::Subtract, sign("-=")),
+            value(AssignmentOperator::Multiply, sign("*=")),
+            value(AssignmentOperator::Divide, sign("/=")),
+        )),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::input;
+
+    #[test]
+    fn parse_reassignment() {
+        let (_, assignment) = parse_assignment(input("total := total + 1")).unwrap();
+
+        assert_eq!(assignment.name(), "total");
+        assert_eq!(assignment.operator(), AssignmentOperator::Reassign);
+    }
+
+    #[test]
+    fn parse_compound_assignment() {
+        let (_, assignment) = parse_assignment(input("count -= 2")).unwrap();
+
+        assert_eq!(assignment.operator(), AssignmentOperator::Subtract);
+    }
+
+    #[test]
+    fn parse_field_assignment() {
+        let (_, assignment) = parse_assignment(input("pivot.price += 1.5")).unwrap();
+
+        assert_eq!(assignment.name(), "pivot");
+        assert_eq!(assignment.fields(), ["price"]);
+    }
+
+    #[test]
+    fn declaration_is_not_an_assignment() {
+        assert!(parse_assignment(input("count = 2")).is_err());
+    }
+}
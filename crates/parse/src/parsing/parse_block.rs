@@ -31,7 +31,6 @@ pub fn indent<'a, O, E, F>(mut parser: F) -> impl FnMut(Input<'a>) -> IResult<In
 pub fn parse_block_indent(input: Input) -> PineResult<usize> {
     let (mut input, indent) = space1(input)?;
     let indent_len = indent.fragment().len();
-    println!("indent_len {:?}",indent_len);
     if input.extra.first_indent == None {
         input.extra.first_indent = Some(indent_len);
         input.extra.block_indent = indent_len;
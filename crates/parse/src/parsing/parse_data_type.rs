@@ -11,6 +11,7 @@ use nom::combinator::map;
 use nom::sequence::terminated;
 use ast::datatype::DataType;
 use crate::input::Input;
+use crate::parsing::parse_identifier::parse_identifier;
 use crate::PineResult;
 
 
@@ -34,5 +35,17 @@ pub fn parse_type(input: Input) -> PineResult<DataType> {
         map(preceded(tag("matrix<"), terminated(parse_type, tag(">"))), |t| {
             DataType::Matrix(Box::new(t))
         }),
+        map(
+            preceded(
+                tag("map<"),
+                terminated(
+                    pair(parse_type, preceded(pair(char(','), multispace0), parse_type)),
+                    tag(">"),
+                ),
+            ),
+            |(key, value)| DataType::Map(Box::new(key), Box::new(value)),
+        ),
+        // Anything else names a user-defined type.
+        map(parse_identifier, |identifier| DataType::Custom(identifier.name)),
     ))(input)
 }
\ No newline at end of file
@@ -0,0 +1,83 @@
+use ast::{Declaration, DeclarationArgument, DeclarationKind};
+use nom::bytes::complete::tag;
+use nom::combinator::{cut, map, opt, verify};
+use nom::error::context;
+use nom::sequence::{terminated, tuple};
+
+use crate::combinator::separated_or_terminated_list0;
+use crate::input::Input;
+use crate::parse_util::{expression, position};
+use crate::parsing::parse_identifier::parse_identifier;
+use crate::{sign, PineResult};
+
+/**
+indicator|strategy|library(<expression>, ..., <identifier> = <expression>, ...)
+ */
+pub fn parse_declaration(input: Input) -> PineResult<Declaration> {
+    context(
+        "declaration",
+        map(
+            tuple((
+                position,
+                verify(parse_identifier, |identifier| {
+                    DeclarationKind::from_name(identifier).is_some()
+                }),
+                tag("("),
+                cut(terminated(
+                    separated_or_terminated_list0(sign(","), parse_declaration_argument),
+                    sign(")"),
+                )),
+            )),
+            |(position, identifier, _, arguments)| {
+                Declaration::new(
+                    DeclarationKind::from_name(&identifier).unwrap(),
+                    arguments,
+                    position(),
+                )
+            },
+        ),
+    )(input)
+}
+
+fn parse_declaration_argument(input: Input) -> PineResult<DeclarationArgument> {
+    map(
+        tuple((
+            position,
+            opt(terminated(parse_identifier, sign("="))),
+            expression,
+        )),
+        |(position, name, value)| {
+            DeclarationArgument::new(name.map(|name| name.name), value, position())
+        },
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::input;
+
+    #[test]
+    fn parse_declaration_with_named_arguments() {
+        let (_, declaration) = parse_declaration(input(
+            "strategy(\"Cross\", \"X\", overlay = true, initial_capital = 1000)\n",
+        ))
+        .unwrap();
+
+        assert_eq!(declaration.kind(), DeclarationKind::Strategy);
+        assert_eq!(
+            declaration
+                .parameters()
+                .map(|(parameter, _)| parameter)
+                .collect::<Vec<_>>(),
+            [
+                Some("title"),
+                Some("shorttitle"),
+                Some("overlay"),
+                Some("initial_capital")
+            ]
+        );
+        assert!(parse_declaration(input("signal(\"x\")\n")).is_err());
+        assert!(parse_declaration(input("indicator.name\n")).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use nom::character::complete::{line_ending, space0};
+use nom::combinator::{cut, map, opt};
+use nom::multi::{many1, separated_list1};
+use nom::sequence::{preceded, tuple};
+use ast::{EnumDefinition, EnumMemberDefinition};
+
+use crate::input::Input;
+use crate::parse_util::{position, string_literal};
+use crate::parsing::parse_block::{indent, parse_block_indent};
+use crate::parsing::parse_identifier::parse_identifier;
+use crate::{keyword, sign, PineResult};
+
+/**
+enum <identifier>
+    <identifier> [= "<title>"]
+    ...
+ */
+pub fn parse_enum(input: Input) -> PineResult<EnumDefinition> {
+    map(
+        tuple((
+            position,
+            keyword("enum"),
+            cut(parse_identifier),
+            space0,
+            many1(line_ending),
+            indent(separated_list1(
+                many1(line_ending),
+                preceded(parse_block_indent, parse_enum_member),
+            )),
+        )),
+        |(position, _, name, _, _, members)| EnumDefinition::new(name, members, position()),
+    )(input)
+}
+
+fn parse_enum_member(input: Input) -> PineResult<EnumMemberDefinition> {
+    map(
+        tuple((
+            position,
+            parse_identifier,
+            opt(preceded(sign("="), cut(string_literal))),
+        )),
+        |(position, name, title)| EnumMemberDefinition::new(name, title, position()),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::input;
+
+    #[test]
+    fn parse_enum_with_titles() {
+        let (_, definition) =
+            parse_enum(input("enum Signal\n    buy = \"Buy signal\"\n    sell\n")).unwrap();
+
+        assert_eq!(definition.name(), "Signal");
+        assert_eq!(definition.members().len(), 2);
+        assert_eq!(definition.members()[0].title(), "Buy signal");
+        assert_eq!(definition.members()[1].title(), "sell");
+    }
+}
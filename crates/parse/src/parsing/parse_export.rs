@@ -0,0 +1,64 @@
+use ast::TopLevel;
+use nom::branch::alt;
+use nom::character::complete::space0;
+use nom::combinator::{cut, map};
+use nom::error::context;
+use nom::sequence::preceded;
+
+use crate::input::Input;
+use crate::parsing::parse_enum::parse_enum;
+use crate::parsing::parse_function::parse_function;
+use crate::parsing::parse_user_type::{parse_method, parse_user_type};
+use crate::{keyword, PineResult};
+
+/**
+export <function>|<method>|<type>|<enum>
+ */
+pub fn parse_export(input: Input) -> PineResult<TopLevel> {
+    context(
+        "export",
+        preceded(
+            keyword("export"),
+            cut(preceded(
+                space0,
+                alt((
+                    map(parse_user_type, |mut definition| {
+                        definition.set_exported(true);
+                        TopLevel::new_type(definition)
+                    }),
+                    map(parse_enum, |mut definition| {
+                        definition.set_exported(true);
+                        TopLevel::new_enum(definition)
+                    }),
+                    map(parse_method, |mut method| {
+                        method.exported = true;
+                        TopLevel::new_method(method)
+                    }),
+                    map(parse_function, |mut function| {
+                        function.exported = true;
+                        TopLevel::new_function(function)
+                    }),
+                )),
+            )),
+        ),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::input;
+
+    #[test]
+    fn parse_exported_declarations() {
+        let (_, top_level) = parse_export(input("export double(float x) => x * 2\n")).unwrap();
+
+        assert!(matches!(top_level, TopLevel::Function(function) if function.exported));
+
+        let (_, top_level) =
+            parse_export(input("export type Point\n    float x\n    float y\n")).unwrap();
+
+        assert!(matches!(top_level, TopLevel::Type(definition) if definition.is_exported()));
+        assert!(parse_export(input("export x = 1\n")).is_err());
+    }
+}
@@ -43,6 +43,8 @@ pub fn parse_function(input: Input) -> PineResult< FunctionDecl> {
             //signature: FuncType::from_args_nb(arguments.len()), // FIXME: Should not generate random signature
             arguments,
             position:position(),
+            docs: None,
+            exported: false,
         },
     )(input)
 }
@@ -17,6 +17,11 @@ pub  fn parse_identifier(input: Input) -> PineResult<Identifier> {
     context("identifier", token(raw_identifier))(input)
 }
 
+/// Name after a `.`, where keywords are allowed (`input.enum`).
+pub fn parse_member_name(input: Input) -> PineResult<Identifier> {
+    context("member name", token(unchecked_identifier))(input)
+}
+
 fn raw_identifier(input: Input) -> PineResult<Identifier> {
     verify(unchecked_identifier, |identifier| {
         !KEYWORDS.contains(&&*identifier.name)
@@ -27,7 +32,9 @@ fn unchecked_identifier(mut input: Input) -> PineResult<Identifier> {
 
 
     let (input, ident_parsed) =
-        recognize(many1(one_of("abcdefghijklmnopqrstuvwxyz_0123456789")))(input)?;
+        recognize(many1(one_of(
+            "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_0123456789",
+        )))(input)?;
 
     let (input, node_id) = new_identity(input, &ident_parsed);
 
@@ -5,11 +5,12 @@ use nom::combinator::{map, opt};
 use nom::error::{ErrorKind, ParseError};
 use nom::multi::many1;
 use nom::sequence::{preceded, terminated, tuple};
-use ast::{Else, For, ForIn, IfDecl, While};
+use ast::{Else, Expression, For, ForIn, ForRange, IfDecl, While};
 
 use crate::input::Input;
 use crate::parsing::parse_node::parse_identity;
-use crate::{expression, PineResult};
+use crate::{expression, keyword, sign, PineResult};
+use crate::parse_util::position;
 use crate::parsing::parse_block::parse_block_indent;
 use crate::parsing::parse_function::parse_body;
 use crate::parsing::parse_identifier::parse_identifier;
@@ -18,6 +19,7 @@ use crate::parsing::parse_identifier::parse_identifier;
 pub fn parse_if(input: Input) -> PineResult< IfDecl> {
     map(
         tuple((
+            position,
             parse_identity,
             terminated(tag("if"), space1),
             expression,
@@ -25,8 +27,14 @@ pub fn parse_if(input: Input) -> PineResult< IfDecl> {
             parse_body,
             opt(tuple((line_ending, parse_else))),
         )),
-        |(node_id, _if_, cond, _, body, else_)| {
-            IfDecl::new(node_id, cond, body, else_.map(|(_, else_)| Box::new(else_)))
+        |(position, node_id, _if_, cond, _, body, else_)| {
+            IfDecl::new(
+                node_id,
+                cond,
+                body,
+                else_.map(|(_, else_)| Box::new(else_)),
+                position(),
+            )
         },
     )(input.clone())
 }
@@ -85,29 +93,123 @@ pub fn parse_else(input: Input) -> PineResult< Else> {
 }
 
 pub fn parse_for(input: Input) -> PineResult< For> {
-    alt((map(parse_for_in, For::In), map(parse_while, For::While)))(input)
+    alt((
+        map(parse_for_range, |for_range| For::Range(Box::new(for_range))),
+        map(parse_for_in, For::In),
+        map(parse_while, For::While),
+    ))(input)
+}
+
+/**
+for <identifier> = <expression> to <expression> [by <expression>]
+ */
+pub fn parse_for_range(input: Input) -> PineResult<ForRange> {
+    map(
+        tuple((
+            position,
+            terminated(tag("for"), space1),
+            terminated(parse_identifier, space0),
+            sign("="),
+            expression,
+            preceded(keyword("to"), expression),
+            terminated(opt(preceded(keyword("by"), expression)), space0),
+            parse_body,
+        )),
+        |(position, _, var, _, start, end, step, body)| {
+            ForRange::new(var, start, end, step, body, position())
+        },
+    )(input)
 }
 
 pub fn parse_for_in(input: Input) -> PineResult< ForIn> {
     map(
         tuple((
+            position,
             terminated(tag("for"), space1),
             terminated(parse_identifier, space0),
             terminated(tag("in"), space0),
             terminated(expression, space0),
             parse_body,
         )),
-        |(_, var, _, expr, body)| ForIn::new(var, expr, body),
+        |(position, _, var, _, expr, body)| ForIn::new(var, expr, body, position()),
     )(input)
 }
 
 pub fn parse_while(input: Input) -> PineResult< While> {
     map(
         tuple((
+            position,
             terminated(tag("while"), space1),
             terminated(expression, space0),
             parse_body,
         )),
-        |(_, cond, body)| While::new(cond, body),
+        |(position, _, cond, body)| While::new(cond, body, position()),
     )(input)
 }
+
+pub fn parse_break(input: Input) -> PineResult<Expression> {
+    map(tuple((position, keyword("break"))), |(position, _)| {
+        Expression::Break(position())
+    })(input)
+}
+
+pub fn parse_continue(input: Input) -> PineResult<Expression> {
+    map(tuple((position, keyword("continue"))), |(position, _)| {
+        Expression::Continue(position())
+    })(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::input;
+
+    #[test]
+    fn parse_while_with_break() {
+        let (_, while_) = parse_while(input("while i < 3\n    i = i + 1\n    break\n")).unwrap();
+
+        assert_eq!(while_.body.stmts.len(), 2);
+        assert!(matches!(
+            while_.body.stmts[1].expression(),
+            Expression::Break(_)
+        ));
+    }
+
+    #[test]
+    fn parse_for_in_with_continue() {
+        let (_, for_) = parse_for(input("for x in xs\n    continue\n")).unwrap();
+
+        match for_ {
+            For::In(for_in) => {
+                assert_eq!(for_in.value.name, "x");
+                assert!(matches!(
+                    for_in.body.stmts[0].expression(),
+                    Expression::Continue(_)
+                ));
+            }
+            _ => panic!("expected for-in loop"),
+        }
+    }
+
+    #[test]
+    fn parse_for_range_with_step() {
+        let (_, for_) = parse_for(input("for i = 10 to 0 by -2\n    i\n")).unwrap();
+
+        match for_ {
+            For::Range(for_range) => {
+                assert_eq!(for_range.value.name, "i");
+                assert!(for_range.step.is_some());
+                assert_eq!(for_range.body.stmts.len(), 1);
+            }
+            _ => panic!("expected range loop"),
+        }
+    }
+
+    #[test]
+    fn parse_else_if_chain() {
+        let (_, if_) = parse_if(input("if a\n    1\nelse if b\n    2\nelse\n    3\n")).unwrap();
+
+        assert_eq!(if_.get_flat().len(), 2);
+        assert!(if_.last_else().is_some());
+    }
+}
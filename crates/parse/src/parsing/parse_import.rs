@@ -32,3 +32,22 @@ pub fn parse_import(input: Input) -> PineResult<Import> {
         ),
     )(input)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::{LibraryModulePath, ModulePath};
+    use crate::input::input;
+
+    #[test]
+    fn parse_library_import() {
+        let (_, import) = parse_import(input("import alice/Geometry/2 as geo\n")).unwrap();
+
+        assert_eq!(
+            import.module_path(),
+            &ModulePath::Library(LibraryModulePath::new("alice", "Geometry", 2))
+        );
+        assert_eq!(import.prefix(), Some("geo"));
+        assert!(parse_import(input("import alice/Geometry/latest\n")).is_err());
+    }
+}
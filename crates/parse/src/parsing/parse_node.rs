@@ -21,6 +21,6 @@ pub fn parse_identity(input: Input) -> PineResult<NodeId> {
     let (input, pos) = position(input)?;
 
     let (input, node_id) = new_identity(input, &pos);
-    println!("node_id{:?}",node_id);
+
     Ok((input, node_id))
 }
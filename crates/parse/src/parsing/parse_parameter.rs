@@ -1,19 +1,24 @@
 use nom::bytes::complete::tag;
 use nom::character::complete::multispace0;
-use nom::combinator::opt;
+use nom::character::complete::space1;
+use nom::combinator::{opt, peek};
 use nom::multi::separated_list0;
-use nom::sequence::{delimited, preceded};
+use nom::sequence::{delimited, preceded, terminated};
 use ast::Parameter;
 use crate::input::Input;
 use crate::parsing::parse_identifier::parse_identifier;
-use crate::{expression, PineResult};
+use crate::{expression, sign, PineResult};
+use crate::parsing::parse_data_type::parse_type;
 use crate::parsing::parse_util::position;
 
 pub fn parse_parameter(input:Input) -> PineResult<Parameter> {
     let (input, pp) = position(input)?;
+    // A type is followed by the name, unlike the name in `length = 14`.
+    let (input, data_type) =
+        opt(terminated(parse_type, terminated(space1, peek(parse_identifier))))(input)?;
     let (input, ident) = parse_identifier(input)?;
-    let (input, default_value) = opt(preceded(tag("="), expression))(input)?;
-    Ok((input, Parameter::new(ident, default_value,pp())))
+    let (input, default_value) = opt(preceded(sign("="), expression))(input)?;
+    Ok((input, Parameter::new(ident, default_value,pp()).with_data_type(data_type)))
 }
 
 pub fn parse_parameter_list(input:Input) -> PineResult< Vec<Parameter>> {
@@ -0,0 +1,87 @@
+use nom::character::complete::{line_ending, space0, space1};
+use nom::combinator::{map, map_opt, not, opt};
+use nom::multi::{many1, separated_list1};
+use nom::sequence::{preceded, tuple};
+use ast::{Body, Expression, Switch, SwitchArm};
+
+use crate::input::Input;
+use crate::parse_util::position;
+use crate::parsing::parse_block::{indent, parse_block_indent};
+use crate::parsing::parse_function::parse_body;
+use crate::{expression, keyword, sign, PineResult};
+
+/**
+switch [<expression>]
+    <expression> => <body>
+    ...
+    => <body>
+ */
+pub fn parse_switch(input: Input) -> PineResult<Switch> {
+    map_opt(
+        tuple((
+            position,
+            keyword("switch"),
+            // The subject has to stay on the `switch` line, otherwise the first arm would be
+            // taken for it.
+            opt(preceded(tuple((space1, not(line_ending))), expression)),
+            space0,
+            many1(line_ending),
+            indent(separated_list1(
+                many1(line_ending),
+                preceded(parse_block_indent, parse_switch_arm),
+            )),
+        )),
+        |(position, _, subject, _, _, mut arms)| {
+            let default = match arms.last() {
+                Some((None, _)) => arms.pop().map(|(_, body)| body),
+                _ => None,
+            };
+
+            // Only the last arm may omit its pattern.
+            let arms = arms
+                .into_iter()
+                .map(|(pattern, body)| pattern.map(|pattern| SwitchArm::new(pattern, body)))
+                .collect::<Option<Vec<_>>>()?;
+
+            Some(Switch::new(subject, arms, default, position()))
+        },
+    )(input)
+}
+
+fn parse_switch_arm(input: Input) -> PineResult<(Option<Expression>, Body)> {
+    map(
+        tuple((opt(expression), sign("=>"), space0, parse_body)),
+        |(pattern, _, _, body)| (pattern, body),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::input;
+
+    #[test]
+    fn parse_switch_with_subject_and_default() {
+        let (_, switch) =
+            parse_switch(input("switch x\n    \"a\" => 1\n    \"b\" =>\n        2\n    => 3\n"))
+                .unwrap();
+
+        assert!(switch.subject.is_some());
+        assert_eq!(switch.arms.len(), 2);
+        assert!(switch.default.is_some());
+    }
+
+    #[test]
+    fn parse_switch_without_subject() {
+        let (_, switch) = parse_switch(input("switch\n    x > 1 => 1\n    x < 1 => 2\n")).unwrap();
+
+        assert!(switch.subject.is_none());
+        assert_eq!(switch.arms.len(), 2);
+        assert!(switch.default.is_none());
+    }
+
+    #[test]
+    fn default_arm_must_be_last() {
+        assert!(parse_switch(input("switch x\n    => 3\n    1 => 1\n")).is_err());
+    }
+}
@@ -0,0 +1,90 @@
+use nom::character::complete::{line_ending, space0, space1};
+use nom::combinator::{cut, map, opt};
+use nom::multi::{many1, separated_list1};
+use nom::sequence::{preceded, terminated, tuple};
+use ast::{FunctionDecl, UserTypeDefinition, UserTypeField};
+
+use crate::input::Input;
+use crate::parse_util::position;
+use crate::parsing::parse_block::{indent, parse_block_indent};
+use crate::parsing::parse_data_type::parse_type;
+use crate::parsing::parse_function::parse_function;
+use crate::parsing::parse_identifier::parse_identifier;
+use crate::{expression, keyword, sign, PineResult};
+
+/**
+type <identifier>
+    <type> <identifier> [= <expression>]
+    ...
+ */
+pub fn parse_user_type(input: Input) -> PineResult<UserTypeDefinition> {
+    map(
+        tuple((
+            position,
+            keyword("type"),
+            cut(parse_identifier),
+            space0,
+            many1(line_ending),
+            indent(separated_list1(
+                many1(line_ending),
+                preceded(parse_block_indent, parse_user_type_field),
+            )),
+        )),
+        |(position, _, name, _, _, fields)| UserTypeDefinition::new(name, fields, position()),
+    )(input)
+}
+
+fn parse_user_type_field(input: Input) -> PineResult<UserTypeField> {
+    map(
+        tuple((
+            position,
+            terminated(parse_type, space1),
+            parse_identifier,
+            opt(preceded(sign("="), expression)),
+        )),
+        |(position, data_type, name, default)| {
+            UserTypeField::new(data_type, name, default, position())
+        },
+    )(input)
+}
+
+/**
+method <identifier>(<type> <receiver>, ...) => <body>
+ */
+pub fn parse_method(input: Input) -> PineResult<FunctionDecl> {
+    preceded(keyword("method"), cut(preceded(space0, parse_function)))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::datatype::DataType;
+    use crate::input::input;
+
+    #[test]
+    fn parse_user_type_with_defaults() {
+        let (_, definition) =
+            parse_user_type(input("type Pivot\n    float price = 0.0\n    int bar\n    Pivot next\n"))
+                .unwrap();
+
+        assert_eq!(definition.name(), "Pivot");
+        assert_eq!(definition.fields().len(), 3);
+        assert!(definition.fields()[0].default().is_some());
+        assert!(definition.fields()[1].default().is_none());
+        assert_eq!(
+            definition.fields()[2].data_type(),
+            &DataType::Custom("Pivot".into())
+        );
+    }
+
+    #[test]
+    fn parse_method_with_typed_receiver() {
+        let (_, method) = parse_method(input("method bump(Pivot this, float amount) =>\n    this.price += amount\n")).unwrap();
+
+        assert_eq!(method.name.name, "bump");
+        assert_eq!(
+            method.arguments[0].data_type,
+            Some(DataType::Custom("Pivot".into()))
+        );
+    }
+}
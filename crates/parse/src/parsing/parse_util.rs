@@ -1,23 +1,25 @@
-use crate::{combinator::{separated_or_terminated_list0, separated_or_terminated_list1}, error::NomError, input::{self, Input}, PineResult, KEYWORDS, operations::{reduce_operations, SuffixOperator}, OPERATOR_CHARACTERS, OPERATOR_MODIFIERS, parse_record, record_definition, parse_import};
-use ast::{types::Type, *};
+use crate::{combinator::{separated_or_terminated_list0, separated_or_terminated_list1}, error::NomError, input::{self, Input}, PineResult, KEYWORDS, operations::{reduce_operations, SuffixOperator}, OPERATOR_CHARACTERS, OPERATOR_MODIFIERS, parse_assignment, parse_tuple_definition, parse_record, record_definition, parse_import, parse_break, parse_continue, parse_for, parse_if, parse_switch};
+use ast::{datatype::DataType, types::Type, *};
 use nom::{
     branch::alt,
-    bytes::complete::tag,
+    bytes::complete::{tag, take_while1},
     character::complete::{
-        alpha1, alphanumeric1, char, digit1, multispace0, multispace1, none_of, one_of,
+        alphanumeric1, anychar, char, digit1, multispace0, multispace1, none_of, one_of, space0,
+        space1,
     },
     combinator::{
-        all_consuming, cut, into, map, not, opt, peek, recognize, success, value, verify,
+        all_consuming, cut, fail, into, map, map_opt, not, opt, peek, recognize, value, verify,
     },
     error::context,
     multi::{count, many0, many0_count, many1, separated_list1},
     number::complete::recognize_float,
-    sequence::{delimited, pair, preceded, terminated, tuple},
+    sequence::{delimited, preceded, terminated, tuple},
     Parser,
 };
 use position::Position;
 use std::{collections::HashSet, str};
-use crate::parsing::parse_identifier::{parse_identifier, qualified_identifier};
+use crate::parsing::parse_data_type::parse_type;
+use crate::parsing::parse_identifier::{parse_identifier, parse_member_name, qualified_identifier};
 
 
 pub fn module(input: Input) -> PineResult<Module> {
@@ -49,6 +51,7 @@ pub fn comments(input: Input) -> PineResult<Vec<Comment>> {
             alt((
                 map(comment, Some),
                 map(raw_string_literal, |_| None),
+                map(color_digits, |_| None),
                 map(none_of("\"#"), |_| None),
             )),
             multispace0,
@@ -74,12 +77,31 @@ pub fn module_path(input: Input) -> PineResult<ModulePath> {
     context(
         "module path",
         token(alt((
+            into(library_module_path),
             into(external_module_path),
             into(internal_module_path),
         ))),
     )(input)
 }
 
+/// `owner/name/version`, a library pinned to a version.
+fn library_module_path(input: Input) -> PineResult<LibraryModulePath> {
+    context(
+        "library module path",
+        map(
+            tuple((
+                parse_identifier,
+                preceded(tag("/"), parse_identifier),
+                preceded(
+                    tag("/"),
+                    map_opt(digit1, |version: Input| version.fragment().parse().ok()),
+                ),
+            )),
+            |(owner, name, version)| LibraryModulePath::new(owner.name, name.name, version),
+        ),
+    )(input)
+}
+
 fn internal_module_path(input: Input) -> PineResult<InternalModulePath> {
     context(
         "internal module path",
@@ -299,16 +321,51 @@ pub fn statement(input: Input) -> PineResult<Statement> {
     context(
         "statement",
         map(
-            tuple((position, opt(terminated(parse_identifier, sign("="))), expression)),
-            |(position, ident, expression)| Statement::new(ident
+            tuple((
+                position,
+                // `name =` must be followed by an expression; otherwise the `=` may belong to
+                // something else, such as the `=>` of a switch arm on the next line.
+                alt((
+                    map(parse_tuple_definition, |definition| (None, definition.into())),
+                    map(parse_assignment, |assignment| (None, assignment.into())),
+                    map(
+                        tuple((terminated(parse_identifier, sign("=")), expression)),
+                        |(ident, expression)| (Some(ident), expression),
+                    ),
+                    map(expression, |expression| (None, expression)),
+                )),
+            )),
+            |(position, (ident, expression))| Statement::new(ident
                                                                .map(|identifier| identifier.name), expression, position()),
         ),
     )(input)
 }
 
+/// `condition ? then : else`. The conditional binds looser than any binary operator and
+/// nests to the right, so `a ? b : c ? d : e` reads as `a ? b : (c ? d : e)`.
 pub fn expression(input: Input) -> PineResult<Expression> {
     context(
         "expression",
+        map(
+            tuple((
+                position,
+                binary_expression,
+                opt(tuple((
+                    preceded(sign("?"), cut(expression)),
+                    preceded(cut(sign(":")), cut(expression)),
+                ))),
+            )),
+            |(position, condition, branches)| match branches {
+                Some((then, else_)) => Conditional::new(condition, then, else_, position()).into(),
+                None => condition,
+            },
+        ),
+    )(input)
+}
+
+fn binary_expression(input: Input) -> PineResult<Expression> {
+    context(
+        "binary expression",
         map(
             tuple((
                 prefix_operation_like,
@@ -329,7 +386,8 @@ fn binary_operator(input: Input) -> PineResult<BinaryOperator> {
             value(BinaryOperator::Add, sign("+")),
             value(BinaryOperator::Subtract, sign("-")),
             value(BinaryOperator::Multiply, sign("*")),
-            value(BinaryOperator::Divide, sign("/")),
+            // An annotation such as `//@version=6` may follow on the next line.
+            value(BinaryOperator::Divide, terminated(sign("/"), not(tag("/")))),
             value(BinaryOperator::Equal, sign("==")),
             value(BinaryOperator::NotEqual, sign("!=")),
             value(BinaryOperator::LessThanOrEqual, sign("<=")),
@@ -369,11 +427,15 @@ fn suffix_operation_like(input: Input) -> PineResult<Expression> {
             suffix_operators
                 .into_iter()
                 .fold(expression, |expression, operator| match operator {
-                    SuffixOperator::Call(arguments, position) => {
-                        Call::new(expression, arguments, position).into()
+                    SuffixOperator::Call(arguments, named_arguments, position) => {
+                        Call::new(expression, arguments, position)
+                            .with_named_arguments(named_arguments)
+                            .into()
                     }
-                    SuffixOperator::RecordField(name, position) => {
-                        RecordDeconstruction::new(expression, name, position).into()
+                    SuffixOperator::RecordField(name, type_arguments, position) => {
+                        RecordDeconstruction::new(expression, name, position)
+                            .with_type_arguments(type_arguments)
+                            .into()
                     }
                     SuffixOperator::Try(position) => {
                         UnaryOperation::new(UnaryOperator::Try, expression, position).into()
@@ -396,47 +458,135 @@ fn call_operator(input: Input) -> PineResult<SuffixOperator> {
                 peek(position),
                 tag("("),
                 cut(terminated(
-                    separated_or_terminated_list0(sign(","), expression),
+                    // Positional arguments come first, as in `input.int(14, "Length", minval = 1)`.
+                    verify(
+                        separated_or_terminated_list0(sign(","), call_argument),
+                        |arguments: &[CallArgument]| {
+                            arguments
+                                .iter()
+                                .skip_while(|argument| {
+                                    matches!(argument, CallArgument::Positional(_))
+                                })
+                                .all(|argument| matches!(argument, CallArgument::Named(_)))
+                        },
+                    ),
                     sign(")"),
                 )),
             )),
-            |(position, _, arguments)| SuffixOperator::Call(arguments, position()),
+            |(position, _, arguments)| {
+                let (positional, named) = arguments.into_iter().fold(
+                    (vec![], vec![]),
+                    |(mut positional, mut named), argument| {
+                        match argument {
+                            CallArgument::Positional(argument) => positional.push(argument),
+                            CallArgument::Named(argument) => named.push(argument),
+                        }
+
+                        (positional, named)
+                    },
+                );
+
+                SuffixOperator::Call(positional, named, position())
+            },
         ),
     )(input)
 }
 
+enum CallArgument {
+    Positional(Expression),
+    Named(NamedArgument),
+}
+
+fn call_argument(input: Input) -> PineResult<CallArgument> {
+    alt((
+        map(
+            tuple((
+                position,
+                terminated(parse_identifier, sign("=")),
+                cut(expression),
+            )),
+            |(position, name, value)| {
+                CallArgument::Named(NamedArgument::new(name.name, value, position()))
+            },
+        ),
+        map(expression, CallArgument::Positional),
+    ))(input)
+}
+
 fn record_field_operator(input: Input) -> PineResult<SuffixOperator> {
     context(
         "record field",
         map(
-            tuple((position, sign("."), cut(parse_identifier))),
-            |(position, _, identifier)| SuffixOperator::RecordField(identifier.name, position()),
+            tuple((
+                position,
+                sign("."),
+                cut(parse_member_name),
+                // `array.new<float>(...)` or `map.new<string, float>()`; values carry their own
+                // type at runtime, the resolver checks map keys and values against it.
+                opt(terminated(type_arguments, peek(char('(')))),
+            )),
+            |(position, _, identifier, type_arguments)| {
+                SuffixOperator::RecordField(
+                    identifier.name,
+                    type_arguments.unwrap_or_default(),
+                    position(),
+                )
+            },
         ),
     )(input)
 }
 
+fn type_arguments(input: Input) -> PineResult<Vec<DataType>> {
+    delimited(
+        char('<'),
+        separated_list1(tuple((char(','), space0)), parse_type),
+        char('>'),
+    )(input)
+}
+
+/// A `?` that isn't the start of the branches of a conditional, `? then : else`.
 fn try_operator(input: Input) -> PineResult<SuffixOperator> {
     context(
         "try operator",
-        map(tuple((position, sign("?"))), |(position, _)| {
-            SuffixOperator::Try(position())
-        }),
+        map(
+            tuple((position, terminated(sign("?"), not(conditional_branch)))),
+            |(position, _)| SuffixOperator::Try(position()),
+        ),
     )(input)
 }
 
+// Only looks ahead, so a failure within the branch doesn't stop the `?` from being a suffix.
+fn conditional_branch(input: Input) -> PineResult<()> {
+    value((), tuple((expression, sign(":"))))(input).map_err(|error| match error {
+        nom::Err::Failure(error) => nom::Err::Error(error),
+        error => error,
+    })
+}
+
 fn atomic_expression(input: Input) -> PineResult<Expression> {
     alt((
         into(lambda),
         into(if_type),
         into(if_list),
         into(if_map),
+        into(parse_if),
+        into(parse_for),
+        into(parse_switch),
+        parse_break,
+        parse_continue,
         into(if_),
         into(parse_record),
+        into(tuple_literal),
         into(list_comprehension),
         into(list_literal),
         into(map_literal),
-        into(number_literal),
-        into(string_literal),
+        alt((
+            into(boolean_literal),
+            na_literal,
+            into(color_literal),
+            into(number_literal),
+            into(string_literal),
+        )),
         into(variable),
         delimited(sign("("), expression, sign(")")),
     ))(input)
@@ -602,6 +752,93 @@ fn if_type_branch(input: Input) -> PineResult<IfTypeBranch> {
 
 
 
+fn boolean_literal(input: Input) -> PineResult<Boolean> {
+    context(
+        "boolean",
+        map(
+            tuple((
+                position,
+                alt((value(true, keyword("true")), value(false, keyword("false")))),
+            )),
+            |(position, value)| Boolean::new(value, position()),
+        ),
+    )(input)
+}
+
+fn color_literal(input: Input) -> PineResult<Color> {
+    context(
+        "color",
+        map(token(tuple((position, color_digits))), |(position, digits)| {
+            let channel =
+                |index: usize| u8::from_str_radix(&digits[index..index + 2], 16).unwrap();
+            let alpha = match digits.len() {
+                8 => channel(6),
+                _ => 0xff,
+            };
+
+            Color::new(channel(0), channel(2), channel(4), alpha, position())
+        }),
+    )(input)
+}
+
+/// The hexadecimal digits of a `#RRGGBB` or `#RRGGBBAA` color where an expression can start; any
+/// other `#` starts a comment, so `x = 1 #facade comment` ends with a comment.
+fn color_digits(input: Input) -> PineResult<Input> {
+    let line = input.get_line_beginning();
+    let line_beginning = String::from_utf8_lossy(&line[..input.get_column() - 1]);
+
+    if !starts_expression(&line_beginning) {
+        return fail(input);
+    }
+
+    preceded(
+        char('#'),
+        terminated(
+            verify(
+                take_while1(|character: char| character.is_ascii_hexdigit()),
+                |digits: &Input| matches!(digits.len(), 6 | 8),
+            ),
+            peek(not(alt((value((), alphanumeric1), value((), char('_')))))),
+        ),
+    )(input)
+}
+
+/// Whether an expression can start after `line_beginning`, the part of its line before it: not
+/// right after an operand such as a name, a number, a string or a closing bracket.
+fn starts_expression(line_beginning: &str) -> bool {
+    !line_beginning.trim_end().ends_with(|character: char| {
+        character.is_alphanumeric() || matches!(character, '_' | '.' | ')' | ']' | '"' | '\'')
+    })
+}
+
+/// Whether `text`, which follows a `#` after `line_beginning`, is a color literal rather than a
+/// comment, by the same rule as [`color_digits`], for source that isn't parsed yet.
+pub(crate) fn is_color_literal(line_beginning: &str, text: &str) -> bool {
+    if !starts_expression(line_beginning) {
+        return false;
+    }
+
+    let rest = text.trim_start_matches(|character: char| character.is_ascii_hexdigit());
+
+    matches!(text.len() - rest.len(), 6 | 8)
+        && !rest.starts_with(|character: char| character.is_alphanumeric() || character == '_')
+}
+
+fn na_literal(input: Input) -> PineResult<Expression> {
+    context(
+        "na",
+        map(
+            token(tuple((
+                position,
+                tag("na"),
+                peek(not(alt((value((), alphanumeric1), value((), char('_')))))),
+                not(sign("(")),
+            ))),
+            |(position, ..)| Expression::Na(position()),
+        ),
+    )(input)
+}
+
 fn number_literal(input: Input) -> PineResult<Number> {
     context(
         "number",
@@ -646,50 +883,63 @@ fn decimal_literal(input: Input) -> PineResult<NumberRepresentation> {
     context(
         "decimal literal",
         map(recognize_float, |characters: Input| {
-            NumberRepresentation::FloatingPoint(
-                str::from_utf8(characters.as_bytes()).unwrap().into(),
-            )
+            let digits: String = str::from_utf8(characters.as_bytes()).unwrap().into();
+
+            if digits.contains(['.', 'e', 'E']) {
+                NumberRepresentation::FloatingPoint(digits)
+            } else {
+                NumberRepresentation::Integer(digits)
+            }
         }),
     )(input)
 }
 
-fn string_literal(input: Input) -> PineResult<ByteString> {
+pub(crate) fn string_literal(input: Input) -> PineResult<ByteString> {
     context("string", token(raw_string_literal))(input)
 }
 
 fn raw_string_literal(input: Input) -> PineResult<ByteString> {
     map(
-        tuple((
-            position,
-            preceded(
-                char('"'),
-                cut(terminated(
-                    many0(alt((
-                        recognize(none_of("\\\"")),
-                        tag("\\\\"),
-                        tag("\\\""),
-                        tag("\\n"),
-                        tag("\\r"),
-                        tag("\\t"),
-                        recognize(tuple((tag("\\x"), count(hexadecimal_digit, 2)))),
-                    ))),
-                    char('"'),
-                )),
-            ),
-        )),
-        |(position, spans)| {
-            ByteString::new(
-                spans
-                    .iter()
-                    .map(|span| str::from_utf8(span.as_bytes()).unwrap())
-                    .collect::<Vec<_>>()
-                    .concat(),
-                position(),
-            )
-        },
+        tuple((position, alt((quoted('"'), quoted('\''))))),
+        |(position, characters)| ByteString::new(String::from_iter(characters), position()),
     )(input)
 }
 
+/// A string between `quote`s, with its escape sequences decoded.
+fn quoted(quote: char) -> impl FnMut(Input) -> PineResult<Vec<char>> {
+    move |input| {
+        preceded(
+            char(quote),
+            cut(terminated(
+                many0(alt((
+                    verify(anychar, |character| *character != '\\' && *character != quote),
+                    preceded(char('\\'), escape_sequence),
+                ))),
+                char(quote),
+            )),
+        )(input)
+    }
+}
+
+fn escape_sequence(input: Input) -> PineResult<char> {
+    alt((
+        value('\\', char('\\')),
+        value('"', char('"')),
+        value('\'', char('\'')),
+        value('\n', char('n')),
+        value('\r', char('r')),
+        value('\t', char('t')),
+        map_opt(
+            preceded(char('x'), recognize(count(hexadecimal_digit, 2))),
+            |digits: Input| u8::from_str_radix(&digits, 16).ok().map(char::from),
+        ),
+        map_opt(
+            delimited(tag("u{"), recognize(many1(hexadecimal_digit)), char('}')),
+            |digits: Input| u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32),
+        ),
+    ))(input)
+}
+
 fn list_literal(input: Input) -> PineResult<List> {
     context(
         "list",
@@ -708,6 +958,30 @@ fn list_literal(input: Input) -> PineResult<List> {
     )(input)
 }
 
+/// `[a, b, ...]`. A list literal starts with its element type followed by a space (`[int 1, 2]`),
+/// which is what tells the two apart.
+fn tuple_literal(input: Input) -> PineResult<Tuple> {
+    context(
+        "tuple",
+        map(
+            tuple((
+                position,
+                not(tuple((sign("["), type_, space1, list_element))),
+                sign("["),
+                expression,
+                many1(preceded(sign(","), expression)),
+                opt(sign(",")),
+                sign("]"),
+            )),
+            |(position, _, _, first, mut rest, _, _)| {
+                rest.insert(0, first);
+
+                Tuple::new(rest, position())
+            },
+        ),
+    )(input)
+}
+
 fn list_element(input: Input) -> PineResult<ListElement> {
     alt((
         map(
@@ -864,15 +1138,20 @@ pub fn comment(input: Input) -> PineResult<Comment> {
     context(
         "comment",
         map(
-            tuple((comment_position, tag("#"), many0(none_of("\n\r")))),
-            |(position, _, characters)| Comment::new(String::from_iter(characters), position),
+            tuple((
+                comment_position,
+                not(color_digits),
+                tag("#"),
+                many0(none_of("\n\r")),
+            )),
+            |(position, _, _, characters)| Comment::new(String::from_iter(characters), position),
         ),
     )(input)
 }
 
 // Optimize comment parsing by skipping contents.
 fn skipped_comment(input: Input) -> PineResult<()> {
-    value((), pair(tag("#"), many0_count(none_of("\n\r"))))(input)
+    value((), tuple((not(color_digits), tag("#"), many0_count(none_of("\n\r")))))(input)
 }
 
 fn comment_position(input: Input) -> PineResult<Position> {
@@ -889,4 +1168,186 @@ pub fn position(input: Input) -> PineResult<impl Fn() -> Position + '_> {
 
     // 使用闭包包裹 Position 返回
     Ok((input.clone(), move || input::position(input.clone())))
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{input, Span};
+    use nom::bytes::complete::take;
+
+    #[test]
+    fn span_from_position_is_at_its_byte_offset() {
+        let (rest, _) =
+            take::<_, _, nom::error::VerboseError<Input>>(6usize)(input("x = 1\n  y")).unwrap();
+        let (_, position) = position(rest).unwrap();
+        let span = Span::from(&position());
+
+        assert_eq!((span.start, span.end, span.line, span.column), (8, 9, 2, 3));
+    }
+
+    #[test]
+    fn parse_conditional_below_binary_operators() {
+        let (_, expression) = expression(input("x > 1 | y ? a + 1 : b")).unwrap();
+
+        match expression {
+            Expression::Conditional(conditional) => {
+                assert!(matches!(conditional.condition(), Expression::BinaryOperation(_)));
+                assert!(matches!(conditional.then(), Expression::BinaryOperation(_)));
+            }
+            expression => panic!("expected conditional, got {expression:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_try_suffix_beside_conditional() {
+        let (_, try_) = expression(input("f(x)?")).unwrap();
+
+        assert!(matches!(
+            try_,
+            Expression::UnaryOperation(operation) if operation.operator() == UnaryOperator::Try
+        ));
+
+        let (_, expression) = expression(input("f(x)? ? a : b")).unwrap();
+
+        match expression {
+            Expression::Conditional(conditional) => {
+                assert!(matches!(conditional.condition(), Expression::UnaryOperation(_)));
+            }
+            expression => panic!("expected conditional, got {expression:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_nested_conditional_to_the_right() {
+        let (_, expression) = expression(input("a ? b : c ? d : e")).unwrap();
+
+        match expression {
+            Expression::Conditional(conditional) => {
+                assert!(matches!(conditional.then(), Expression::Variable(_)));
+                assert!(matches!(conditional.else_(), Expression::Conditional(_)));
+            }
+            expression => panic!("expected conditional, got {expression:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_tuple_and_typed_list() {
+        let (_, tuple) = expression(input("[f(x), y * 2]")).unwrap();
+        let (_, list) = expression(input("[int 1, 2]")).unwrap();
+
+        assert!(matches!(tuple, Expression::Tuple(tuple) if tuple.elements().len() == 2));
+        assert!(matches!(list, Expression::List(_)));
+    }
+
+    #[test]
+    fn parse_member_call_with_type_argument() {
+        let (_, call) = expression(input("array.new<float>(3, 0.0)")).unwrap();
+        let (_, comparison) = expression(input("a.b < c")).unwrap();
+
+        assert!(matches!(call, Expression::Call(call) if call.arguments().len() == 2));
+        assert!(matches!(comparison, Expression::BinaryOperation(_)));
+    }
+
+    #[test]
+    fn parse_call_with_named_arguments() {
+        let (_, call) =
+            expression(input("input.int(14, \"Length\", minval=1, maxval = 100)")).unwrap();
+        let (_, comparison) = expression(input("f(a == b)")).unwrap();
+
+        match call {
+            Expression::Call(call) => {
+                assert_eq!(call.arguments().len(), 2);
+                assert_eq!(
+                    call.named_arguments()
+                        .iter()
+                        .map(NamedArgument::name)
+                        .collect::<Vec<_>>(),
+                    ["minval", "maxval"]
+                );
+            }
+            expression => panic!("call expected, found {expression:?}"),
+        }
+        assert!(matches!(
+            comparison,
+            Expression::Call(call) if call.arguments().len() == 1 && call.named_arguments().is_empty()
+        ));
+        assert!(expression(input("f(title = \"x\", 1)")).is_err());
+    }
+
+    #[test]
+    fn parse_color_literals() {
+        let (_, opaque) = color_literal(input("#FF5252")).unwrap();
+        let (_, transparent) = color_literal(input("#2196f380")).unwrap();
+
+        assert_eq!(
+            (opaque.red(), opaque.green(), opaque.blue(), opaque.alpha()),
+            (0xff, 0x52, 0x52, 0xff)
+        );
+        assert_eq!(transparent.alpha(), 0x80);
+        assert!(color_literal(input("#FFF")).is_err());
+        assert!(color_literal(input("#FF5252A")).is_err());
+        assert!(color_literal(input("#FF5252 ")).is_ok());
+        assert!(blank(input("# FF5252 is red")).unwrap().0.is_empty());
+        assert!(!blank(input("#FF5252")).unwrap().0.is_empty());
+    }
+
+    #[test]
+    fn parse_string_escape_sequences() {
+        for (source, value) in [
+            (r#""a\tb\n""#, "a\tb\n"),
+            (r#""say \"hi\"""#, "say \"hi\""),
+            (r#"'it\'s'"#, "it's"),
+            (r#"'"quoted"'"#, "\"quoted\""),
+            (r#""\x41\u{e9}\\""#, "A\u{e9}\\"),
+        ] {
+            let (_, string) = string_literal(input(source)).unwrap();
+
+            assert_eq!(string.value(), value, "{source}");
+        }
+
+        assert!(string_literal(input(r#""\q""#)).is_err());
+    }
+
+    #[test]
+    fn parse_int_and_float_literals() {
+        for (source, representation) in [
+            ("1", NumberRepresentation::Integer("1".into())),
+            ("1.0", NumberRepresentation::FloatingPoint("1.0".into())),
+            ("1e-3", NumberRepresentation::FloatingPoint("1e-3".into())),
+            ("0x1F", NumberRepresentation::Hexadecimal("1f".into())),
+        ] {
+            let (_, number) = number_literal(input(source)).unwrap();
+
+            assert_eq!(number.value(), &representation, "{source}");
+        }
+    }
+
+    #[test]
+    fn parse_na_value_and_na_call() {
+        let (_, value) = expression(input("na + 1")).unwrap();
+        let (_, call) = expression(input("na(x)")).unwrap();
+        let (_, name) = expression(input("nan")).unwrap();
+
+        assert!(matches!(
+            value,
+            Expression::BinaryOperation(operation) if matches!(operation.lhs(), Expression::Na(_))
+        ));
+        assert!(matches!(call, Expression::Call(_)));
+        assert!(matches!(name, Expression::Variable(_)));
+    }
+
+    #[test]
+    fn parse_member_call_with_type_arguments() {
+        let (_, call) = expression(input("map.new<string, float>()")).unwrap();
+
+        let Expression::Call(call) = call else {
+            panic!("expected a call");
+        };
+
+        assert!(matches!(
+            call.function(),
+            Expression::RecordDeconstruction(member)
+                if member.type_arguments() == [DataType::String, DataType::Float]
+        ));
+    }
+}
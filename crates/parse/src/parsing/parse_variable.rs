@@ -1,13 +1,16 @@
 use std::fs;
 use std::path::PathBuf;
+use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::multispace0;
 use nom::combinator::{map, opt};
+use nom::multi::separated_list1;
 use nom::error::context;
-use nom::sequence::{preceded, tuple};
-use ast::{FunctionDefinition, Variable, VariableDefinition};
+use nom::character::complete::space1;
+use nom::sequence::{pair, preceded, terminated, tuple};
+use ast::{TupleDefinition, VariableDefinition};
 use crate::input::{Input, input, spaced};
-use crate::{expression, PineResult};
+use crate::{expression, sign, PineResult};
 use crate::parse_util::{position, token, type_alias};
 use crate::parsing::parse_data_type::parse_type;
 use crate::parsing::parse_declaration_mode::parse_declaration_mode;
@@ -27,20 +30,59 @@ pub fn parse_variable(input: Input) -> PineResult<VariableDefinition> {
                 position,
                 opt(preceded(
                     multispace0,
-                    parse_declaration_mode,
-                    //alt(( const_tag,var_tag,varip_tag)),
-                    //alt((parse_declaration_mode, map(tag(""), |_| None))),
+                    terminated(parse_declaration_mode, space1),
                 )),
-                opt(preceded(multispace0, parse_type)),
-                preceded(multispace0, parse_identifier),
-                opt(preceded(spaced(tag("=")), expression)),
-            )),|(position,declaration_mode, var_type, identifier, value)| {
-                VariableDefinition::new(declaration_mode, var_type, identifier, value.unwrap(), position())
+                // A type name such as `label` is also a valid identifier.
+                preceded(
+                    multispace0,
+                    alt((
+                        pair(map(terminated(parse_type, space1), Some), parse_identifier),
+                        map(parse_identifier, |identifier| (None, identifier)),
+                    )),
+                ),
+                preceded(spaced(tag("=")), expression),
+            )),|(position,declaration_mode, (var_type, identifier), value)| {
+                VariableDefinition::new(declaration_mode, var_type, identifier, value, position())
             },
         ),
     )(input)
 }
 
+/**
+[<identifier>, <identifier>, ...] = <expression>
+ */
+pub fn parse_tuple_definition(input: Input) -> PineResult<TupleDefinition> {
+    context(
+        "tupleDefinition",
+        map(
+            tuple((
+                position,
+                sign("["),
+                separated_list1(sign(","), parse_identifier),
+                sign("]"),
+                sign("="),
+                expression,
+            )),
+            |(position, _, names, _, _, value)| TupleDefinition::new(names, value, position()),
+        ),
+    )(input)
+}
+
+#[test]
+fn parse_tuple_definition_with_call() {
+    let (_, definition) =
+        parse_tuple_definition(input("[macdLine, signal, hist] = ta.macd(close, 12, 26, 9)"))
+            .unwrap();
+
+    let names = definition
+        .names()
+        .iter()
+        .map(|identifier| identifier.name.as_str())
+        .collect::<Vec<_>>();
+
+    assert_eq!(names, ["macdLine", "signal", "hist"]);
+}
+
 #[test]
 fn main() {
     let script = "var int a= 3 + 4";
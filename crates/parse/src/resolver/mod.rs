@@ -0,0 +1,489 @@
+use crate::parser::{Diagnostic, ParsingCtx, Root};
+
+mod resolve_ctx;
+
+pub use resolve_ctx::*;
+
+/// Checks that every `:=`/`+=`-style reassignment targets a variable that is already declared
+/// in an enclosing scope, and that tuple destructuring matches the arity of the tuple when it
+/// is known statically. Fields and methods used on values of a user-defined type are checked
+/// against its declaration, and switches over an enum that miss some of its members are reported
+/// as warnings. `receiver.name(...)` calls are dispatched from the static type of the receiver
+/// into `Root.method_calls`.
+pub fn resolve(root: &mut Root, parsing_ctx: &mut ParsingCtx) -> Result<(), Diagnostic> {
+    let mut resolve_ctx = ResolveCtx::new(parsing_ctx);
+
+    resolve_ctx.run(root);
+    root.method_calls.extend(resolve_ctx.method_calls);
+
+    parsing_ctx.return_if_error()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ast::datatype::DataType;
+    use crate::parser::{self, config::{Config, Overload}, DiagnosticKind, DiagnosticType, SourceFile};
+    use crate::test::TempDir;
+
+    fn resolve_ctx(content: &str) -> (ParsingCtx, Result<(), Diagnostic>) {
+        resolve_with_config(
+            Config {
+                quiet: true,
+                ..Config::default()
+            },
+            content,
+        )
+    }
+
+    fn resolve_with_config(config: Config, content: &str) -> (ParsingCtx, Result<(), Diagnostic>) {
+        let mut parsing_ctx = ParsingCtx::new(&config);
+
+        parsing_ctx.add_file(&SourceFile::from_str("root.ps", content).unwrap());
+
+        let result = parser::parse(&mut parsing_ctx)
+            .and_then(|mut root| resolve(&mut root, &mut parsing_ctx));
+
+        (parsing_ctx, result)
+    }
+
+    fn resolve_str(content: &str) -> Result<(), Diagnostic> {
+        resolve_ctx(content).1
+    }
+
+    /// Resolves `content` with `libraries`, as `(owner/name/version, source)`, in a library
+    /// directory of its own.
+    fn resolve_with_libraries(
+        test: &str,
+        libraries: &[(&str, &str)],
+        content: &str,
+    ) -> Result<(), Diagnostic> {
+        libraries_ctx(test, libraries, content).1
+    }
+
+    fn libraries_ctx(
+        test: &str,
+        libraries: &[(&str, &str)],
+        content: &str,
+    ) -> (ParsingCtx, Result<(), Diagnostic>) {
+        let libraries: Vec<_> = libraries
+            .iter()
+            .map(|(path, source)| (format!("{path}.ps"), *source))
+            .collect();
+        let library_dir = TempDir::new(&format!("libraries-{test}"), &libraries);
+
+        let config = Config {
+            quiet: true,
+            library_dir: library_dir.path().to_path_buf(),
+            ..Config::default()
+        };
+        let mut parsing_ctx = ParsingCtx::new(&config);
+
+        parsing_ctx.add_file(&SourceFile::from_str("root.ps", content).unwrap());
+
+        let result = parser::parse(&mut parsing_ctx)
+            .and_then(|mut root| resolve(&mut root, &mut parsing_ctx));
+
+        (parsing_ctx, result)
+    }
+
+    fn warnings(content: &str) -> Vec<DiagnosticKind> {
+        let (parsing_ctx, result) = resolve_ctx(content);
+
+        assert!(result.is_ok());
+
+        parsing_ctx
+            .diagnostics
+            .list
+            .iter()
+            .zip(&parsing_ctx.diagnostics.list_types)
+            .filter(|(_, ty)| matches!(ty, DiagnosticType::Warning))
+            .map(|(diagnostic, _)| diagnostic.get_kind())
+            .collect()
+    }
+
+    const PIVOT: &str = "type Pivot\n    float price = 0.0\n    int bar\n";
+
+    const SIGNAL: &str = "enum Signal\n    buy = \"Buy\"\n    sell = \"Sell\"\n    neutral\n";
+
+    /// Resolves `script` after `prelude`, one of the declarations above or nothing.
+    fn resolve_after(prelude: &str, script: &str) -> Result<(), Diagnostic> {
+        resolve_str(&format!("{prelude}{script}"))
+    }
+
+    #[test]
+    fn accepted_scripts() {
+        for (prelude, script) in [
+            ("", "x = 1\nif x > 0\n    x := 2\nfor i = 0 to 3\n    x += i\n"),
+            ("", "pair(x) => [x, x * 2]\n[a, b] = pair(1)\na := b\n"),
+            ("", "f(a) =>\n    a += 1\n    a\n"),
+            (
+                PIVOT,
+                "method bump(Pivot this, float amount) =>\n    this.price += amount\np = Pivot.new(1.0, 2)\nmoved = p.bump(1.0)\nq = p.copy()\nq.bar := p.bar\n",
+            ),
+            (PIVOT, "p = Pivot.new(bar = 2, price = 1.0)\n"),
+            (
+                SIGNAL,
+                "method flip(Signal this) =>\n    this == Signal.buy ? Signal.sell : Signal.buy\ns = Signal.buy\nt = s.flip()\n",
+            ),
+            (
+                "",
+                "m = map.new<string, float>()\nm.put(\"a\", 1)\nmap.put(m, \"b\", 2.5)\nn = m.copy()\nx = n.get(\"a\")\nf(map<string, float> prices) =>\n    prices.contains(\"a\")\n",
+            ),
+            // Literals dividing exactly stay ints.
+            ("", "m = map.new<string, int>()\nm.put(\"x\", 4 / 2)\n"),
+            (
+                "",
+                "strategy(\"S\", \"s\", true, initial_capital = 5000, commission_value = 0.1)\n",
+            ),
+            ("", "//@version=6\nx = 1\n"),
+        ] {
+            assert!(resolve_after(prelude, script).is_ok(), "{prelude}{script}");
+        }
+    }
+
+    /// Each script is rejected with the diagnostic at the line, counted from after the
+    /// prelude, and column given.
+    #[test]
+    fn rejected_scripts() {
+        use DiagnosticKind::*;
+
+        let int_division = "m = map.new<string, int>()\na = 4\nb = 2\n";
+
+        for (prelude, script, kind, line, column) in [
+            ("", "x = 1\ny := x\n", UndeclaredAssignment("y".into()), 2, 1),
+            ("", "if true\n    x = 1\nx := 2\n", UndeclaredAssignment("x".into()), 3, 1),
+            ("", "pair(x) => [x, x * 2]\n[a, b, c] = pair(1)\n", TupleArityMismatch(2, 3), 2, 1),
+            (
+                PIVOT,
+                "p = Pivot.new()\nx = p.volume\n",
+                UnknownField("Pivot".into(), "volume".into()),
+                2,
+                6,
+            ),
+            (
+                PIVOT,
+                "method reset(Pivot this) =>\n    this.time := 0\n",
+                UnknownField("Pivot".into(), "time".into()),
+                2,
+                5,
+            ),
+            (PIVOT, "p = Pivot.new(1.0, 2, 3)\n", ConstructorArity("Pivot".into(), 2, 3), 1, 14),
+            (
+                PIVOT,
+                "p = Pivot.new(\"x\")\n",
+                TypeMismatch("float".into(), "string".into()),
+                1,
+                15,
+            ),
+            (
+                PIVOT,
+                "p = Pivot.new()\np.bar := 1.5\n",
+                TypeMismatch("int".into(), "float".into()),
+                2,
+                10,
+            ),
+            (
+                PIVOT,
+                "p = Pivot.new(1.0, bar = \"x\")\n",
+                TypeMismatch("int".into(), "string".into()),
+                1,
+                26,
+            ),
+            (
+                PIVOT,
+                "p = Pivot.new(volume = 1)\n",
+                UnknownField("Pivot".into(), "volume".into()),
+                1,
+                15,
+            ),
+            (
+                PIVOT,
+                "p = Pivot.new()\nq = p.bump(1.0)\n",
+                UnknownMethod("Pivot".into(), "bump".into()),
+                2,
+                11,
+            ),
+            // A plain function is not a method.
+            (
+                PIVOT,
+                "bump(p, amount) => amount\np = Pivot.new()\nq = p.bump(1.0)\n",
+                UnknownMethod("Pivot".into(), "bump".into()),
+                3,
+                11,
+            ),
+            (SIGNAL, "s = Signal.hold\n", UnknownField("Signal".into(), "hold".into()), 1, 11),
+            (
+                "",
+                "m = map.new<string, float>()\nmap.put(m, \"a\", \"b\")\n",
+                TypeMismatch("float".into(), "string".into()),
+                2,
+                17,
+            ),
+            (
+                "",
+                "m = map.new<string, float>()\ns = \"b\"\nm.put(\"a\", s)\n",
+                TypeMismatch("float".into(), "string".into()),
+                3,
+                12,
+            ),
+            (
+                "",
+                "m = map.new<string, float>()\nx = m.get(1)\n",
+                TypeMismatch("string".into(), "int".into()),
+                2,
+                11,
+            ),
+            (
+                "",
+                "map<string, float> m = map.new<int, float>()\n",
+                TypeMismatch("map<string, float>".into(), "map<int, float>".into()),
+                1,
+                43,
+            ),
+            // Int division is a float unless literals divide exactly.
+            (
+                int_division,
+                "m.put(\"x\", 5 / 2)\n",
+                TypeMismatch("int".into(), "float".into()),
+                1,
+                14,
+            ),
+            (
+                int_division,
+                "m.put(\"x\", a / b)\n",
+                TypeMismatch("int".into(), "float".into()),
+                1,
+                14,
+            ),
+            (
+                int_division,
+                "m.put(\"x\", a / 2)\n",
+                TypeMismatch("int".into(), "float".into()),
+                1,
+                14,
+            ),
+            (
+                "",
+                "indicator(\"I\", initial_capital = 5000)\n",
+                UnknownDeclarationArgument("indicator".into(), "initial_capital".into()),
+                1,
+                16,
+            ),
+            (
+                "",
+                "library(\"L\", true, overlay = false)\n",
+                DuplicateDeclarationArgument("library".into(), "overlay".into()),
+                1,
+                20,
+            ),
+            (
+                "",
+                "library(\"L\", true, true, true)\n",
+                DeclarationArity("library".into(), 3, 4),
+                1,
+                1,
+            ),
+            (
+                "",
+                "indicator(\"A\")\nstrategy(\"B\")\n",
+                DuplicateDeclaration("strategy".into()),
+                2,
+                1,
+            ),
+            ("", "//@version=4\nx = 1\n", UnsupportedVersion("4".into()), 1, 1),
+            ("", "//@version=six\nx = 1\n", UnsupportedVersion("six".into()), 1, 1),
+        ] {
+            let diagnostic = resolve_after(prelude, script).unwrap_err();
+            let line = line + prelude.lines().count() as u32;
+
+            assert_eq!(
+                (diagnostic.get_kind(), diagnostic.span.line, diagnostic.span.column),
+                (kind, line, column),
+                "{prelude}{script}"
+            );
+        }
+    }
+
+    #[test]
+    fn destructure_builtin_returning_tuple() {
+        // The runtime declares these, see `Runtime::overloads`.
+        let resolve = |content| {
+            let bands = vec![Overload {
+                parameters: vec![],
+                returns: DataType::Tuple(vec![DataType::Float; 3]),
+            }];
+            let config = Config {
+                quiet: true,
+                overloads: [
+                    ("ta.macd".to_string(), bands.clone()),
+                    ("ta.bb".to_string(), bands),
+                ]
+                .into(),
+                ..Config::default()
+            };
+
+            resolve_with_config(config, content).1
+        };
+
+        assert!(resolve("[line, signal, hist] = ta.macd(close, 12, 26, 9)\n").is_ok());
+
+        let diagnostic = resolve("[upper, lower] = ta.bb(close, 20, 2.0)\n").unwrap_err();
+
+        assert_eq!(diagnostic.get_kind(), DiagnosticKind::TupleArityMismatch(3, 2));
+        assert_eq!((diagnostic.span.line, diagnostic.span.column), (1, 1));
+        assert!(resolve("ta = 1\n[upper, lower] = ta.bb(close, 20, 2.0)\n").is_ok());
+        // Without the runtime's declarations, nothing is known about the result.
+        assert!(resolve_str("[upper, lower] = ta.bb(close, 20, 2.0)\n").is_ok());
+    }
+
+    #[test]
+    fn exhaustive_enum_switch() {
+        let script = format!(
+            "{SIGNAL}s = Signal.buy\nx = switch s\n    Signal.buy => 1\n    Signal.sell => -1\n    Signal.neutral => 0\ny = switch s\n    Signal.buy => 1\n    => 0\n"
+        );
+
+        assert!(warnings(&script).is_empty());
+    }
+
+    #[test]
+    fn non_exhaustive_enum_switch_warns() {
+        let script = format!(
+            "{SIGNAL}f(Signal s) =>\n    switch s\n        Signal.buy => 1\n        Signal.sell => -1\n"
+        );
+
+        assert!(matches!(
+            warnings(&script).as_slice(),
+            [DiagnosticKind::NonExhaustiveSwitch(ty, missing)]
+                if ty == "Signal" && missing == &["neutral".to_string()]
+        ));
+    }
+
+    const GEOMETRY: &str = "library(\"Geometry\")\nexport type Point\n    float x\n    float y\nsquare(x) => x * x\nexport norm(Point p) => math.sqrt(square(p.x) + square(p.y))\n";
+
+    #[test]
+    fn import_library_exports() {
+        let libraries = [("alice/Geometry/1", GEOMETRY)];
+
+        assert!(resolve_with_libraries(
+            "exports",
+            &libraries,
+            "import alice/Geometry/1 as geo\nn = geo.norm(geo.Point.new(3.0, 4.0))\n",
+        )
+        .is_ok());
+
+        let diagnostic = resolve_with_libraries(
+            "missing-export",
+            &libraries,
+            "import alice/Geometry/1\nn = Geometry.square(2)\n",
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            diagnostic.get_kind(),
+            DiagnosticKind::MissingExport(library, name)
+                if library == "alice/Geometry/1" && name == "square"
+        ));
+    }
+
+    #[test]
+    fn import_missing_library_or_version() {
+        let libraries = [("alice/Geometry/1", GEOMETRY), ("alice/Geometry/3", GEOMETRY)];
+        let diagnostic =
+            resolve_with_libraries("version", &libraries, "import alice/Geometry/2 as geo\n")
+                .unwrap_err();
+
+        assert!(matches!(
+            diagnostic.get_kind(),
+            DiagnosticKind::LibraryVersionNotFound(library, 2, available)
+                if library == "alice/Geometry" && available == [1, 3]
+        ));
+
+        let diagnostic =
+            resolve_with_libraries("missing", &libraries, "import bob/Geometry/1 as geo\n")
+                .unwrap_err();
+
+        assert!(matches!(
+            diagnostic.get_kind(),
+            DiagnosticKind::LibraryNotFound(library, _) if library == "bob/Geometry/1"
+        ));
+    }
+
+    #[test]
+    fn import_script_that_is_not_a_library() {
+        let diagnostic = resolve_with_libraries(
+            "not-a-library",
+            &[("alice/Trend/1", "indicator(\"Trend\")\nx = 1\n")],
+            "import alice/Trend/1 as trend\n",
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            diagnostic.get_kind(),
+            DiagnosticKind::NotALibrary(library) if library == "alice/Trend/1"
+        ));
+    }
+
+    #[test]
+    fn export_outside_library() {
+        let diagnostic = resolve_str("indicator(\"I\")\nexport f(x) => x\n").unwrap_err();
+
+        assert!(matches!(
+            diagnostic.get_kind(),
+            DiagnosticKind::ExportOutsideLibrary(name) if name == "f"
+        ));
+    }
+
+    #[test]
+    fn library_imported_twice_is_loaded_once() {
+        let signals = format!(
+            "library(\"Signals\")\n{SIGNAL}export f(Signal s) =>\n    switch s\n        Signal.buy => 1\n"
+        );
+        let importer = |name: &str| format!("library(\"{name}\")\nimport alice/Signals/1 as s\n");
+        let (parsing_ctx, result) = libraries_ctx(
+            "loaded-once",
+            &[
+                ("alice/A/1", &importer("A")),
+                ("alice/B/1", &importer("B")),
+                ("alice/Signals/1", &signals),
+            ],
+            "import alice/A/1 as a\nimport alice/B/1 as b\n",
+        );
+
+        assert!(result.is_ok());
+
+        // The warnings of the library are reported once.
+        assert_eq!(
+            parsing_ctx
+                .diagnostics
+                .list
+                .iter()
+                .filter(|diagnostic| matches!(
+                    diagnostic.get_kind(),
+                    DiagnosticKind::NonExhaustiveSwitch(..)
+                ))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn libraries_importing_each_other() {
+        let diagnostic = resolve_with_libraries(
+            "cycle",
+            &[
+                ("alice/A/1", "library(\"A\")\nimport alice/B/1 as b\n"),
+                ("alice/B/1", "library(\"B\")\nimport alice/A/1 as a\n"),
+            ],
+            "import alice/A/1 as a\n",
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            diagnostic.get_kind(),
+            DiagnosticKind::ImportCycle(chain)
+                if chain == ["alice/A/1", "alice/B/1", "alice/A/1"]
+        ));
+    }
+
+}
@@ -0,0 +1,1208 @@
+use std::collections::HashMap;
+
+use ast::datatype::DataType;
+use ast::{
+    Annotation, AnnotationKind, Assignment, BinaryOperator, Body, Call, Declaration, Else, EnumDefinition, Expression, For, FunctionDecl, IfDecl,
+    Import, LibraryModulePath, ListElement, Mod, ModulePath, NamedArgument, NumberRepresentation, Statement, Switch, TopLevel, TupleDefinition,
+    UserTypeDefinition, While,
+};
+
+use position::Position;
+
+use crate::input::Span;
+use crate::parser::scopes::Scopes;
+use crate::parser::{call_site, CallSite, Diagnostic, MethodCall, ParsingCtx, Root};
+
+/// What is known statically about a declared name.
+#[derive(Debug, Clone)]
+pub struct Binding {
+    /// Type of the value, a user-defined type or enum being a `DataType::Custom`.
+    pub ty: Option<DataType>,
+    /// Number of values returned by a function whose body ends with a tuple literal.
+    pub tuple_arity: Option<usize>,
+}
+
+/// Walks the tree in source order, keeping track of the names declared in each block.
+#[derive(Debug)]
+pub struct ResolveCtx<'a> {
+    pub parsing_ctx: &'a mut ParsingCtx,
+    pub scopes: Scopes<String, Binding>,
+    /// Fields of every user-defined type, in declaration order.
+    pub user_types: HashMap<String, Vec<(String, DataType)>>,
+    /// Names of the methods declared for each user-defined type or enum.
+    pub methods: HashMap<String, Vec<String>>,
+    /// Member names of every enum, in declaration order.
+    pub enums: HashMap<String, Vec<String>>,
+    /// Whether the script's declaration statement was met.
+    pub declared: bool,
+    /// Names exported by every library the script loads.
+    pub library_exports: HashMap<LibraryModulePath, Vec<String>>,
+    /// Library imported under each alias.
+    pub imports: HashMap<String, LibraryModulePath>,
+    /// Receiver type and name of the methods every library the script loads exports.
+    pub library_methods: HashMap<LibraryModulePath, Vec<(String, String)>>,
+    /// How each `receiver.name(...)` call is dispatched, when the receiver's type is known.
+    pub method_calls: HashMap<CallSite, MethodCall>,
+}
+
+impl<'a> ResolveCtx<'a> {
+    pub fn new(parsing_ctx: &'a mut ParsingCtx) -> Self {
+        Self {
+            parsing_ctx,
+            scopes: Scopes::new(),
+            user_types: HashMap::new(),
+            methods: HashMap::new(),
+            enums: HashMap::new(),
+            declared: false,
+            library_exports: HashMap::new(),
+            imports: HashMap::new(),
+            library_methods: HashMap::new(),
+            method_calls: HashMap::new(),
+        }
+    }
+
+    pub fn run(&mut self, root: &Root) {
+        self.library_exports = root
+            .libraries
+            .iter()
+            .map(|(path, library)| {
+                let exports = library.exports().map(|(name, _)| name.to_string()).collect();
+
+                (path.clone(), exports)
+            })
+            .collect();
+        self.library_methods = root
+            .libraries
+            .iter()
+            .map(|(path, library)| {
+                let methods = library
+                    .top_levels
+                    .iter()
+                    .filter_map(|top_level| match top_level {
+                        TopLevel::Method(method) if method.exported => Some((
+                            type_name(method.arguments.first()?.data_type.as_ref()?),
+                            method.name.name.clone(),
+                        )),
+                        _ => None,
+                    })
+                    .collect();
+
+                (path.clone(), methods)
+            })
+            .collect();
+
+        if !root.r#mod.is_library() {
+            for (name, position) in root.r#mod.exports() {
+                self.parsing_ctx
+                    .diagnostics
+                    .push_error(Diagnostic::new_export_outside_library(
+                        Span::from(position),
+                        name.to_string(),
+                    ));
+            }
+        }
+
+        self.resolve_mod(&root.r#mod);
+    }
+
+    pub fn add_to_current_scope(&mut self, name: String) {
+        self.add_typed_to_current_scope(name, None);
+    }
+
+    pub fn add_typed_to_current_scope(&mut self, name: String, ty: Option<DataType>) {
+        let binding = Binding {
+            // A parameter of any type is no better known than an untyped one.
+            ty: ty.filter(|ty| *ty != any()),
+            tuple_arity: None,
+        };
+
+        self.scopes.add(name, binding);
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scopes.push();
+    }
+
+    pub fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Statically known type of the variable `name`, if it is declared.
+    fn variable_type(&self, name: &str) -> Option<DataType> {
+        self.scopes.get(name.to_string())?.ty
+    }
+
+    fn resolve_mod(&mut self, r#mod: &Mod) {
+        for top_level in &r#mod.top_levels {
+            self.resolve_top_level(top_level);
+        }
+    }
+
+    fn resolve_top_level(&mut self, top_level: &TopLevel) {
+        match top_level {
+            TopLevel::Variable(variable) => {
+                self.resolve_expression(variable.value());
+
+                let ty = self.declared_type(variable.var_type(), variable.value());
+
+                self.add_typed_to_current_scope(variable.identifier().name.clone(), ty);
+            }
+            TopLevel::Assignment(assignment) => {
+                self.resolve_expression(&Expression::Assignment(assignment.clone()))
+            }
+            TopLevel::TupleDefinition(definition) => self.resolve_tuple_definition(definition),
+            TopLevel::Function(function) => self.resolve_function(function),
+            TopLevel::Method(method) => {
+                if let Some(receiver) = method
+                    .arguments
+                    .first()
+                    .and_then(|argument| argument.data_type.as_ref())
+                {
+                    self.methods
+                        .entry(type_name(receiver))
+                        .or_default()
+                        .push(method.name.name.clone());
+                }
+
+                self.resolve_function(method)
+            }
+            TopLevel::Type(definition) => self.resolve_user_type(definition),
+            TopLevel::Enum(definition) => self.resolve_enum(definition),
+            TopLevel::Declaration(declaration) => self.resolve_declaration(declaration),
+            TopLevel::Annotation(annotation) => self.resolve_annotation(annotation),
+            TopLevel::If(if_) => self.resolve_if(if_),
+            TopLevel::While(while_) => self.resolve_while(while_),
+            TopLevel::For(for_) => self.resolve_for(for_),
+            TopLevel::Switch(switch) => self.resolve_switch(switch),
+            TopLevel::Expression(expression) => self.resolve_expression(expression),
+            TopLevel::Mod(_, r#mod, _) => {
+                self.push_scope();
+                self.resolve_mod(r#mod);
+                self.pop_scope();
+            }
+            TopLevel::Import(import) => self.resolve_import(import),
+            TopLevel::Comment(_) => {}
+        }
+    }
+
+    /// Declares the alias of an imported library, and the names imported from it unqualified.
+    fn resolve_import(&mut self, import: &Import) {
+        let ModulePath::Library(path) = import.module_path() else {
+            return;
+        };
+        // A library that couldn't be loaded is reported already.
+        let Some(exports) = self.library_exports.get(path).cloned() else {
+            return;
+        };
+
+        for name in import.unqualified_names() {
+            if exports.iter().any(|export| export == name.name()) {
+                self.add_to_current_scope(name.name().to_string());
+            } else {
+                self.parsing_ctx
+                    .diagnostics
+                    .push_error(Diagnostic::new_missing_export(
+                        Span::from(name.position()),
+                        path.to_string(),
+                        name.name().to_string(),
+                    ));
+            }
+        }
+
+        // Exported methods are declared by name, so `object.method()` finds them.
+        for (receiver, name) in self.library_methods.get(path).cloned().unwrap_or_default() {
+            self.methods.entry(receiver).or_default().push(name);
+        }
+
+        let alias = import.prefix().unwrap_or(path.name()).to_string();
+
+        self.imports.insert(alias.clone(), path.clone());
+        self.add_to_current_scope(alias);
+    }
+
+    /// Checks that `library.name` refers to an export when `expression` is the alias of an
+    /// imported library, and tells whether it is one.
+    fn check_export(&mut self, expression: &Expression, name: &str, span: Span) -> bool {
+        let Expression::Variable(variable) = expression else {
+            return false;
+        };
+        let Some(path) = self.imports.get(variable.name()) else {
+            return false;
+        };
+
+        if !self.library_exports[path].iter().any(|export| export == name) {
+            self.parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_missing_export(
+                    span,
+                    path.to_string(),
+                    name.to_string(),
+                ));
+        }
+
+        true
+    }
+
+    fn resolve_function(&mut self, function: &FunctionDecl) {
+        let tuple_arity = match function.body.stmts.last().map(Statement::expression) {
+            Some(Expression::Tuple(tuple)) => Some(tuple.elements().len()),
+            _ => None,
+        };
+
+        self.scopes.add(
+            function.name.name.clone(),
+            Binding {
+                ty: None,
+                tuple_arity,
+            },
+        );
+
+        self.push_scope();
+
+        for argument in &function.arguments {
+            if let Some(default_value) = &argument.default_value {
+                self.resolve_expression(default_value);
+            }
+
+            self.add_typed_to_current_scope(argument.name.name.clone(), argument.data_type.clone());
+        }
+
+        self.resolve_body(&function.body);
+        self.pop_scope();
+    }
+
+    /// Resolves `body` in its own scope.
+    fn resolve_scoped_body(&mut self, body: &Body) {
+        self.push_scope();
+        self.resolve_body(body);
+        self.pop_scope();
+    }
+
+    fn resolve_body(&mut self, body: &Body) {
+        for statement in &body.stmts {
+            self.resolve_statement(statement);
+        }
+    }
+
+    fn resolve_statement(&mut self, statement: &Statement) {
+        self.resolve_expression(statement.expression());
+
+        if let Some(name) = statement.name() {
+            let ty = self.type_of(statement.expression());
+
+            self.add_typed_to_current_scope(name.to_string(), ty);
+        }
+    }
+
+    fn resolve_user_type(&mut self, definition: &UserTypeDefinition) {
+        for field in definition.fields() {
+            if let Some(default) = field.default() {
+                self.resolve_expression(default);
+            }
+        }
+
+        let fields = definition
+            .fields()
+            .iter()
+            .map(|field| (field.name().to_string(), field.data_type().clone()))
+            .collect();
+
+        self.user_types
+            .insert(definition.name().to_string(), fields);
+        self.add_to_current_scope(definition.name().to_string());
+    }
+
+    fn resolve_enum(&mut self, definition: &EnumDefinition) {
+        let members = definition
+            .members()
+            .iter()
+            .map(|member| member.name().to_string())
+            .collect();
+
+        self.enums.insert(definition.name().to_string(), members);
+        self.add_to_current_scope(definition.name().to_string());
+    }
+
+    fn resolve_annotation(&mut self, annotation: &Annotation) {
+        let version = match annotation.kind() {
+            AnnotationKind::Version(version) if !crate::SUPPORTED_VERSIONS.contains(version) => {
+                version.to_string()
+            }
+            AnnotationKind::Other(name, text) if name == "version" => {
+                text.trim_start_matches('=').trim().to_string()
+            }
+            _ => return,
+        };
+
+        self.parsing_ctx
+            .diagnostics
+            .push_error(Diagnostic::new_unsupported_version(
+                Span::from(annotation.position()),
+                version,
+            ));
+    }
+
+    /// A script has at most one declaration, whose arguments must each match one of its
+    /// parameters once.
+    fn resolve_declaration(&mut self, declaration: &Declaration) {
+        let kind = declaration.kind().name().to_string();
+
+        if std::mem::replace(&mut self.declared, true) {
+            self.parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_duplicate_declaration(
+                    Span::from(declaration.position()),
+                    kind.clone(),
+                ));
+        }
+
+        let parameters = declaration.kind().parameters();
+        let positional = declaration
+            .arguments()
+            .iter()
+            .filter(|argument| argument.name().is_none())
+            .count();
+
+        if positional > parameters.len() {
+            self.parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_declaration_arity(
+                    Span::from(declaration.position()),
+                    kind.clone(),
+                    parameters.len(),
+                    positional,
+                ));
+        }
+
+        let mut given = vec![];
+
+        for (parameter, argument) in declaration.parameters() {
+            self.resolve_expression(argument.value());
+
+            let Some(parameter) = parameter else {
+                continue;
+            };
+            let span = Span::from(argument.position());
+
+            if !parameters.contains(&parameter) {
+                self.parsing_ctx.diagnostics.push_error(
+                    Diagnostic::new_unknown_declaration_argument(
+                        span,
+                        kind.clone(),
+                        parameter.to_string(),
+                    ),
+                );
+            } else if given.contains(&parameter) {
+                self.parsing_ctx.diagnostics.push_error(
+                    Diagnostic::new_duplicate_declaration_argument(
+                        span,
+                        kind.clone(),
+                        parameter.to_string(),
+                    ),
+                );
+            } else {
+                given.push(parameter);
+            }
+        }
+    }
+
+    /// Name of the enum when `expression` is an enum itself, such as `Signal` in `Signal.buy`.
+    fn enum_name<'e>(&self, expression: &'e Expression) -> Option<&'e str> {
+        match expression {
+            Expression::Variable(variable) if self.enums.contains_key(variable.name()) => {
+                Some(variable.name())
+            }
+            _ => None,
+        }
+    }
+
+    /// Statically known user-defined type or enum of `expression`, if any.
+    fn user_type_of(&self, expression: &Expression) -> Option<String> {
+        match expression {
+            Expression::RecordDeconstruction(member)
+                if self.enum_name(member.expression()).is_some() =>
+            {
+                self.enum_name(member.expression()).map(str::to_string)
+            }
+            Expression::Variable(variable) => match self.variable_type(variable.name())? {
+                DataType::Custom(name) => Some(name),
+                _ => None,
+            },
+            Expression::Call(call) => match call.function() {
+                Expression::RecordDeconstruction(member) if member.name() == "new" => {
+                    match member.expression() {
+                        Expression::Variable(variable)
+                            if self.user_types.contains_key(variable.name()) =>
+                        {
+                            Some(variable.name().to_string())
+                        }
+                        // `alias.Type.new(...)`, a type exported by an imported library.
+                        Expression::RecordDeconstruction(ty) => match ty.expression() {
+                            Expression::Variable(alias)
+                                if self.imports.contains_key(alias.name()) =>
+                            {
+                                Some(ty.name().to_string())
+                            }
+                            _ => None,
+                        },
+                        _ => None,
+                    }
+                }
+                Expression::RecordDeconstruction(member) if member.name() == "copy" => {
+                    self.user_type_of(member.expression())
+                }
+                _ => None,
+            },
+            Expression::RecordDeconstruction(member) => {
+                let ty = self.user_type_of(member.expression())?;
+
+                match self.field_type(&ty, member.name())? {
+                    DataType::Custom(name) => Some(name),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn field_type(&self, ty: &str, field: &str) -> Option<DataType> {
+        self.user_types
+            .get(ty)?
+            .iter()
+            .find(|(name, _)| name == field)
+            .map(|(_, data_type)| data_type.clone())
+    }
+
+    /// Reports `field` when `ty` is a known user-defined type without such a field.
+    fn check_field(&mut self, ty: Option<String>, field: &str, span: Span) -> Option<String> {
+        let ty = ty?;
+
+        self.user_types.get(&ty)?;
+
+        match self.field_type(&ty, field) {
+            Some(DataType::Custom(name)) => Some(name),
+            Some(_) => None,
+            None => {
+                self.parsing_ctx
+                    .diagnostics
+                    .push_error(Diagnostic::new_unknown_field(span, ty, field.to_string()));
+
+                None
+            }
+        }
+    }
+
+    /// Decides how `receiver.name(...)` is dispatched from the static type of the receiver: to
+    /// the `method` declared for the type, else to the built-in namespace of the type. A
+    /// user-defined type or enum without such a method is reported; calls on receivers of
+    /// unknown type are left to the runtime.
+    fn resolve_method_call(&mut self, receiver: &Expression, name: &str, position: &Position) {
+        let Some(ty) = self.type_name_of(receiver) else {
+            return;
+        };
+
+        let is_type = self.user_types.contains_key(&ty);
+
+        if is_type && name == "copy" {
+            return;
+        }
+
+        let has_method = |ty: &str| {
+            self.methods
+                .get(ty)
+                .is_some_and(|methods| methods.iter().any(|method| method == name))
+        };
+        // An int is passed where a float is expected.
+        let target = if has_method(&ty) || (ty == "int" && has_method("float")) {
+            Some(MethodCall::Method)
+        } else {
+            type_namespace(&ty)
+                .filter(|namespace| self.scopes.get(namespace.to_string()).is_none())
+                .map(MethodCall::Namespace)
+        };
+
+        match target {
+            Some(target) => {
+                self.method_calls.insert(call_site(position), target);
+            }
+            None if is_type || self.enums.contains_key(&ty) => self
+                .parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_unknown_method(
+                    Span::from(position),
+                    ty,
+                    name.to_string(),
+                )),
+            None => {}
+        }
+    }
+
+    /// Type the runtime declares for the built-in `namespace.function` called with `arguments`,
+    /// from the first of its overloads they match. Overloads are told apart by position, so with
+    /// named arguments the type is only known when all of them return the same.
+    fn builtin_return_type<'e>(
+        &self,
+        namespace: &str,
+        function: &str,
+        arguments: impl IntoIterator<Item = &'e Expression>,
+        named_arguments: &[NamedArgument],
+    ) -> Option<DataType> {
+        let overloads = self
+            .parsing_ctx
+            .config
+            .overloads
+            .get(&format!("{namespace}.{function}"))?;
+
+        if !named_arguments.is_empty() {
+            let (first, others) = overloads.split_first()?;
+
+            return others
+                .iter()
+                .all(|overload| overload.returns == first.returns)
+                .then(|| first.returns.clone());
+        }
+
+        let arguments: Vec<_> = arguments
+            .into_iter()
+            .map(|argument| self.type_name_of(argument))
+            .collect();
+
+        overloads
+            .iter()
+            .find(|overload| accepts(&overload.parameters, &arguments))
+            .map(|overload| overload.returns.clone())
+    }
+
+    /// Statically known type of `expression`, named as `type_name` does: a user-defined type or
+    /// enum, a primitive type, or a collection or drawing type such as `array`.
+    fn type_name_of(&self, expression: &Expression) -> Option<String> {
+        self.type_of(expression).map(|ty| type_name(&ty))
+    }
+
+    /// Statically known type of `expression`, a user-defined type or enum being a
+    /// `DataType::Custom`.
+    fn type_of(&self, expression: &Expression) -> Option<DataType> {
+        if let Some(ty) = self.user_type_of(expression) {
+            return Some(DataType::Custom(ty));
+        }
+
+        if let Some(ty) = literal_type(expression) {
+            return Some(ty);
+        }
+
+        match expression {
+            Expression::Variable(variable) => self.variable_type(variable.name()),
+            Expression::List(_) => Some(DataType::Array(Box::new(any()))),
+            Expression::BinaryOperation(operation) => {
+                let lhs = self.type_of(operation.lhs())?;
+                let rhs = self.type_of(operation.rhs())?;
+
+                match operation.operator() {
+                    BinaryOperator::Add if lhs == DataType::String && rhs == DataType::String => {
+                        Some(lhs)
+                    }
+                    BinaryOperator::Add | BinaryOperator::Subtract | BinaryOperator::Multiply => {
+                        numeric_type(&[lhs, rhs])
+                    }
+                    // Like the runtime, dividing ints only gives an int when nothing is lost,
+                    // which is known before the script runs for literals only.
+                    BinaryOperator::Divide => {
+                        let ty = numeric_type(&[lhs, rhs])?;
+
+                        match (int_literal(operation.lhs()), int_literal(operation.rhs())) {
+                            (Some(first), Some(second)) if first.checked_rem(second) == Some(0) => {
+                                Some(ty)
+                            }
+                            _ => Some(DataType::Float),
+                        }
+                    }
+                    _ => Some(DataType::Bool),
+                }
+            }
+            Expression::Call(call) => {
+                let Expression::RecordDeconstruction(member) = call.function() else {
+                    return None;
+                };
+
+                match member.expression() {
+                    Expression::Variable(namespace)
+                        if self.scopes.get(namespace.name().to_string()).is_none() =>
+                    {
+                        let returned = || {
+                            self.builtin_return_type(
+                                namespace.name(),
+                                member.name(),
+                                call.arguments(),
+                                call.named_arguments(),
+                            )
+                        };
+
+                        match (member.name(), member.type_arguments()) {
+                            ("new", [key, value]) if namespace.name() == "map" => Some(
+                                DataType::Map(Box::new(key.clone()), Box::new(value.clone())),
+                            ),
+                            ("new", [element]) if namespace.name() == "array" => {
+                                Some(DataType::Array(Box::new(element.clone())))
+                            }
+                            // A copy keeps the element types of the original.
+                            ("copy", _) => call
+                                .arguments()
+                                .first()
+                                .and_then(|original| self.type_of(original))
+                                .or_else(returned),
+                            _ => returned(),
+                        }
+                    }
+                    receiver => {
+                        let ty = self.type_of(receiver)?;
+
+                        if member.name() == "copy" {
+                            return Some(ty);
+                        }
+
+                        let name = type_name(&ty);
+                        let is_method = self.methods.get(&name).is_some_and(|methods| {
+                            methods.iter().any(|method| method == member.name())
+                        });
+
+                        if is_method {
+                            return None;
+                        }
+
+                        let arguments = std::iter::once(receiver).chain(call.arguments());
+
+                        self.builtin_return_type(
+                            type_namespace(&name)?,
+                            member.name(),
+                            arguments,
+                            call.named_arguments(),
+                        )
+                    }
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Statically known key and value types of a map expression: `map.new<key, value>()`, a
+    /// copy of it, or a variable holding one.
+    fn map_type_of(&self, expression: &Expression) -> Option<(DataType, DataType)> {
+        match self.type_of(expression)? {
+            DataType::Map(key, value) if *key != any() && *value != any() => Some((*key, *value)),
+            _ => None,
+        }
+    }
+
+    /// Whether `expression` is the built-in `map` namespace rather than a variable named `map`.
+    fn is_map_namespace(&self, expression: &Expression) -> bool {
+        matches!(expression, Expression::Variable(variable)
+            if variable.name() == "map" && self.scopes.get("map".to_string()).is_none())
+    }
+
+    /// Type of a variable declared as `declared`, if it is, holding `value`, reporting a map
+    /// whose types differ from the declared `map<key, value>` type.
+    fn declared_type(
+        &mut self,
+        declared: Option<&DataType>,
+        value: &Expression,
+    ) -> Option<DataType> {
+        let Some(declared) = declared else {
+            return self.type_of(value);
+        };
+
+        if let (DataType::Map(..), Some((key, value_type))) = (declared, self.map_type_of(value)) {
+            let inferred = DataType::Map(Box::new(key), Box::new(value_type));
+
+            if inferred != *declared {
+                self.parsing_ctx
+                    .diagnostics
+                    .push_error(Diagnostic::new_type_mismatch(
+                        Span::from(value.position()),
+                        declared.to_string(),
+                        inferred.to_string(),
+                    ));
+            }
+        }
+
+        Some(declared.clone())
+    }
+
+    /// Checks the keys and values passed to `put`, `get`, `contains` and `remove` of a map
+    /// whose types are known, called either as `map.put(m, ...)` or `m.put(...)`.
+    fn check_map_call(&mut self, call: &Call) {
+        let Expression::RecordDeconstruction(member) = call.function() else {
+            return;
+        };
+
+        let (receiver, arguments) = match self.is_map_namespace(member.expression()) {
+            true => match call.arguments().split_first() {
+                Some((receiver, arguments)) => (receiver, arguments),
+                None => return,
+            },
+            false => (member.expression(), call.arguments()),
+        };
+
+        let Some((key, value)) = self.map_type_of(receiver) else {
+            return;
+        };
+
+        let expected = match member.name() {
+            "put" => vec![key, value],
+            "get" | "contains" | "remove" => vec![key],
+            _ => return,
+        };
+
+        for (expected, argument) in expected.iter().zip(arguments) {
+            self.check_type(expected, argument);
+        }
+    }
+
+    /// Reports `value` when its type is statically known, a variable's included, and doesn't
+    /// fit `expected`.
+    fn check_type(&mut self, expected: &DataType, value: &Expression) {
+        let Some(found) = self.type_of(value) else {
+            return;
+        };
+
+        if !fits(expected, &found) {
+            self.parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_type_mismatch(
+                    Span::from(value.position()),
+                    expected.to_string(),
+                    found.to_string(),
+                ));
+        }
+    }
+
+    fn resolve_assignment(&mut self, assignment: &Assignment) {
+        self.resolve_expression(assignment.value());
+
+        if self.scopes.get(assignment.name().to_string()).is_none() {
+            self.parsing_ctx
+                .diagnostics
+                .push_error(Diagnostic::new_undeclared_assignment(
+                    Span::from(assignment.position()),
+                    assignment.name().to_string(),
+                ));
+
+            return;
+        }
+
+        let mut ty = match self.variable_type(assignment.name()) {
+            Some(DataType::Custom(name)) => Some(name),
+            _ => None,
+        };
+
+        let mut field_type = None;
+
+        for field in assignment.fields() {
+            field_type = ty.as_deref().and_then(|ty| self.field_type(ty, field));
+            ty = self.check_field(ty, field, Span::from(assignment.position()));
+        }
+
+        if let Some(field_type) = field_type {
+            self.check_type(&field_type, assignment.value());
+        }
+    }
+
+    fn resolve_tuple_definition(&mut self, definition: &TupleDefinition) {
+        self.resolve_expression(definition.value());
+
+        let arity = match definition.value() {
+            Expression::Tuple(tuple) => Some(tuple.elements().len()),
+            Expression::Call(call) => match call.function() {
+                Expression::Variable(variable) => self
+                    .scopes
+                    .get(variable.name().to_string())
+                    .and_then(|binding| binding.tuple_arity),
+                Expression::RecordDeconstruction(member) => match member.expression() {
+                    // A script's own variable isn't a built-in namespace.
+                    Expression::Variable(namespace)
+                        if self.scopes.get(namespace.name().to_string()).is_none() =>
+                    {
+                        match self.builtin_return_type(
+                            namespace.name(),
+                            member.name(),
+                            call.arguments(),
+                            call.named_arguments(),
+                        ) {
+                            Some(DataType::Tuple(elements)) => Some(elements.len()),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some(arity) = arity {
+            if arity != definition.names().len() {
+                self.parsing_ctx
+                    .diagnostics
+                    .push_error(Diagnostic::new_tuple_arity_mismatch(
+                        Span::from(definition.position()),
+                        arity,
+                        definition.names().len(),
+                    ));
+            }
+        }
+
+        for name in definition.names() {
+            self.add_to_current_scope(name.name.clone());
+        }
+    }
+
+    fn resolve_if(&mut self, if_: &IfDecl) {
+        self.resolve_expression(&if_.predicat);
+        self.resolve_scoped_body(&if_.body);
+
+        match if_.else_.as_deref() {
+            Some(Else::If(if_)) => self.resolve_if(if_),
+            Some(Else::Body(body)) => self.resolve_scoped_body(body),
+            None => {}
+        }
+    }
+
+    fn resolve_while(&mut self, while_: &While) {
+        self.resolve_expression(&while_.predicat);
+        self.resolve_scoped_body(&while_.body);
+    }
+
+    fn resolve_for(&mut self, for_: &For) {
+        match for_ {
+            For::In(for_in) => {
+                self.resolve_expression(&for_in.expr);
+                self.push_scope();
+                self.add_to_current_scope(for_in.value.name.clone());
+                self.resolve_body(&for_in.body);
+                self.pop_scope();
+            }
+            For::Range(for_range) => {
+                self.resolve_expression(&for_range.start);
+                self.resolve_expression(&for_range.end);
+
+                if let Some(step) = &for_range.step {
+                    self.resolve_expression(step);
+                }
+
+                self.push_scope();
+                self.add_to_current_scope(for_range.value.name.clone());
+                self.resolve_body(&for_range.body);
+                self.pop_scope();
+            }
+            For::While(while_) => self.resolve_while(while_),
+        }
+    }
+
+    fn resolve_switch(&mut self, switch: &Switch) {
+        if let Some(subject) = &switch.subject {
+            self.resolve_expression(subject);
+        }
+
+        for arm in &switch.arms {
+            self.resolve_expression(&arm.pattern);
+            self.resolve_scoped_body(&arm.body);
+        }
+
+        if let Some(default) = &switch.default {
+            self.resolve_scoped_body(default);
+        }
+
+        self.check_exhaustive(switch);
+    }
+
+    /// Warns when a switch over an enum without a default arm leaves some members unhandled.
+    fn check_exhaustive(&mut self, switch: &Switch) {
+        if switch.default.is_some() {
+            return;
+        }
+
+        let Some(ty) = switch.subject.as_ref().and_then(|subject| self.user_type_of(subject))
+        else {
+            return;
+        };
+
+        let Some(members) = self.enums.get(&ty) else {
+            return;
+        };
+
+        let missing: Vec<_> = members
+            .iter()
+            .filter(|name| {
+                !switch.arms.iter().any(|arm| match &arm.pattern {
+                    Expression::RecordDeconstruction(member) => {
+                        self.enum_name(member.expression()) == Some(ty.as_str())
+                            && member.name() == name.as_str()
+                    }
+                    _ => false,
+                })
+            })
+            .cloned()
+            .collect();
+
+        if !missing.is_empty() {
+            self.parsing_ctx
+                .diagnostics
+                .push_warning(Diagnostic::new_non_exhaustive_switch(
+                    Span::from(switch.position()),
+                    ty,
+                    missing,
+                ));
+        }
+    }
+
+    fn resolve_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Assignment(assignment) => self.resolve_assignment(assignment),
+            Expression::BinaryOperation(operation) => {
+                self.resolve_expression(operation.lhs());
+                self.resolve_expression(operation.rhs());
+            }
+            Expression::UnaryOperation(operation) => {
+                self.resolve_expression(operation.expression())
+            }
+            Expression::Conditional(conditional) => {
+                self.resolve_expression(conditional.condition());
+                self.resolve_expression(conditional.then());
+                self.resolve_expression(conditional.else_());
+            }
+            Expression::Call(call) => {
+                match call.function() {
+                    // Constructors, `copy()` and methods are looked up on the receiver at runtime.
+                    Expression::RecordDeconstruction(member)
+                        if self.check_export(
+                            member.expression(),
+                            member.name(),
+                            Span::from(member.position()),
+                        ) => {}
+                    Expression::RecordDeconstruction(member) => {
+                        self.resolve_expression(member.expression());
+                        self.resolve_method_call(
+                            member.expression(),
+                            member.name(),
+                            call.position(),
+                        );
+
+                        if let (Expression::Variable(variable), "new") =
+                            (member.expression(), member.name())
+                        {
+                            let fields = self.user_types.get(variable.name()).cloned();
+
+                            match fields {
+                                Some(fields) if call.arguments().len() > fields.len() => {
+                                    self.parsing_ctx.diagnostics.push_error(
+                                        Diagnostic::new_constructor_arity(
+                                            Span::from(call.position()),
+                                            variable.name().to_string(),
+                                            fields.len(),
+                                            call.arguments().len(),
+                                        ),
+                                    )
+                                }
+                                Some(fields) => {
+                                    for ((_, field_type), argument) in
+                                        fields.iter().zip(call.arguments())
+                                    {
+                                        self.check_type(field_type, argument);
+                                    }
+
+                                    for argument in call.named_arguments() {
+                                        match fields
+                                            .iter()
+                                            .find(|(name, _)| name == argument.name())
+                                        {
+                                            Some((_, field_type)) => {
+                                                self.check_type(field_type, argument.value())
+                                            }
+                                            None => self.parsing_ctx.diagnostics.push_error(
+                                                Diagnostic::new_unknown_field(
+                                                    Span::from(argument.position()),
+                                                    variable.name().to_string(),
+                                                    argument.name().to_string(),
+                                                ),
+                                            ),
+                                        }
+                                    }
+                                }
+                                None => {}
+                            }
+                        }
+                    }
+                    function => self.resolve_expression(function),
+                }
+
+                self.check_map_call(call);
+
+                for argument in call.arguments() {
+                    self.resolve_expression(argument);
+                }
+
+                for argument in call.named_arguments() {
+                    self.resolve_expression(argument.value());
+                }
+            }
+            Expression::RecordDeconstruction(member) => {
+                self.resolve_expression(member.expression());
+
+                if self.check_export(
+                    member.expression(),
+                    member.name(),
+                    Span::from(member.position()),
+                ) {
+                    return;
+                }
+
+                if let Some(name) = self.enum_name(member.expression()) {
+                    if !self.enums[name].iter().any(|variant| variant == member.name()) {
+                        self.parsing_ctx.diagnostics.push_error(Diagnostic::new_unknown_field(
+                            Span::from(member.position()),
+                            name.to_string(),
+                            member.name().to_string(),
+                        ));
+                    }
+
+                    return;
+                }
+
+                let ty = self.user_type_of(member.expression());
+
+                self.check_field(ty, member.name(), Span::from(member.position()));
+            }
+            Expression::List(list) => {
+                for element in list.elements() {
+                    match element {
+                        ListElement::Multiple(expression) | ListElement::Single(expression) => {
+                            self.resolve_expression(expression)
+                        }
+                    }
+                }
+            }
+            Expression::Tuple(tuple) => {
+                for element in tuple.elements() {
+                    self.resolve_expression(element);
+                }
+            }
+            Expression::TupleDefinition(definition) => self.resolve_tuple_definition(definition),
+            Expression::IfDecl(if_) => self.resolve_if(if_),
+            Expression::For(for_) => self.resolve_for(for_),
+            Expression::Switch(switch) => self.resolve_switch(switch),
+            _ => {}
+        }
+    }
+}
+
+/// Name of `ty` as a `method` receiver: the type of the elements of a collection doesn't
+/// matter.
+fn type_name(ty: &DataType) -> String {
+    match ty {
+        DataType::Array(_) => "array".to_string(),
+        DataType::Matrix(_) => "matrix".to_string(),
+        DataType::Map(..) => "map".to_string(),
+        ty => ty.to_string(),
+    }
+}
+
+/// Built-in namespace of the functions taking a value of type `ty` first.
+fn type_namespace(ty: &str) -> Option<&'static str> {
+    match ty {
+        "int" | "float" => Some("math"),
+        "string" => Some("str"),
+        "color" => Some("color"),
+        "array" => Some("array"),
+        "matrix" => Some("matrix"),
+        "map" => Some("map"),
+        "line" => Some("line"),
+        "label" => Some("label"),
+        "box" => Some("box"),
+        "linefill" => Some("linefill"),
+        "polyline" => Some("polyline"),
+        "table" => Some("table"),
+        _ => None,
+    }
+}
+
+/// Whether arguments of the types `arguments` match `parameters`, the last of which takes any
+/// further arguments: an int passes for a float, and anything for `any`.
+fn accepts(parameters: &[DataType], arguments: &[Option<String>]) -> bool {
+    let Some(last) = parameters.last() else {
+        return true;
+    };
+
+    arguments.len() >= parameters.len()
+        && arguments.iter().enumerate().all(|(index, argument)| {
+            let parameter = type_name(parameters.get(index).unwrap_or(last));
+
+            match argument.as_deref() {
+                _ if parameter == "any" => true,
+                Some("int") if parameter == "float" => true,
+                Some(argument) => argument == parameter,
+                None => false,
+            }
+        })
+}
+
+/// Whether a value of type `found` can be used where `expected` is: an int is accepted where a
+/// float is expected, and elements of any type where elements of a given one are.
+fn fits(expected: &DataType, found: &DataType) -> bool {
+    match (expected, found) {
+        (expected, found) if *expected == any() || *found == any() => true,
+        (DataType::Float, DataType::Int) => true,
+        (DataType::Array(expected), DataType::Array(found))
+        | (DataType::Matrix(expected), DataType::Matrix(found)) => fits(expected, found),
+        (DataType::Map(key, value), DataType::Map(found_key, found_value)) => {
+            fits(key, found_key) && fits(value, found_value)
+        }
+        (expected, found) => expected == found,
+    }
+}
+
+/// `float` when one of `types` is, else `int` when they all are.
+fn numeric_type(types: &[DataType]) -> Option<DataType> {
+    let mut ty = DataType::Int;
+
+    for argument in types {
+        match argument {
+            DataType::Int => {}
+            DataType::Float => ty = DataType::Float,
+            _ => return None,
+        }
+    }
+
+    Some(ty)
+}
+
+/// Type of the elements of a collection when they may be of any type, as the runtime names it.
+fn any() -> DataType {
+    DataType::Custom("any".to_string())
+}
+
+/// Value of an int literal.
+fn int_literal(expression: &Expression) -> Option<i64> {
+    let Expression::Number(number) = expression else {
+        return None;
+    };
+
+    match number.value() {
+        NumberRepresentation::Binary(digits) => i64::from_str_radix(digits, 2).ok(),
+        NumberRepresentation::Hexadecimal(digits) => i64::from_str_radix(digits, 16).ok(),
+        NumberRepresentation::Integer(digits) => digits.parse().ok(),
+        NumberRepresentation::FloatingPoint(_) => None,
+    }
+}
+
+/// Type of a literal expression.
+fn literal_type(expression: &Expression) -> Option<DataType> {
+    match expression {
+        Expression::Boolean(_) => Some(DataType::Bool),
+        Expression::String(_) => Some(DataType::String),
+        Expression::Color(_) => Some(DataType::Color),
+        Expression::Number(number) => match number.value() {
+            NumberRepresentation::FloatingPoint(_) => Some(DataType::Float),
+            _ => Some(DataType::Int),
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,33 @@
+use std::path::{Path, PathBuf};
+
+/// A directory of source files under the system's temporary directory, removed when dropped.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Writes `files`, as `(path, source)` relative to the directory, into a directory named
+    /// after `name` and the current process, so concurrent tests don't share one.
+    pub fn new(name: &str, files: &[(impl AsRef<Path>, &str)]) -> Self {
+        let path = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+
+        for (file, source) in files {
+            let file = path.join(file);
+
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, source).unwrap();
+        }
+
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
@@ -16,6 +16,8 @@ struct PositionInner {
     line_number: usize,
     column_number: usize,
     line: String,
+    #[serde(default)]
+    offset: usize,
 }
 
 impl Position {
@@ -31,6 +33,7 @@ impl Position {
                 line_number,
                 column_number,
                 line: line.into(),
+                offset: 0,
             }
             .into(),
         )
@@ -51,6 +54,16 @@ impl Position {
     pub fn line(&self) -> &str {
         &self.0.line
     }
+
+    /// Byte offset of the position in its source.
+    pub fn offset(&self) -> usize {
+        self.0.offset
+    }
+
+    pub fn with_offset(mut self, offset: usize) -> Self {
+        Rc::make_mut(&mut self.0).offset = offset;
+        self
+    }
 }
 
 impl Display for Position {
@@ -0,0 +1,829 @@
+use std::cmp::Ordering;
+
+use ast::datatype::DataType;
+use parse::util::ArraySize;
+use parse::Span;
+
+use super::ArgumentError;
+use crate::value::native::{Module, NativeModule};
+use crate::value::{Elements, ErrorCode, Value};
+use crate::{FArguments, FunctionBuilder};
+
+/// The `array` namespace. Every function takes the array as its first argument, so
+/// `array.push(a, x)` can also be written `a.push(x)`.
+pub struct Array;
+
+/// `order.ascending` and `order.descending`, used by `array.sort`.
+pub struct Order;
+
+pub(super) const ASCENDING: &str = "ascending";
+const DESCENDING: &str = "descending";
+
+pub(super) fn any() -> DataType {
+    DataType::Custom("any".into())
+}
+
+fn array() -> DataType {
+    DataType::Array(Box::new(any()))
+}
+
+impl NativeModule for Order {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("order");
+
+        module.export(ASCENDING, Value::String(ASCENDING.into()));
+        module.export(DESCENDING, Value::String(DESCENDING.into()));
+
+        module
+    }
+}
+
+impl NativeModule for Array {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("array");
+
+        // `array.new<type>` takes the type of its elements as a type argument.
+        for (name, ty) in [
+            ("new", any()),
+            ("new_int", DataType::Int),
+            ("new_float", DataType::Float),
+            ("new_bool", DataType::Bool),
+            ("new_string", DataType::String),
+        ] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param_default("size", DataType::Int, Value::Number(0))
+                    .param_default("initial_value", ty.clone(), Value::None)
+                    .returns(DataType::Array(Box::new(ty.clone())))
+                    .build(move |args| {
+                        let ty = match args.type_arguments() {
+                            [element] => Some(element.clone()),
+                            _ => Some(ty.clone()).filter(|ty| *ty != any()),
+                        };
+                        let value = match int(args, "size") {
+                            Some(size) if size >= 0 => {
+                                let initial = args.get("initial_value").unwrap_or(Value::None);
+                                let elements = vec![initial; size as usize];
+                                let elements = match ty {
+                                    Some(ty) => Elements::typed(elements, ty),
+                                    None => elements.into(),
+                                };
+
+                                Value::Array(elements, ArraySize::Dynamic)
+                            }
+                            _ => Value::error(
+                                Span::empty(),
+                                ErrorCode::Type,
+                                "array size must be a non-negative int",
+                            ),
+                        };
+
+                        args.returns(value);
+                    }),
+            );
+        }
+
+        module.export(
+            "from",
+            FunctionBuilder::new("from")
+                .rest("values", any())
+                .returns(array())
+                .build(|args| {
+                    let values = args.get("values").unwrap_or(Value::None);
+
+                    args.returns(values);
+                }),
+        );
+
+        module.export(
+            "copy",
+            FunctionBuilder::new("copy")
+                .param("id", array())
+                .returns(array())
+                .build(|args| {
+                    with_array(args, |_, array| {
+                        Value::Array(array.copy(), ArraySize::Dynamic)
+                    })
+                }),
+        );
+
+        module.export(
+            "size",
+            FunctionBuilder::new("size")
+                .param("id", array())
+                .returns(DataType::Int)
+                .build(|args| with_array(args, |_, array| Value::Number(array.len() as i64))),
+        );
+
+        module.export(
+            "clear",
+            FunctionBuilder::new("clear")
+                .param("id", array())
+                .build(|args| {
+                    with_array(args, |_, array| {
+                        array.write().clear();
+
+                        Value::None
+                    })
+                }),
+        );
+
+        module.export(
+            "push",
+            FunctionBuilder::new("push")
+                .param("id", array())
+                .param("value", any())
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let value = array.promote(args.get("value").unwrap_or(Value::None));
+
+                        array.write().push(value);
+
+                        Value::None
+                    })
+                }),
+        );
+
+        module.export(
+            "unshift",
+            FunctionBuilder::new("unshift")
+                .param("id", array())
+                .param("value", any())
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let value = array.promote(args.get("value").unwrap_or(Value::None));
+
+                        array.write().insert(0, value);
+
+                        Value::None
+                    })
+                }),
+        );
+
+        module.export(
+            "pop",
+            FunctionBuilder::new("pop")
+                .param("id", array())
+                .build(|args| {
+                    with_array(args, |_, array| {
+                        let popped = array.write().pop();
+
+                        popped.unwrap_or_else(|| empty("pop"))
+                    })
+                }),
+        );
+
+        module.export(
+            "shift",
+            FunctionBuilder::new("shift")
+                .param("id", array())
+                .build(|args| {
+                    with_array(args, |_, array| match array.is_empty() {
+                        true => empty("shift"),
+                        false => array.write().remove(0),
+                    })
+                }),
+        );
+
+        module.export(
+            "first",
+            FunctionBuilder::new("first")
+                .param("id", array())
+                .build(|args| {
+                    with_array(args, |_, array| {
+                        let first = array.read().first().cloned();
+
+                        first.unwrap_or_else(|| empty("first"))
+                    })
+                }),
+        );
+
+        module.export(
+            "last",
+            FunctionBuilder::new("last")
+                .param("id", array())
+                .build(|args| {
+                    with_array(args, |_, array| {
+                        let last = array.read().last().cloned();
+
+                        last.unwrap_or_else(|| empty("last"))
+                    })
+                }),
+        );
+
+        module.export(
+            "get",
+            FunctionBuilder::new("get")
+                .param("id", array())
+                .param("index", DataType::Int)
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let elements = array.read();
+
+                        match index(args, "index", elements.len()) {
+                            Ok(index) => elements[index].clone(),
+                            Err(error) => error.into(),
+                        }
+                    })
+                }),
+        );
+
+        module.export(
+            "set",
+            FunctionBuilder::new("set")
+                .param("id", array())
+                .param("index", DataType::Int)
+                .param("value", any())
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let mut elements = array.write();
+
+                        match index(args, "index", elements.len()) {
+                            Ok(index) => {
+                                elements[index] =
+                                    array.promote(args.get("value").unwrap_or(Value::None));
+
+                                Value::None
+                            }
+                            Err(error) => error.into(),
+                        }
+                    })
+                }),
+        );
+
+        module.export(
+            "insert",
+            FunctionBuilder::new("insert")
+                .param("id", array())
+                .param("index", DataType::Int)
+                .param("value", any())
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let mut elements = array.write();
+
+                        // Inserting right after the last element appends.
+                        match index(args, "index", elements.len() + 1) {
+                            Ok(index) => {
+                                let value = array.promote(args.get("value").unwrap_or(Value::None));
+
+                                elements.insert(index, value);
+
+                                Value::None
+                            }
+                            Err(error) => error.into(),
+                        }
+                    })
+                }),
+        );
+
+        module.export(
+            "remove",
+            FunctionBuilder::new("remove")
+                .param("id", array())
+                .param("index", DataType::Int)
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let mut elements = array.write();
+
+                        match index(args, "index", elements.len()) {
+                            Ok(index) => elements.remove(index),
+                            Err(error) => error.into(),
+                        }
+                    })
+                }),
+        );
+
+        module.export(
+            "fill",
+            FunctionBuilder::new("fill")
+                .param("id", array())
+                .param("value", any())
+                .param_default("index_from", DataType::Int, Value::Number(0))
+                .param_default("index_to", DataType::Int, Value::None)
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let mut elements = array.write();
+
+                        match range(args, elements.len()) {
+                            Ok((from, to)) => {
+                                let value = array.promote(args.get("value").unwrap_or(Value::None));
+
+                                elements[from..to].fill(value);
+
+                                Value::None
+                            }
+                            Err(error) => error.into(),
+                        }
+                    })
+                }),
+        );
+
+        module.export(
+            "slice",
+            FunctionBuilder::new("slice")
+                .param("id", array())
+                .param("index_from", DataType::Int)
+                .param_default("index_to", DataType::Int, Value::None)
+                .returns(array())
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let elements = array.read();
+
+                        match range(args, elements.len()) {
+                            Ok((from, to)) => new_array(elements[from..to].to_vec()),
+                            Err(error) => error.into(),
+                        }
+                    })
+                }),
+        );
+
+        module.export(
+            "concat",
+            FunctionBuilder::new("concat")
+                .param("id", array())
+                .param("id2", array())
+                .returns(array())
+                .build(|args| {
+                    let appended = match args.get("id2") {
+                        Some(Value::Array(elements, _)) => elements.to_vec(),
+                        value => return args.returns(not_an_array(value)),
+                    };
+
+                    with_array(args, |_, array| {
+                        array.write().extend(appended);
+
+                        Value::Array(array.clone(), ArraySize::Dynamic)
+                    })
+                }),
+        );
+
+        module.export(
+            "reverse",
+            FunctionBuilder::new("reverse")
+                .param("id", array())
+                .build(|args| {
+                    with_array(args, |_, array| {
+                        array.write().reverse();
+
+                        Value::None
+                    })
+                }),
+        );
+
+        module.export(
+            "sort",
+            FunctionBuilder::new("sort")
+                .param("id", array())
+                .param_default("order", DataType::String, Value::String(ASCENDING.into()))
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let descending = descending(args);
+
+                        array.write().sort_by(|a, b| ordered(compare(a, b), descending));
+
+                        Value::None
+                    })
+                }),
+        );
+
+        module.export(
+            "sort_indices",
+            FunctionBuilder::new("sort_indices")
+                .param("id", array())
+                .param_default("order", DataType::String, Value::String(ASCENDING.into()))
+                .returns(DataType::Array(Box::new(DataType::Int)))
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let descending = descending(args);
+                        let elements = array.read();
+                        let mut indices = (0..elements.len()).collect::<Vec<_>>();
+
+                        indices.sort_by(|a, b| {
+                            ordered(compare(&elements[*a], &elements[*b]), descending)
+                        });
+
+                        new_array(
+                            indices
+                                .into_iter()
+                                .map(|index| Value::Number(index as i64))
+                                .collect(),
+                        )
+                    })
+                }),
+        );
+
+        module.export(
+            "includes",
+            FunctionBuilder::new("includes")
+                .param("id", array())
+                .param("value", any())
+                .returns(DataType::Bool)
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let value = args.get("value").unwrap_or(Value::None);
+
+                        Value::Boolean(array.read().iter().any(|element| equals(element, &value)))
+                    })
+                }),
+        );
+
+        module.export(
+            "indexof",
+            FunctionBuilder::new("indexof")
+                .param("id", array())
+                .param("value", any())
+                .returns(DataType::Int)
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let value = args.get("value").unwrap_or(Value::None);
+                        let position = array
+                            .read()
+                            .iter()
+                            .position(|element| equals(element, &value));
+
+                        Value::Number(position.map_or(-1, |index| index as i64))
+                    })
+                }),
+        );
+
+        module.export(
+            "lastindexof",
+            FunctionBuilder::new("lastindexof")
+                .param("id", array())
+                .param("value", any())
+                .returns(DataType::Int)
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let value = args.get("value").unwrap_or(Value::None);
+                        let position = array
+                            .read()
+                            .iter()
+                            .rposition(|element| equals(element, &value));
+
+                        Value::Number(position.map_or(-1, |index| index as i64))
+                    })
+                }),
+        );
+
+        module.export(
+            "join",
+            FunctionBuilder::new("join")
+                .param("id", array())
+                .param_default("separator", DataType::String, Value::String(String::new()))
+                .returns(DataType::String)
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let separator = args.get_string_opt("separator").unwrap_or_default();
+                        let joined = array
+                            .read()
+                            .iter()
+                            .map(|element| element.to_string())
+                            .collect::<Vec<_>>()
+                            .join(&separator);
+
+                        Value::String(joined)
+                    })
+                }),
+        );
+
+        module.export(
+            "sum",
+            FunctionBuilder::new("sum")
+                .param("id", array())
+                .build(|args| {
+                    with_array(args, |_, array| {
+                        let elements = array.read();
+
+                        match elements.iter().all(|element| matches!(element, Value::Number(_))) {
+                            true => Value::Number(
+                                elements
+                                    .iter()
+                                    .filter_map(|element| match element {
+                                        Value::Number(number) => Some(*number),
+                                        _ => None,
+                                    })
+                                    .sum(),
+                            ),
+                            false => statistic(&elements, |numbers| Some(numbers.iter().sum())),
+                        }
+                    })
+                }),
+        );
+
+        module.export(
+            "avg",
+            FunctionBuilder::new("avg")
+                .param("id", array())
+                .returns(DataType::Float)
+                .build(|args| with_array(args, |_, array| statistic(&array.read(), mean))),
+        );
+
+        module.export(
+            "median",
+            FunctionBuilder::new("median")
+                .param("id", array())
+                .returns(DataType::Float)
+                .build(|args| with_array(args, |_, array| statistic(&array.read(), median))),
+        );
+
+        for name in ["stdev", "variance"] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param("id", array())
+                    .param_default("biased", DataType::Bool, true)
+                    .returns(DataType::Float)
+                    .build(move |args| {
+                        with_array(args, |args, array| {
+                            let biased = args.get_boolean_opt("biased").unwrap_or(true);
+
+                            statistic(&array.read(), |numbers| {
+                                let variance = variance(numbers, biased)?;
+
+                                match name {
+                                    "stdev" => Some(variance.sqrt()),
+                                    _ => Some(variance),
+                                }
+                            })
+                        })
+                    }),
+            );
+        }
+
+        module.export(
+            "range",
+            FunctionBuilder::new("range")
+                .param("id", array())
+                .returns(DataType::Float)
+                .build(|args| {
+                    with_array(args, |_, array| {
+                        statistic(&array.read(), |numbers| {
+                            Some(numbers.last()? - numbers.first()?)
+                        })
+                    })
+                }),
+        );
+
+        module.export(
+            "mode",
+            FunctionBuilder::new("mode")
+                .param("id", array())
+                .returns(DataType::Float)
+                .build(|args| {
+                    with_array(args, |_, array| {
+                        statistic(&array.read(), |numbers| {
+                            // `numbers` is sorted, so ties keep the smallest value.
+                            let mut mode = (*numbers.first()?, 0);
+                            let mut run = (numbers[0], 0);
+
+                            for number in numbers {
+                                run = match run.0 == *number {
+                                    true => (run.0, run.1 + 1),
+                                    false => (*number, 1),
+                                };
+
+                                if run.1 > mode.1 {
+                                    mode = run;
+                                }
+                            }
+
+                            Some(mode.0)
+                        })
+                    })
+                }),
+        );
+
+        module.export(
+            "percentile_linear_interpolation",
+            FunctionBuilder::new("percentile_linear_interpolation")
+                .param("id", array())
+                .param("percentage", DataType::Float)
+                .returns(DataType::Float)
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let percentage = percentage(args);
+
+                        statistic(&array.read(), |numbers| {
+                            let rank = percentage? / 100.0 * (numbers.len() - 1) as f64;
+                            let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+
+                            Some(
+                                numbers[lower]
+                                    + (numbers[upper] - numbers[lower]) * (rank - lower as f64),
+                            )
+                        })
+                    })
+                }),
+        );
+
+        module.export(
+            "percentile_nearest_rank",
+            FunctionBuilder::new("percentile_nearest_rank")
+                .param("id", array())
+                .param("percentage", DataType::Float)
+                .returns(DataType::Float)
+                .build(|args| {
+                    with_array(args, |args, array| {
+                        let percentage = percentage(args);
+
+                        statistic(&array.read(), |numbers| {
+                            let rank = (percentage? / 100.0 * numbers.len() as f64).ceil();
+
+                            Some(numbers[(rank as usize).clamp(1, numbers.len()) - 1])
+                        })
+                    })
+                }),
+        );
+
+        for (name, descending) in [("min", false), ("max", true)] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param("id", array())
+                    .param_default("nth", DataType::Int, Value::Number(0))
+                    .build(move |args| {
+                        with_array(args, |args, array| {
+                            let mut elements = array
+                                .to_vec()
+                                .into_iter()
+                                .filter(|element| element.as_float().is_some())
+                                .collect::<Vec<_>>();
+
+                            elements.sort_by(|a, b| ordered(compare(a, b), descending));
+
+                            match int(args, "nth") {
+                                Some(nth) if nth >= 0 => {
+                                    elements.get(nth as usize).cloned().unwrap_or(Value::None)
+                                }
+                                _ => Value::error(
+                                    Span::empty(),
+                                    ErrorCode::Type,
+                                    "nth must be a non-negative int",
+                                ),
+                            }
+                        })
+                    }),
+            );
+        }
+
+        module
+    }
+}
+
+pub(super) fn new_array(elements: Vec<Value>) -> Value {
+    Value::Array(elements.into(), ArraySize::Dynamic)
+}
+
+fn not_an_array(value: Option<Value>) -> Value {
+    match value {
+        None | Some(Value::None) => {
+            Value::error(Span::empty(), ErrorCode::Reference, "array is na")
+        }
+        Some(value) => Value::error(
+            Span::empty(),
+            ErrorCode::Type,
+            format!("array expected, but {} given", value.type_of()),
+        ),
+    }
+}
+
+fn empty(function: &str) -> Value {
+    Value::error(
+        Span::empty(),
+        ErrorCode::OutOfBounds,
+        format!("array.{function} can't be used on an empty array"),
+    )
+}
+
+/// Returns the result of `body` for the array passed as `id`, or the type error.
+fn with_array(args: &mut FArguments, body: impl FnOnce(&FArguments, &Elements) -> Value) {
+    let value = match args.get("id") {
+        Some(Value::Array(elements, _)) => body(args, &elements),
+        value => not_an_array(value),
+    };
+
+    args.returns(value);
+}
+
+pub(super) fn int(args: &FArguments, name: &str) -> Option<i64> {
+    match args.get(name)? {
+        Value::Number(number) => Some(number),
+        Value::Float(float) if float.fract() == 0.0 => Some(float as i64),
+        _ => None,
+    }
+}
+
+/// Index argument `name` into `len` elements; negative indices count from the end.
+fn index(args: &FArguments, name: &str, len: usize) -> Result<usize, ArgumentError> {
+    let index = int(args, name)
+        .ok_or_else(|| ArgumentError(ErrorCode::Type, format!("{name} must be an int")))?;
+    let resolved = match index < 0 {
+        true => len as i64 + index,
+        false => index,
+    };
+
+    match resolved >= 0 && resolved < len as i64 {
+        true => Ok(resolved as usize),
+        false => Err(ArgumentError::out_of_bounds(format!(
+            "index {index} is out of bounds, the array has {len} elements"
+        ))),
+    }
+}
+
+/// `index_from` (inclusive) to `index_to` (exclusive, the end of the array when na).
+fn range(args: &FArguments, len: usize) -> Result<(usize, usize), ArgumentError> {
+    let from = int(args, "index_from").unwrap_or(0);
+    let to = int(args, "index_to").unwrap_or(len as i64);
+
+    match 0 <= from && from <= to && to <= len as i64 {
+        true => Ok((from as usize, to as usize)),
+        false => Err(ArgumentError::out_of_bounds(format!(
+            "range {from}..{to} is out of bounds, the array has {len} elements"
+        ))),
+    }
+}
+
+fn percentage(args: &FArguments) -> Option<f64> {
+    args.get("percentage")?
+        .as_float()
+        .filter(|percentage| (0.0..=100.0).contains(percentage))
+}
+
+/// Numbers before strings and booleans, na last.
+pub(super) fn compare(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::None, Value::None) => Ordering::Equal,
+        (Value::None, _) => Ordering::Greater,
+        (_, Value::None) => Ordering::Less,
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Boolean(a), Value::Boolean(b)) => a.cmp(b),
+        _ => match (a.as_float(), b.as_float()) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        },
+    }
+}
+
+pub(super) fn descending(args: &FArguments) -> bool {
+    args.get_string_opt("order").as_deref() == Some(DESCENDING)
+}
+
+pub(super) fn ordered(ordering: Ordering, descending: bool) -> Ordering {
+    match descending {
+        true => ordering.reverse(),
+        false => ordering,
+    }
+}
+
+/// Ints and floats holding the same number are equal.
+fn equals(a: &Value, b: &Value) -> bool {
+    match (a.as_float(), b.as_float()) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Applies `body` to the sorted numeric elements, ignoring na; na for an empty array.
+pub(super) fn statistic(elements: &[Value], body: impl FnOnce(&[f64]) -> Option<f64>) -> Value {
+    let mut numbers = elements
+        .iter()
+        .filter_map(Value::as_float)
+        .collect::<Vec<_>>();
+
+    numbers.sort_by(f64::total_cmp);
+
+    match numbers.is_empty() {
+        true => Value::None,
+        false => body(&numbers).map_or(Value::None, Value::Float),
+    }
+}
+
+/// Median of sorted `numbers`.
+pub(super) fn median(numbers: &[f64]) -> Option<f64> {
+    let middle = numbers.len() / 2;
+
+    match numbers.len() % 2 {
+        0 => mean(numbers.get(middle.checked_sub(1)?..=middle)?),
+        _ => Some(numbers[middle]),
+    }
+}
+
+pub(super) fn mean(numbers: &[f64]) -> Option<f64> {
+    match numbers.len() {
+        0 => None,
+        len => Some(numbers.iter().sum::<f64>() / len as f64),
+    }
+}
+
+fn variance(numbers: &[f64], biased: bool) -> Option<f64> {
+    let mean = mean(numbers)?;
+    let count = match biased {
+        true => numbers.len(),
+        false => numbers.len().checked_sub(1).filter(|count| *count > 0)?,
+    };
+
+    Some(numbers.iter().map(|number| (number - mean).powi(2)).sum::<f64>() / count as f64)
+}
@@ -0,0 +1,65 @@
+use ast::datatype::DataType;
+use parse::Span;
+
+use super::array::any;
+use crate::value::native::{Module, NativeModule};
+use crate::value::{ErrorCode, Value};
+use crate::FunctionBuilder;
+
+/// `int(x)` and `float(x)`, the global functions converting between the numeric types.
+pub struct Cast;
+
+impl NativeModule for Cast {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("cast");
+
+        // Floats are truncated towards zero; na and floats out of the int range give na.
+        module.export(
+            "int",
+            FunctionBuilder::new("int")
+                .param("x", any())
+                .returns(DataType::Int)
+                .build(|args| {
+                    let value = match args.get("x").unwrap_or(Value::None) {
+                        value if value.is_na() => Value::None,
+                        Value::Number(number) => Value::Number(number),
+                        Value::Float(float) if float.trunc().abs() < i64::MAX as f64 => {
+                            Value::Number(float.trunc() as i64)
+                        }
+                        Value::Float(_) => Value::None,
+                        value => not_a_number("int", value),
+                    };
+
+                    args.returns(value);
+                }),
+        );
+
+        module.export(
+            "float",
+            FunctionBuilder::new("float")
+                .param("x", any())
+                .returns(DataType::Float)
+                .build(|args| {
+                    let value = match args.get("x").unwrap_or(Value::None) {
+                        value if value.is_na() => Value::None,
+                        value => match value.as_float() {
+                            Some(float) => Value::Float(float),
+                            None => not_a_number("float", value),
+                        },
+                    };
+
+                    args.returns(value);
+                }),
+        );
+
+        module
+    }
+}
+
+fn not_a_number(cast: &str, value: Value) -> Value {
+    Value::error(
+        Span::empty(),
+        ErrorCode::Type,
+        format!("a {} can't be converted to {}", value.type_of(), cast),
+    )
+}
@@ -0,0 +1,202 @@
+use ast::datatype::DataType;
+
+use super::{evaluate, ArgumentError};
+use crate::value::native::{Module, NativeModule};
+use crate::value::{self, ErrorCode, Value};
+use crate::{FArguments, FunctionBuilder};
+
+/// The `color` namespace: the named colors, and functions building colors or reading their
+/// channels. Colors are also written as `#RRGGBB` or `#RRGGBBAA` literals.
+pub struct Color;
+
+const NAMED: [(&str, u32); 17] = [
+    ("aqua", 0x00BCD4),
+    ("black", 0x363A45),
+    ("blue", 0x2196F3),
+    ("fuchsia", 0xE040FB),
+    ("gray", 0x787B86),
+    ("green", 0x4CAF50),
+    ("lime", 0x00E676),
+    ("maroon", 0x880E4F),
+    ("navy", 0x311B92),
+    ("olive", 0x808000),
+    ("orange", 0xFF9800),
+    ("purple", 0x9C27B0),
+    ("red", 0xFF5252),
+    ("silver", 0xB2B5BE),
+    ("teal", 0x00897B),
+    ("white", 0xFFFFFF),
+    ("yellow", 0xFFEB3B),
+];
+
+/// The named color `name`, such as the defaults of drawing objects.
+pub(super) fn named(name: &str) -> Value {
+    NAMED
+        .iter()
+        .find(|(named, _)| *named == name)
+        .map_or(Value::None, |(_, rgb)| {
+            let [_, red, green, blue] = rgb.to_be_bytes();
+
+            Value::Color(value::Color::new(red, green, blue, 0xff))
+        })
+}
+
+type Channel = fn(&value::Color) -> f64;
+
+impl NativeModule for Color {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("color");
+
+        for (name, rgb) in NAMED {
+            let [_, red, green, blue] = rgb.to_be_bytes();
+
+            module.export(
+                name,
+                Value::Color(value::Color::new(red, green, blue, 0xff)),
+            );
+        }
+
+        module.export(
+            "new",
+            FunctionBuilder::new("new")
+                .param("color", DataType::Color)
+                .param("transp", DataType::Float)
+                .returns(DataType::Color)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        Ok(match (color(args, "color")?, number(args, "transp")?) {
+                            (Some(color), Some(transparency)) => {
+                                Value::Color(color.with_transparency(transparency))
+                            }
+                            _ => Value::None,
+                        })
+                    })
+                }),
+        );
+
+        module.export(
+            "rgb",
+            FunctionBuilder::new("rgb")
+                .param("red", DataType::Float)
+                .param("green", DataType::Float)
+                .param("blue", DataType::Float)
+                .param_default("transp", DataType::Float, Value::Number(0))
+                .returns(DataType::Color)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let channels = [
+                            number(args, "red")?,
+                            number(args, "green")?,
+                            number(args, "blue")?,
+                        ];
+                        let transparency = number(args, "transp")?.unwrap_or(0.0);
+
+                        Ok(match channels {
+                            [Some(red), Some(green), Some(blue)] => Value::Color(
+                                value::Color::new(byte(red), byte(green), byte(blue), 0xff)
+                                    .with_transparency(transparency),
+                            ),
+                            _ => Value::None,
+                        })
+                    })
+                }),
+        );
+
+        for (name, channel) in [
+            ("r", (|color| color.red as f64) as Channel),
+            ("g", |color| color.green as f64),
+            ("b", |color| color.blue as f64),
+            ("t", value::Color::transparency),
+        ] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param("color", DataType::Color)
+                    .returns(DataType::Float)
+                    .build(move |args| {
+                        evaluate(args, |args| {
+                            Ok(color(args, "color")?
+                                .map_or(Value::None, |color| Value::Float(channel(&color))))
+                        })
+                    }),
+            );
+        }
+
+        // Blends the two colors by where `value` lies between the bottom and top values; values
+        // outside of them give the nearest color.
+        module.export(
+            "from_gradient",
+            FunctionBuilder::new("from_gradient")
+                .param("value", DataType::Float)
+                .param("bottom_value", DataType::Float)
+                .param("top_value", DataType::Float)
+                .param("bottom_color", DataType::Color)
+                .param("top_color", DataType::Color)
+                .returns(DataType::Color)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let values = [
+                            number(args, "value")?,
+                            number(args, "bottom_value")?,
+                            number(args, "top_value")?,
+                        ];
+                        let colors = [color(args, "bottom_color")?, color(args, "top_color")?];
+
+                        Ok(match (values, colors) {
+                            ([Some(value), Some(bottom), Some(top)], [Some(from), Some(to)]) => {
+                                let position = match top == bottom {
+                                    true => 0.0,
+                                    false => ((value - bottom) / (top - bottom)).clamp(0.0, 1.0),
+                                };
+
+                                Value::Color(gradient(from, to, position))
+                            }
+                            _ => Value::None,
+                        })
+                    })
+                }),
+        );
+
+        module
+    }
+}
+
+/// The color argument `name`, or `None` when it's na.
+pub(super) fn color(args: &FArguments, name: &str) -> Result<Option<value::Color>, ArgumentError> {
+    match args.get(name).unwrap_or(Value::None) {
+        Value::Color(color) => Ok(Some(color)),
+        value if value.is_na() => Ok(None),
+        value => Err(ArgumentError(
+            ErrorCode::Type,
+            format!("{name} must be a color, but {} given", value.type_of()),
+        )),
+    }
+}
+
+/// The numeric argument `name`, or `None` when it's na.
+fn number(args: &FArguments, name: &str) -> Result<Option<f64>, ArgumentError> {
+    match args.get(name).unwrap_or(Value::None) {
+        value if value.is_na() => Ok(None),
+        value => value.as_float().map(Some).ok_or_else(|| {
+            ArgumentError(
+                ErrorCode::Type,
+                format!("{name} must be a number, but {} given", value.type_of()),
+            )
+        }),
+    }
+}
+
+fn byte(channel: f64) -> u8 {
+    channel.round().clamp(0.0, 255.0) as u8
+}
+
+fn gradient(from: value::Color, to: value::Color, position: f64) -> value::Color {
+    let blend = |from: u8, to: u8| byte(from as f64 + (to as f64 - from as f64) * position);
+
+    value::Color::new(
+        blend(from.red, to.red),
+        blend(from.green, to.green),
+        blend(from.blue, to.blue),
+        blend(from.alpha, to.alpha),
+    )
+}
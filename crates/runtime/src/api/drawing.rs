@@ -0,0 +1,430 @@
+use ast::datatype::DataType;
+
+use super::array::{any, new_array};
+use super::color::named;
+use super::{evaluate, ArgumentError};
+use crate::drawing::{Drawing, DrawingKind};
+use crate::value::native::{Module, NativeModule};
+use crate::value::{ErrorCode, Value};
+use crate::{FArguments, FunctionBuilder};
+
+/// The namespace of a kind of drawing object: `new`, `copy`, `delete`, `all`, and the getters
+/// and setters of its properties.
+pub struct DrawingNamespace(pub DrawingKind);
+
+/// A namespace of string constants, such as `xloc.bar_index`.
+pub struct Constants(pub &'static str, pub &'static [&'static str]);
+
+impl NativeModule for Constants {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new(self.0);
+
+        for constant in self.1 {
+            module.export(constant, Value::String(constant.to_string()));
+        }
+
+        module
+    }
+}
+
+/// The properties of a kind of object, in the order `new` takes them, and which of them
+/// scripts can read and change.
+struct Spec {
+    /// Name, type and default; properties without a default must be passed to `new`.
+    properties: Vec<(&'static str, DataType, Option<Value>)>,
+    getters: &'static [&'static str],
+    setters: &'static [&'static str],
+    /// Setters of two properties at once, such as `line.set_xy1(id, x, y)`.
+    pairs: &'static [(&'static str, &'static str, &'static str)],
+    constants: &'static [&'static str],
+}
+
+fn text(value: &str) -> Option<Value> {
+    Some(Value::String(value.into()))
+}
+
+fn spec(kind: DrawingKind) -> Spec {
+    let xloc = ("xloc", DataType::String, text("bar_index"));
+    let extend = ("extend", DataType::String, text("none"));
+
+    match kind {
+        DrawingKind::Line => Spec {
+            properties: vec![
+                ("x1", DataType::Int, None),
+                ("y1", DataType::Float, None),
+                ("x2", DataType::Int, None),
+                ("y2", DataType::Float, None),
+                xloc,
+                extend,
+                ("color", DataType::Color, Some(named("blue"))),
+                ("style", DataType::String, text("style_solid")),
+                ("width", DataType::Int, Some(Value::Number(1))),
+            ],
+            getters: &["x1", "y1", "x2", "y2"],
+            setters: &[
+                "x1", "y1", "x2", "y2", "xloc", "extend", "color", "style", "width",
+            ],
+            pairs: &[("set_xy1", "x1", "y1"), ("set_xy2", "x2", "y2")],
+            constants: &[
+                "style_solid",
+                "style_dotted",
+                "style_dashed",
+                "style_arrow_left",
+                "style_arrow_right",
+                "style_arrow_both",
+            ],
+        },
+        DrawingKind::Label => Spec {
+            properties: vec![
+                ("x", DataType::Int, None),
+                ("y", DataType::Float, None),
+                ("text", DataType::String, text("")),
+                xloc,
+                ("yloc", DataType::String, text("price")),
+                ("color", DataType::Color, Some(named("blue"))),
+                ("style", DataType::String, text("style_label_down")),
+                ("textcolor", DataType::Color, Some(named("white"))),
+                ("size", DataType::String, text("normal")),
+                ("textalign", DataType::String, text("align_center")),
+                ("tooltip", DataType::String, text("")),
+            ],
+            getters: &["x", "y", "text"],
+            setters: &[
+                "x",
+                "y",
+                "text",
+                "xloc",
+                "yloc",
+                "color",
+                "style",
+                "textcolor",
+                "size",
+                "textalign",
+                "tooltip",
+            ],
+            pairs: &[("set_xy", "x", "y")],
+            constants: &[
+                "style_none",
+                "style_xcross",
+                "style_cross",
+                "style_triangleup",
+                "style_triangledown",
+                "style_flag",
+                "style_circle",
+                "style_arrowup",
+                "style_arrowdown",
+                "style_square",
+                "style_diamond",
+                "style_label_up",
+                "style_label_down",
+                "style_label_left",
+                "style_label_right",
+                "style_label_center",
+            ],
+        },
+        DrawingKind::Box => Spec {
+            properties: vec![
+                ("left", DataType::Int, None),
+                ("top", DataType::Float, None),
+                ("right", DataType::Int, None),
+                ("bottom", DataType::Float, None),
+                ("border_color", DataType::Color, Some(named("blue"))),
+                ("border_width", DataType::Int, Some(Value::Number(1))),
+                ("border_style", DataType::String, text("style_solid")),
+                extend,
+                xloc,
+                ("bgcolor", DataType::Color, Some(named("blue"))),
+                ("text", DataType::String, text("")),
+                ("text_size", DataType::String, text("auto")),
+                ("text_color", DataType::Color, Some(named("black"))),
+                ("text_halign", DataType::String, text("align_center")),
+                ("text_valign", DataType::String, text("align_center")),
+            ],
+            getters: &["left", "top", "right", "bottom"],
+            setters: &[
+                "left",
+                "top",
+                "right",
+                "bottom",
+                "border_color",
+                "border_width",
+                "border_style",
+                "extend",
+                "bgcolor",
+                "text",
+                "text_size",
+                "text_color",
+                "text_halign",
+                "text_valign",
+            ],
+            pairs: &[
+                ("set_lefttop", "left", "top"),
+                ("set_rightbottom", "right", "bottom"),
+            ],
+            constants: &[],
+        },
+        DrawingKind::Linefill => Spec {
+            properties: vec![
+                ("line1", DataType::Line, None),
+                ("line2", DataType::Line, None),
+                ("color", DataType::Color, None),
+            ],
+            getters: &["line1", "line2"],
+            setters: &["color"],
+            pairs: &[],
+            constants: &[],
+        },
+        DrawingKind::Polyline => Spec {
+            properties: vec![
+                ("points", DataType::Array(Box::new(any())), None),
+                ("curved", DataType::Bool, Some(Value::Boolean(false))),
+                ("closed", DataType::Bool, Some(Value::Boolean(false))),
+                xloc,
+                ("line_color", DataType::Color, Some(named("blue"))),
+                ("fill_color", DataType::Color, Some(Value::None)),
+                ("line_style", DataType::String, text("style_solid")),
+                ("line_width", DataType::Int, Some(Value::Number(1))),
+            ],
+            getters: &[],
+            setters: &[],
+            pairs: &[],
+            constants: &[],
+        },
+    }
+}
+
+fn data_type(kind: DrawingKind) -> DataType {
+    match kind {
+        DrawingKind::Line => DataType::Line,
+        DrawingKind::Label => DataType::Label,
+        DrawingKind::Box => DataType::Box,
+        DrawingKind::Linefill => DataType::LineFill,
+        DrawingKind::Polyline => DataType::Custom("polyline".into()),
+    }
+}
+
+impl NativeModule for DrawingNamespace {
+    fn build_module(&'static self) -> Module {
+        let kind = self.0;
+        let spec = spec(kind);
+        let mut module = Module::new(kind.name());
+
+        for constant in spec.constants {
+            module.export(constant, Value::String(constant.to_string()));
+        }
+
+        let names = spec
+            .properties
+            .iter()
+            .map(|(name, ..)| *name)
+            .collect::<Vec<_>>();
+        let new = spec.properties.into_iter().fold(
+            FunctionBuilder::new("new"),
+            |builder, (name, ty, default)| match default {
+                Some(default) => builder.param_default(name, ty, default),
+                None => builder.param(name, ty),
+            },
+        );
+
+        module.export(
+            "new",
+            new.returns(data_type(kind)).build(move |args| {
+                evaluate(args, |args| {
+                    let properties = names
+                        .iter()
+                        .map(|name| Ok((*name, property(kind, args, name)?)))
+                        .collect::<Result<Vec<_>, ArgumentError>>()?;
+
+                    Ok(Value::Drawing(
+                        args.drawings(|drawings| drawings.create(kind, properties)),
+                    ))
+                })
+            }),
+        );
+
+        module.export(
+            "copy",
+            FunctionBuilder::new("copy")
+                .param("id", data_type(kind))
+                .returns(data_type(kind))
+                .build(move |args| {
+                    evaluate(args, |args| {
+                        Ok(match drawing(kind, args)? {
+                            Some(drawing) => {
+                                args.drawings(|drawings| match drawings.get(&drawing).cloned() {
+                                    Some(object) => {
+                                        Value::Drawing(drawings.create(kind, object.properties))
+                                    }
+                                    None => Value::None,
+                                })
+                            }
+                            None => Value::None,
+                        })
+                    })
+                }),
+        );
+
+        module.export(
+            "delete",
+            FunctionBuilder::new("delete")
+                .param("id", data_type(kind))
+                .build(move |args| {
+                    evaluate(args, |args| {
+                        if let Some(drawing) = drawing(kind, args)? {
+                            args.drawings(|drawings| drawings.delete(&drawing));
+                        }
+
+                        Ok(Value::None)
+                    })
+                }),
+        );
+
+        module.export(
+            "all",
+            FunctionBuilder::new("all")
+                .returns(DataType::Array(Box::new(data_type(kind))))
+                .build(move |args| {
+                    let all = args.drawings(|drawings| drawings.all(kind));
+
+                    args.returns(new_array(all.into_iter().map(Value::Drawing).collect()));
+                }),
+        );
+
+        // A deleted object reads as na.
+        for name in spec.getters {
+            let getter = format!("get_{name}");
+
+            module.export(
+                &getter,
+                FunctionBuilder::new(&getter)
+                    .param("id", data_type(kind))
+                    .build(move |args| {
+                        evaluate(args, |args| {
+                            Ok(drawing(kind, args)?
+                                .and_then(|drawing| {
+                                    args.drawings(|drawings| {
+                                        drawings.get(&drawing)?.get(name).cloned()
+                                    })
+                                })
+                                .unwrap_or(Value::None))
+                        })
+                    }),
+            );
+        }
+
+        for name in spec.setters {
+            let setter = format!("set_{name}");
+
+            module.export(
+                &setter,
+                FunctionBuilder::new(&setter)
+                    .param("id", data_type(kind))
+                    .param(name, any())
+                    .build(move |args| set(kind, args, &[name])),
+            );
+        }
+
+        for (setter, first, second) in spec.pairs {
+            module.export(
+                setter,
+                FunctionBuilder::new(setter)
+                    .param("id", data_type(kind))
+                    .param(first, any())
+                    .param(second, any())
+                    .build(move |args| set(kind, args, &[first, second])),
+            );
+        }
+
+        if kind == DrawingKind::Line {
+            module.export(
+                "get_price",
+                FunctionBuilder::new("get_price")
+                    .param("id", DataType::Line)
+                    .param("x", DataType::Int)
+                    .returns(DataType::Float)
+                    .build(|args| {
+                        evaluate(args, |args| {
+                            let line = drawing(DrawingKind::Line, args)?.and_then(|line| {
+                                args.drawings(|drawings| drawings.get(&line).cloned())
+                            });
+                            let x = args.get("x").and_then(|x| x.as_float());
+
+                            Ok(match (line, x) {
+                                (Some(line), Some(x)) => price(&line.properties, x),
+                                _ => Value::None,
+                            })
+                        })
+                    }),
+            );
+        }
+
+        module
+    }
+}
+
+/// The `id` argument, which must be an object of `kind`, or `None` when it's na.
+fn drawing(kind: DrawingKind, args: &FArguments) -> Result<Option<Drawing>, ArgumentError> {
+    match args.get("id").unwrap_or(Value::None) {
+        Value::Drawing(drawing) if drawing.kind == kind => Ok(Some(drawing)),
+        Value::None => Ok(None),
+        value => Err(ArgumentError(
+            ErrorCode::Type,
+            format!("{kind} expected, but {} given", value.type_of()),
+        )),
+    }
+}
+
+/// The argument for property `name`, checked where a wrong type would make the object
+/// meaningless: the lines of a linefill and the points of a polyline.
+fn property(kind: DrawingKind, args: &FArguments, name: &str) -> Result<Value, ArgumentError> {
+    let value = args.get(name).unwrap_or(Value::None);
+    let valid = match (kind, name, &value) {
+        (DrawingKind::Linefill, "line1" | "line2", Value::Drawing(line)) => {
+            line.kind == DrawingKind::Line
+        }
+        (DrawingKind::Linefill, "line1" | "line2", _) => false,
+        (DrawingKind::Polyline, "points", value) => matches!(value, Value::Array(..)),
+        _ => true,
+    };
+
+    match valid {
+        true => Ok(value),
+        false => Err(ArgumentError(
+            ErrorCode::Type,
+            format!("{kind}.new can't take a {} as {name}", value.type_of()),
+        )),
+    }
+}
+
+/// Sets the properties `names` from the arguments of the same names.
+fn set(kind: DrawingKind, args: &mut FArguments, names: &[&str]) {
+    evaluate(args, |args| {
+        if let Some(drawing) = drawing(kind, args)? {
+            for name in names {
+                let value = args.get(name).unwrap_or(Value::None);
+
+                args.drawings(|drawings| drawings.set(&drawing, name, value));
+            }
+        }
+
+        Ok(Value::None)
+    })
+}
+
+/// The price of a line at bar `x`, extending the line through its two points.
+fn price(properties: &[(&'static str, Value)], x: f64) -> Value {
+    let number = |name: &str| {
+        properties
+            .iter()
+            .find(|(property, _)| *property == name)
+            .and_then(|(_, value)| value.as_float())
+    };
+
+    match (number("x1"), number("y1"), number("x2"), number("y2")) {
+        (Some(x1), Some(y1), Some(x2), Some(y2)) if x1 != x2 => {
+            Value::Float(y1 + (y2 - y1) * (x - x1) / (x2 - x1))
+        }
+        (Some(_), Some(y1), Some(_), Some(_)) => Value::Float(y1),
+        _ => Value::None,
+    }
+}
@@ -0,0 +1,187 @@
+use ast::datatype::DataType;
+use parse::Span;
+
+use super::array::any;
+use crate::input::{InputKind, ScriptInput};
+use crate::value::native::{Module, NativeModule};
+use crate::value::{ErrorCode, Value};
+use crate::{FArguments, FunctionBuilder};
+
+/// `input.*` declarations. Each one evaluates to its default value, or to the value the host set
+/// for it, see [`crate::input::ScriptInputs`].
+pub struct Input;
+
+/// The type of the default of each kind, and the parameters following `defval` and `title`, in
+/// the order Pine takes them.
+fn parameters(kind: InputKind) -> (DataType, &'static [&'static str]) {
+    const NUMBERS: &[&str] = &[
+        "minval", "maxval", "step", "tooltip", "inline", "group", "confirm", "options",
+    ];
+    const OPTIONS: &[&str] = &["options", "tooltip", "inline", "group", "confirm"];
+    const PLAIN: &[&str] = &["tooltip", "inline", "group", "confirm"];
+
+    match kind {
+        InputKind::Int => (DataType::Int, NUMBERS),
+        InputKind::Float => (DataType::Float, NUMBERS),
+        InputKind::Bool => (DataType::Bool, PLAIN),
+        InputKind::String => (DataType::String, OPTIONS),
+        InputKind::Source => (DataType::Float, &["tooltip", "inline", "group"]),
+        InputKind::Timeframe => (DataType::String, OPTIONS),
+        InputKind::Session => (DataType::String, OPTIONS),
+        InputKind::Color => (DataType::Color, PLAIN),
+        InputKind::Enum => (DataType::Custom("enum".into()), OPTIONS),
+    }
+}
+
+impl NativeModule for Input {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("input");
+
+        for kind in [
+            InputKind::Int,
+            InputKind::Float,
+            InputKind::Bool,
+            InputKind::String,
+            InputKind::Source,
+            InputKind::Timeframe,
+            InputKind::Session,
+            InputKind::Color,
+            InputKind::Enum,
+        ] {
+            let (ty, parameters) = parameters(kind);
+            let function = parameters.iter().fold(
+                FunctionBuilder::new(kind.name())
+                    .param("defval", ty.clone())
+                    .param_default("title", DataType::String, Value::None),
+                |builder, name| builder.param_default(name, any(), Value::None),
+            );
+
+            module.export(
+                kind.name(),
+                function.returns(ty).build(move |args| {
+                    let value = declare(kind, args);
+
+                    args.returns(value);
+                }),
+            );
+        }
+
+        module
+    }
+}
+
+/// Checks and records the input declared by `args`, and returns its value.
+fn declare(kind: InputKind, args: &FArguments) -> Value {
+    let default = args.get("defval").unwrap_or(Value::None);
+    let options = match args.get("options") {
+        Some(Value::Array(options, _)) => options.to_vec(),
+        Some(Value::Tuple(options)) => options,
+        _ => vec![],
+    };
+    let text = |name: &str| match args.get(name) {
+        Some(Value::String(text)) => Some(text),
+        _ => None,
+    };
+    let number = |name: &str| args.get(name).and_then(|value| value.as_float());
+    let mut input = ScriptInput {
+        name: None,
+        kind,
+        title: text("title"),
+        default: default.clone(),
+        minval: number("minval"),
+        maxval: number("maxval"),
+        step: number("step"),
+        options: options.clone(),
+        tooltip: text("tooltip"),
+        inline: text("inline"),
+        group: text("group"),
+        value: None,
+        call_site: args.call_site,
+    };
+
+    match kind {
+        InputKind::Enum => {
+            if let error @ Value::Error(..) = enum_input(default, options) {
+                return error;
+            }
+        }
+        // A source's default is the series itself rather than its name.
+        InputKind::Source => {}
+        _ => match input.validate(default) {
+            Ok(default) => input.default = default,
+            Err(message) => {
+                return Value::error(
+                    Span::empty(),
+                    ErrorCode::Type,
+                    format!("invalid default of {kind}: {message}"),
+                )
+            }
+        },
+    }
+
+    let enumeration = match &input.default {
+        Value::EnumMember(member) => Some(member.enumeration.clone()),
+        _ => None,
+    };
+    let value = args.inputs(|inputs| inputs.declare(input));
+
+    // Hosts set sources and enum members by name.
+    match (kind, value) {
+        (InputKind::Source, Value::String(source)) => args.get(source).unwrap_or(Value::None),
+        (InputKind::Enum, Value::String(name)) => {
+            match enumeration.and_then(|enumeration| args.get(enumeration)) {
+                Some(Value::Enum(enumeration)) => match enumeration.get(&name).cloned() {
+                    Some(member) => Value::EnumMember(member),
+                    None => Value::error(
+                        Span::empty(),
+                        ErrorCode::Type,
+                        format!("`{name}` is not a member of {}", enumeration.name),
+                    ),
+                },
+                _ => Value::None,
+            }
+        }
+        (_, value) => value,
+    }
+}
+
+/// The default must be an enum member and, when options are given, one of them; every option
+/// must belong to the same enum.
+fn enum_input(defval: Value, options: Vec<Value>) -> Value {
+    let Value::EnumMember(default) = &defval else {
+        return Value::error(
+            Span::empty(),
+            ErrorCode::Type,
+            format!(
+                "input.enum expects an enum member, but {} given",
+                defval.type_of()
+            ),
+        );
+    };
+
+    let foreign = options.iter().find(|option| {
+        !matches!(option, Value::EnumMember(member) if member.enumeration == default.enumeration)
+    });
+
+    if let Some(option) = foreign {
+        return Value::error(
+            Span::empty(),
+            ErrorCode::Type,
+            format!(
+                "options of input.enum must be members of {}, but {} given",
+                default.enumeration,
+                option.type_of()
+            ),
+        );
+    }
+
+    if !options.is_empty() && !options.contains(&defval) {
+        return Value::error(
+            Span::empty(),
+            ErrorCode::Type,
+            format!("`{}` is not one of the input.enum options", default.name),
+        );
+    }
+
+    defval
+}
@@ -0,0 +1,210 @@
+use ast::datatype::DataType;
+use parse::Span;
+
+use super::array::{any, new_array};
+use super::ArgumentError;
+use crate::value::native::{Module, NativeModule};
+use crate::value::{self, Entries, ErrorCode, Value};
+use crate::{FArguments, FunctionBuilder};
+
+/// The `map` namespace. Keys and values are checked against `map.new<key, value>()` by the
+/// resolver; at runtime keys only need to be of a primitive type, and ints put in a map of
+/// floats become floats.
+pub struct Map;
+
+fn map() -> DataType {
+    DataType::Map(Box::new(any()), Box::new(any()))
+}
+
+impl NativeModule for Map {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("map");
+
+        module.export(
+            "new",
+            FunctionBuilder::new("new").returns(map()).build(|args| {
+                let entries = match args.type_arguments() {
+                    [key, value] => Entries::typed(key.clone(), value.clone()),
+                    _ => Entries::default(),
+                };
+
+                args.returns(new_map(entries))
+            }),
+        );
+
+        module.export(
+            "copy",
+            FunctionBuilder::new("copy")
+                .param("id", map())
+                .returns(map())
+                .build(|args| with_map(args, |_, map| new_map(map.to_entries()))),
+        );
+
+        module.export(
+            "size",
+            FunctionBuilder::new("size")
+                .param("id", map())
+                .returns(DataType::Int)
+                .build(|args| with_map(args, |_, map| Value::Number(map.read().len() as i64))),
+        );
+
+        module.export(
+            "clear",
+            FunctionBuilder::new("clear")
+                .param("id", map())
+                .build(|args| {
+                    with_map(args, |_, map| {
+                        map.write().clear();
+
+                        Value::None
+                    })
+                }),
+        );
+
+        module.export(
+            "put",
+            FunctionBuilder::new("put")
+                .param("id", map())
+                .param("key", any())
+                .param("value", any())
+                .build(|args| {
+                    with_map(args, |args, map| match key(args) {
+                        Ok(key) => {
+                            let value = args.get("value").unwrap_or(Value::None);
+
+                            map.write().insert(key, value).unwrap_or(Value::None)
+                        }
+                        Err(error) => error.into(),
+                    })
+                }),
+        );
+
+        module.export(
+            "put_all",
+            FunctionBuilder::new("put_all")
+                .param("id", map())
+                .param("id2", map())
+                .build(|args| {
+                    with_map(args, |args, map| match args.get("id2") {
+                        Some(Value::Map(other)) => {
+                            let other = other.to_entries();
+                            let mut entries = map.write();
+
+                            for (key, value) in other.iter() {
+                                entries.insert(key.clone(), value.clone());
+                            }
+
+                            Value::None
+                        }
+                        value => not_a_map(value),
+                    })
+                }),
+        );
+
+        for (name, lookup) in [
+            ("get", (|entries, key| entries.get(key).cloned()) as Lookup),
+            ("contains", |entries, key| {
+                Some(Value::Boolean(entries.contains_key(key)))
+            }),
+        ] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param("id", map())
+                    .param("key", any())
+                    .build(move |args| {
+                        with_map(args, |args, map| match key(args) {
+                            Ok(key) => lookup(&map.read(), &key).unwrap_or(Value::None),
+                            Err(error) => error.into(),
+                        })
+                    }),
+            );
+        }
+
+        module.export(
+            "remove",
+            FunctionBuilder::new("remove")
+                .param("id", map())
+                .param("key", any())
+                .build(|args| {
+                    with_map(args, |args, map| match key(args) {
+                        Ok(key) => map.write().remove(&key).unwrap_or(Value::None),
+                        Err(error) => error.into(),
+                    })
+                }),
+        );
+
+        module.export(
+            "keys",
+            FunctionBuilder::new("keys")
+                .param("id", map())
+                .returns(DataType::Array(Box::new(any())))
+                .build(|args| with_map(args, |_, map| new_array(map.read().keys().to_vec()))),
+        );
+
+        module.export(
+            "values",
+            FunctionBuilder::new("values")
+                .param("id", map())
+                .returns(DataType::Array(Box::new(any())))
+                .build(|args| {
+                    with_map(args, |_, map| {
+                        new_array(map.read().iter().map(|(_, value)| value.clone()).collect())
+                    })
+                }),
+        );
+
+        module
+    }
+}
+
+type Lookup = fn(&Entries, &Value) -> Option<Value>;
+
+fn new_map(entries: Entries) -> Value {
+    Value::Map(entries.into())
+}
+
+fn not_a_map(value: Option<Value>) -> Value {
+    match value {
+        None | Some(Value::None) => Value::error(Span::empty(), ErrorCode::Reference, "map is na"),
+        Some(value) => Value::error(
+            Span::empty(),
+            ErrorCode::Type,
+            format!("map expected, but {} given", value.type_of()),
+        ),
+    }
+}
+
+/// Returns the result of `body` for the map passed as `id`, or the type error.
+fn with_map(args: &mut FArguments, body: impl FnOnce(&FArguments, &value::Map) -> Value) {
+    let value = match args.get("id") {
+        Some(Value::Map(map)) => body(args, &map),
+        value => not_a_map(value),
+    };
+
+    args.returns(value);
+}
+
+/// The `key` argument, which must be a non-na value of a primitive type.
+fn key(args: &FArguments) -> Result<Value, ArgumentError> {
+    match args.get("key").unwrap_or(Value::None) {
+        // NaN isn't equal to itself, so it couldn't be found again.
+        Value::None => Err(ArgumentError(
+            ErrorCode::Type,
+            "map keys can't be na".into(),
+        )),
+        Value::Float(key) if key.is_nan() => Err(ArgumentError(
+            ErrorCode::Type,
+            "map keys can't be na".into(),
+        )),
+        key @ (Value::Number(_)
+        | Value::Float(_)
+        | Value::Boolean(_)
+        | Value::String(_)
+        | Value::EnumMember(_)) => Ok(key),
+        key => Err(ArgumentError(
+            ErrorCode::Type,
+            format!("a {} can't be used as a map key", key.type_of()),
+        )),
+    }
+}
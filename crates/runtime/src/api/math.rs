@@ -0,0 +1,409 @@
+use std::f64::consts::{E, PI};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ast::datatype::DataType;
+use parse::Span;
+
+use super::array::{any, int, mean};
+use super::ArgumentError;
+use crate::value::native::{Module, NativeModule};
+use crate::value::{ErrorCode, Value};
+use crate::{FArguments, FunctionBuilder};
+
+/// The `math` namespace. Functions keep ints as ints where Pine does, and return na for an na
+/// argument or a result that isn't a finite number.
+pub struct Math;
+
+/// The golden ratio.
+const PHI: f64 = 1.618_033_988_749_895;
+
+type Unary = fn(f64) -> f64;
+
+impl NativeModule for Math {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("math");
+
+        for (name, value) in [("pi", PI), ("e", E), ("phi", PHI)] {
+            module.export(name, Value::Float(value));
+        }
+
+        module.export(
+            "abs",
+            FunctionBuilder::new("abs")
+                .param("number", any())
+                .overload([DataType::Int], DataType::Int)
+                .overload([DataType::Float], DataType::Float)
+                .build(|args| {
+                    let value = match argument(args, "number") {
+                        Ok(Value::Number(number)) => {
+                            number.checked_abs().map_or(Value::None, Value::Number)
+                        }
+                        Ok(Value::Float(float)) => Value::Float(float.abs()),
+                        Ok(value) => value,
+                        Err(error) => error.into(),
+                    };
+
+                    args.returns(value);
+                }),
+        );
+
+        module.export(
+            "sign",
+            FunctionBuilder::new("sign")
+                .param("number", any())
+                .overload([DataType::Int], DataType::Int)
+                .overload([DataType::Float], DataType::Float)
+                .build(|args| {
+                    let value = match argument(args, "number") {
+                        Ok(Value::Number(number)) => Value::Number(number.signum()),
+                        Ok(Value::Float(0.0)) => Value::Float(0.0),
+                        Ok(Value::Float(float)) => Value::Float(float.signum()),
+                        Ok(value) => value,
+                        Err(error) => error.into(),
+                    };
+
+                    args.returns(value);
+                }),
+        );
+
+        for (name, rounding) in [("ceil", f64::ceil as Unary), ("floor", f64::floor)] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param("number", any())
+                    .returns(DataType::Int)
+                    .build(move |args| {
+                        let value = match argument(args, "number") {
+                            Ok(Value::Float(float)) => to_int(rounding(float)),
+                            Ok(value) => value,
+                            Err(error) => error.into(),
+                        };
+
+                        args.returns(value);
+                    }),
+            );
+        }
+
+        // Without a precision the result is an int, otherwise a float with `precision` decimals.
+        module.export(
+            "round",
+            FunctionBuilder::new("round")
+                .param("number", any())
+                .param_default("precision", DataType::Int, Value::None)
+                .overload([DataType::Float, DataType::Int], DataType::Float)
+                .returns(DataType::Int)
+                .build(|args| {
+                    let value = match (argument(args, "number"), args.get("precision")) {
+                        (Err(error), _) => error.into(),
+                        (Ok(Value::Float(float)), None | Some(Value::None)) => {
+                            to_int(float.round())
+                        }
+                        (Ok(value), None | Some(Value::None)) => value,
+                        (Ok(value), Some(_)) => match (value.as_float(), int(args, "precision")) {
+                            (Some(number), Some(precision)) if precision >= 0 => {
+                                let scale = 10f64.powi(precision.min(i32::MAX as i64) as i32);
+
+                                Value::finite((number * scale).round() / scale)
+                            }
+                            (None, _) => Value::None,
+                            (Some(_), _) => Value::error(
+                                Span::empty(),
+                                ErrorCode::Type,
+                                "precision must be a non-negative int",
+                            ),
+                        },
+                    };
+
+                    args.returns(value);
+                }),
+        );
+
+        module.export(
+            "round_to_mintick",
+            FunctionBuilder::new("round_to_mintick")
+                .param("number", DataType::Float)
+                .returns(DataType::Float)
+                .build(|args| {
+                    let mintick = args.mintick();
+
+                    float(args, |number| (number / mintick).round() * mintick);
+                }),
+        );
+
+        for (name, function) in [
+            ("sqrt", f64::sqrt as Unary),
+            ("exp", f64::exp),
+            ("log", f64::ln),
+            ("log10", f64::log10),
+            ("sin", f64::sin),
+            ("cos", f64::cos),
+            ("tan", f64::tan),
+            ("asin", f64::asin),
+            ("acos", f64::acos),
+            ("atan", f64::atan),
+            ("todegrees", f64::to_degrees),
+            ("toradians", f64::to_radians),
+        ] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param("number", DataType::Float)
+                    .returns(DataType::Float)
+                    .build(move |args| float(args, function)),
+            );
+        }
+
+        module.export(
+            "pow",
+            FunctionBuilder::new("pow")
+                .param("base", DataType::Float)
+                .param("exponent", DataType::Float)
+                .returns(DataType::Float)
+                .build(|args| {
+                    let value = match (argument(args, "base"), argument(args, "exponent")) {
+                        (Err(error), _) | (_, Err(error)) => error.into(),
+                        (Ok(base), Ok(exponent)) => match (base.as_float(), exponent.as_float()) {
+                            (Some(base), Some(exponent)) => Value::finite(base.powf(exponent)),
+                            _ => Value::None,
+                        },
+                    };
+
+                    args.returns(value);
+                }),
+        );
+
+        for (name, pick_int, pick_float) in [
+            (
+                "max",
+                i64::max as fn(i64, i64) -> i64,
+                f64::max as fn(f64, f64) -> f64,
+            ),
+            ("min", i64::min, f64::min),
+        ] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .rest("numbers", any())
+                    .overload([DataType::Int], DataType::Int)
+                    .overload([DataType::Float], DataType::Float)
+                    .build(move |args| {
+                        let value = numbers(args).map(|numbers| match numbers {
+                            Numbers::Ints(ints) => {
+                                ints.into_iter().reduce(pick_int).map(Value::Number)
+                            }
+                            Numbers::Floats(floats) => {
+                                floats.into_iter().reduce(pick_float).map(Value::Float)
+                            }
+                            Numbers::Na => Some(Value::None),
+                        });
+
+                        args.returns(flatten(name, value));
+                    }),
+            );
+        }
+
+        module.export(
+            "avg",
+            FunctionBuilder::new("avg")
+                .rest("numbers", any())
+                .returns(DataType::Float)
+                .build(|args| {
+                    let value = numbers(args).map(|numbers| match numbers {
+                        Numbers::Ints(ints) => {
+                            mean(&ints.into_iter().map(|int| int as f64).collect::<Vec<_>>())
+                                .map(Value::Float)
+                        }
+                        Numbers::Floats(floats) => mean(&floats).map(Value::Float),
+                        Numbers::Na => Some(Value::None),
+                    });
+
+                    args.returns(flatten("avg", value));
+                }),
+        );
+
+        // Without bar history, the window holds the values passed by previous calls from the same
+        // place in the script; the sum is na until it has `length` of them.
+        module.export(
+            "sum",
+            FunctionBuilder::new("sum")
+                .param("source", DataType::Float)
+                .param("length", DataType::Int)
+                .returns(DataType::Float)
+                .build(|args| {
+                    let length = match int(args, "length") {
+                        Some(length) if length > 0 => length as usize,
+                        _ => {
+                            return args.returns(Value::error(
+                                Span::empty(),
+                                ErrorCode::Type,
+                                "length must be a positive int",
+                            ))
+                        }
+                    };
+                    let source = match argument(args, "source") {
+                        Ok(source) => source,
+                        Err(error) => return args.returns(Value::from(error)),
+                    };
+                    let mut window = match args.state() {
+                        Some(Value::Tuple(window)) => window,
+                        _ => Vec::new(),
+                    };
+
+                    window.push(source);
+
+                    if window.len() > length {
+                        window.remove(0);
+                    }
+
+                    let value = match window.len() == length {
+                        true => window
+                            .iter()
+                            .map(Value::as_float)
+                            .sum::<Option<f64>>()
+                            .map_or(Value::None, Value::finite),
+                        false => Value::None,
+                    };
+
+                    args.set_state(Value::Tuple(window));
+                    args.returns(value);
+                }),
+        );
+
+        // Each call site has its own generator, seeded by `seed` on its first call so that the
+        // sequence repeats between runs, or by the clock otherwise.
+        module.export(
+            "random",
+            FunctionBuilder::new("random")
+                .param_default("min", DataType::Float, Value::Float(0.0))
+                .param_default("max", DataType::Float, Value::Float(1.0))
+                .param_default("seed", DataType::Int, Value::None)
+                .returns(DataType::Float)
+                .build(|args| {
+                    let range = match (argument(args, "min"), argument(args, "max")) {
+                        (Err(error), _) | (_, Err(error)) => {
+                            return args.returns(Value::from(error))
+                        }
+                        (Ok(min), Ok(max)) => min.as_float().zip(max.as_float()),
+                    };
+                    let seed = match args.state() {
+                        Some(Value::Number(state)) => state as u64,
+                        _ => int(args, "seed").map_or_else(clock, |seed| seed as u64),
+                    };
+                    let (state, random) = next_random(seed);
+
+                    args.set_state(Value::Number(state as i64));
+                    args.returns(range.map_or(Value::None, |(min, max)| {
+                        Value::Float(min + (max - min) * random)
+                    }));
+                }),
+        );
+
+        module
+    }
+}
+
+/// Numeric arguments of a variadic function.
+enum Numbers {
+    Ints(Vec<i64>),
+    Floats(Vec<f64>),
+    /// At least one of them is na.
+    Na,
+}
+
+/// The `numbers` rest argument, as ints when none of them is a float.
+fn numbers(args: &FArguments) -> Result<Numbers, ArgumentError> {
+    let values = match args.get("numbers") {
+        Some(Value::Array(elements, _)) => elements.to_vec(),
+        _ => Vec::new(),
+    };
+
+    if values.iter().any(Value::is_na) {
+        return Ok(Numbers::Na);
+    }
+
+    if let Some(value) = values.iter().find(|value| value.as_float().is_none()) {
+        return Err(not_a_number(value));
+    }
+
+    match values.iter().all(|value| matches!(value, Value::Number(_))) {
+        true => Ok(Numbers::Ints(
+            values
+                .iter()
+                .filter_map(|value| match value {
+                    Value::Number(number) => Some(*number),
+                    _ => None,
+                })
+                .collect(),
+        )),
+        false => Ok(Numbers::Floats(
+            values.iter().filter_map(Value::as_float).collect(),
+        )),
+    }
+}
+
+/// Result of a variadic function, which needs at least one number.
+fn flatten(name: &str, value: Result<Option<Value>, ArgumentError>) -> Value {
+    match value {
+        Ok(Some(value)) => value,
+        Ok(None) => Value::error(
+            Span::empty(),
+            ErrorCode::Type,
+            format!("math.{name} needs at least one number"),
+        ),
+        Err(error) => error.into(),
+    }
+}
+
+/// The numeric argument `name`; na is returned as is for the caller to propagate.
+fn argument(args: &FArguments, name: &str) -> Result<Value, ArgumentError> {
+    match args.get(name).unwrap_or(Value::None) {
+        value if value.is_na() => Ok(Value::None),
+        value @ (Value::Number(_) | Value::Float(_)) => Ok(value),
+        value => Err(not_a_number(&value)),
+    }
+}
+
+/// Returns `function` of the `number` argument as a float.
+fn float(args: &mut FArguments, function: impl Fn(f64) -> f64) {
+    let value = match argument(args, "number") {
+        Ok(value) => value
+            .as_float()
+            .map_or(Value::None, |number| Value::finite(function(number))),
+        Err(error) => error.into(),
+    };
+
+    args.returns(value);
+}
+
+fn not_a_number(value: &Value) -> ArgumentError {
+    ArgumentError(
+        ErrorCode::Type,
+        format!("number expected, but {} given", value.type_of()),
+    )
+}
+
+/// A rounded float as an int, or na when it doesn't fit.
+fn to_int(number: f64) -> Value {
+    match number.is_finite() && number.abs() < i64::MAX as f64 {
+        true => Value::Number(number as i64),
+        false => Value::None,
+    }
+}
+
+fn clock() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64)
+}
+
+/// The next state of a SplitMix64 generator and a number in `[0, 1)` derived from it.
+fn next_random(state: u64) -> (u64, f64) {
+    let state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut mixed = state;
+
+    mixed = (mixed ^ (mixed >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    mixed = (mixed ^ (mixed >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    mixed ^= mixed >> 31;
+
+    (state, (mixed >> 11) as f64 / (1u64 << 53) as f64)
+}
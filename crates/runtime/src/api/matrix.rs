@@ -0,0 +1,1451 @@
+use ast::datatype::DataType;
+use ast::BinaryOperator;
+use parse::Span;
+
+use super::array::{
+    any, compare, descending, int, mean, median, new_array, ordered, statistic, ASCENDING,
+};
+use super::{evaluate, ArgumentError};
+use crate::value::native::{Module, NativeModule};
+use crate::value::{self, ErrorCode, Grid, Value};
+use crate::{FArguments, FunctionBuilder, Runtime};
+
+/// The `matrix` namespace: dense matrices of any element type, plus linear algebra on numeric
+/// ones. Like arrays, matrices are shared between variables until `matrix.copy`.
+pub struct Matrix;
+
+fn matrix() -> DataType {
+    DataType::Matrix(Box::new(any()))
+}
+
+fn array() -> DataType {
+    DataType::Array(Box::new(any()))
+}
+
+impl NativeModule for Matrix {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("matrix");
+
+        module.export(
+            "new",
+            FunctionBuilder::new("new")
+                .param_default("rows", DataType::Int, Value::Number(0))
+                .param_default("columns", DataType::Int, Value::Number(0))
+                .param_default("initial_value", any(), Value::None)
+                .returns(matrix())
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let rows = size(args, "rows")?;
+                        let columns = size(args, "columns")?;
+                        let initial = args.get("initial_value").unwrap_or(Value::None);
+
+                        Ok(new_matrix(Grid::new(rows, columns, initial)))
+                    })
+                }),
+        );
+
+        module.export(
+            "copy",
+            FunctionBuilder::new("copy")
+                .param("id", matrix())
+                .returns(matrix())
+                .build(|args| evaluate(args, |args| Ok(new_matrix(grid(args, "id")?)))),
+        );
+
+        for (name, count) in [
+            ("rows", (|grid| grid.rows) as fn(&Grid) -> usize),
+            ("columns", |grid| grid.columns),
+            ("elements_count", |grid| grid.elements.len()),
+        ] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param("id", matrix())
+                    .returns(DataType::Int)
+                    .build(move |args| {
+                        evaluate(args, |args| {
+                            Ok(Value::Number(count(&matrix_arg(args, "id")?.read()) as i64))
+                        })
+                    }),
+            );
+        }
+
+        module.export(
+            "get",
+            FunctionBuilder::new("get")
+                .param("id", matrix())
+                .param("row", DataType::Int)
+                .param("column", DataType::Int)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = matrix_arg(args, "id")?;
+                        let grid = matrix.read();
+                        let (row, column) = cell(args, &grid)?;
+
+                        Ok(grid.get(row, column).clone())
+                    })
+                }),
+        );
+
+        module.export(
+            "set",
+            FunctionBuilder::new("set")
+                .param("id", matrix())
+                .param("row", DataType::Int)
+                .param("column", DataType::Int)
+                .param("value", any())
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = matrix_arg(args, "id")?;
+                        let mut grid = matrix.write();
+                        let (row, column) = cell(args, &grid)?;
+
+                        grid.set(row, column, args.get("value").unwrap_or(Value::None));
+
+                        Ok(Value::None)
+                    })
+                }),
+        );
+
+        module.export(
+            "row",
+            FunctionBuilder::new("row")
+                .param("id", matrix())
+                .param("row", DataType::Int)
+                .returns(array())
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let grid = grid(args, "id")?;
+                        let row = position(args, "row", grid.rows, "row")?;
+
+                        Ok(new_array(grid.row(row)))
+                    })
+                }),
+        );
+
+        module.export(
+            "col",
+            FunctionBuilder::new("col")
+                .param("id", matrix())
+                .param("column", DataType::Int)
+                .returns(array())
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let grid = grid(args, "id")?;
+                        let column = position(args, "column", grid.columns, "column")?;
+
+                        Ok(new_array(grid.column(column)))
+                    })
+                }),
+        );
+
+        module.export(
+            "add_row",
+            FunctionBuilder::new("add_row")
+                .param("id", matrix())
+                .param_default("row", DataType::Int, Value::None)
+                .param_default("array_id", array(), Value::None)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = matrix_arg(args, "id")?;
+                        let mut rows = matrix.read().to_rows();
+                        let columns = matrix.read().columns;
+                        let row = insertion(args, "row", rows.len(), "row")?;
+                        let values = line(args, columns, rows.is_empty())?;
+
+                        rows.insert(row, values);
+                        *matrix.write() = Grid::from_rows(rows);
+
+                        Ok(Value::None)
+                    })
+                }),
+        );
+
+        module.export(
+            "add_col",
+            FunctionBuilder::new("add_col")
+                .param("id", matrix())
+                .param_default("column", DataType::Int, Value::None)
+                .param_default("array_id", array(), Value::None)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = matrix_arg(args, "id")?;
+                        let mut columns = transposed(&matrix.read()).to_rows();
+                        let rows = matrix.read().rows;
+                        let column = insertion(args, "column", columns.len(), "column")?;
+                        let values = line(args, rows, columns.is_empty())?;
+
+                        columns.insert(column, values);
+                        *matrix.write() = transposed(&Grid::from_rows(columns));
+
+                        Ok(Value::None)
+                    })
+                }),
+        );
+
+        module.export(
+            "remove_row",
+            FunctionBuilder::new("remove_row")
+                .param("id", matrix())
+                .param_default("row", DataType::Int, Value::None)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = matrix_arg(args, "id")?;
+                        let mut rows = matrix.read().to_rows();
+                        let columns = matrix.read().columns;
+                        let row = removal(args, "row", rows.len(), "row")?;
+                        let removed = rows.remove(row);
+
+                        *matrix.write() = reshaped(rows, columns);
+
+                        Ok(new_array(removed))
+                    })
+                }),
+        );
+
+        module.export(
+            "remove_col",
+            FunctionBuilder::new("remove_col")
+                .param("id", matrix())
+                .param_default("column", DataType::Int, Value::None)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = matrix_arg(args, "id")?;
+                        let mut columns = transposed(&matrix.read()).to_rows();
+                        let rows = matrix.read().rows;
+                        let column = removal(args, "column", columns.len(), "column")?;
+                        let removed = columns.remove(column);
+
+                        *matrix.write() = transposed(&reshaped(columns, rows));
+
+                        Ok(new_array(removed))
+                    })
+                }),
+        );
+
+        module.export(
+            "swap_rows",
+            FunctionBuilder::new("swap_rows")
+                .param("id", matrix())
+                .param("row1", DataType::Int)
+                .param("row2", DataType::Int)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = matrix_arg(args, "id")?;
+                        let mut grid = matrix.write();
+                        let first = position(args, "row1", grid.rows, "row")?;
+                        let second = position(args, "row2", grid.rows, "row")?;
+
+                        for column in 0..grid.columns {
+                            let value = grid.get(first, column).clone();
+                            let other = grid.get(second, column).clone();
+
+                            grid.set(first, column, other);
+                            grid.set(second, column, value);
+                        }
+
+                        Ok(Value::None)
+                    })
+                }),
+        );
+
+        module.export(
+            "swap_columns",
+            FunctionBuilder::new("swap_columns")
+                .param("id", matrix())
+                .param("column1", DataType::Int)
+                .param("column2", DataType::Int)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = matrix_arg(args, "id")?;
+                        let mut grid = matrix.write();
+                        let first = position(args, "column1", grid.columns, "column")?;
+                        let second = position(args, "column2", grid.columns, "column")?;
+
+                        for row in 0..grid.rows {
+                            let value = grid.get(row, first).clone();
+                            let other = grid.get(row, second).clone();
+
+                            grid.set(row, first, other);
+                            grid.set(row, second, value);
+                        }
+
+                        Ok(Value::None)
+                    })
+                }),
+        );
+
+        module.export(
+            "fill",
+            FunctionBuilder::new("fill")
+                .param("id", matrix())
+                .param("value", any())
+                .param_default("from_row", DataType::Int, Value::Number(0))
+                .param_default("to_row", DataType::Int, Value::None)
+                .param_default("from_column", DataType::Int, Value::Number(0))
+                .param_default("to_column", DataType::Int, Value::None)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = matrix_arg(args, "id")?;
+                        let mut grid = matrix.write();
+                        let rows = span(args, "from_row", "to_row", grid.rows)?;
+                        let columns = span(args, "from_column", "to_column", grid.columns)?;
+                        let value = args.get("value").unwrap_or(Value::None);
+
+                        for row in rows.0..rows.1 {
+                            for column in columns.0..columns.1 {
+                                grid.set(row, column, value.clone());
+                            }
+                        }
+
+                        Ok(Value::None)
+                    })
+                }),
+        );
+
+        module.export(
+            "submatrix",
+            FunctionBuilder::new("submatrix")
+                .param("id", matrix())
+                .param_default("from_row", DataType::Int, Value::Number(0))
+                .param_default("to_row", DataType::Int, Value::None)
+                .param_default("from_column", DataType::Int, Value::Number(0))
+                .param_default("to_column", DataType::Int, Value::None)
+                .returns(matrix())
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let grid = grid(args, "id")?;
+                        let rows = span(args, "from_row", "to_row", grid.rows)?;
+                        let columns = span(args, "from_column", "to_column", grid.columns)?;
+                        let submatrix = (rows.0..rows.1)
+                            .map(|row| grid.row(row)[columns.0..columns.1].to_vec())
+                            .collect::<Vec<_>>();
+
+                        Ok(new_matrix(reshaped(submatrix, columns.1 - columns.0)))
+                    })
+                }),
+        );
+
+        module.export(
+            "reshape",
+            FunctionBuilder::new("reshape")
+                .param("id", matrix())
+                .param("rows", DataType::Int)
+                .param("columns", DataType::Int)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = matrix_arg(args, "id")?;
+                        let mut grid = matrix.write();
+                        let rows = size(args, "rows")?;
+                        let columns = size(args, "columns")?;
+
+                        if rows * columns != grid.elements.len() {
+                            return Err(ArgumentError::out_of_bounds(format!(
+                                "a {}x{} matrix can't be reshaped to {rows}x{columns}",
+                                grid.rows, grid.columns
+                            )));
+                        }
+
+                        grid.rows = rows;
+                        grid.columns = columns;
+
+                        Ok(Value::None)
+                    })
+                }),
+        );
+
+        module.export(
+            "reverse",
+            FunctionBuilder::new("reverse")
+                .param("id", matrix())
+                .build(|args| {
+                    evaluate(args, |args| {
+                        matrix_arg(args, "id")?.write().elements.reverse();
+
+                        Ok(Value::None)
+                    })
+                }),
+        );
+
+        module.export(
+            "transpose",
+            FunctionBuilder::new("transpose")
+                .param("id", matrix())
+                .returns(matrix())
+                .build(|args| {
+                    evaluate(args, |args| Ok(new_matrix(transposed(&grid(args, "id")?))))
+                }),
+        );
+
+        module.export(
+            "concat",
+            FunctionBuilder::new("concat")
+                .param("id1", matrix())
+                .param("id2", matrix())
+                .returns(matrix())
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = matrix_arg(args, "id1")?;
+                        let other = grid(args, "id2")?;
+                        let mut grid = matrix.write();
+
+                        if grid.columns != other.columns && !grid.elements.is_empty() {
+                            return Err(mismatch("concat", &grid, &other));
+                        }
+
+                        grid.columns = other.columns;
+                        grid.rows += other.rows;
+                        grid.elements.extend(other.elements);
+                        drop(grid);
+
+                        Ok(Value::Matrix(matrix))
+                    })
+                }),
+        );
+
+        for (name, operator) in [
+            ("sum", BinaryOperator::Add),
+            ("diff", BinaryOperator::Subtract),
+        ] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param("id1", matrix())
+                    .param("id2", any())
+                    .returns(matrix())
+                    .build(move |args| {
+                        evaluate(args, |args| {
+                            let grid = grid(args, "id1")?;
+
+                            match args.get("id2") {
+                                Some(Value::Matrix(other)) => {
+                                    let other = other.to_grid();
+
+                                    if (grid.rows, grid.columns) != (other.rows, other.columns) {
+                                        return Err(mismatch(name, &grid, &other));
+                                    }
+
+                                    let elements = grid.elements.iter().zip(&other.elements);
+
+                                    elementwise(&grid, elements, operator)
+                                }
+                                scalar => {
+                                    let scalar = scalar.unwrap_or(Value::None);
+                                    let elements =
+                                        grid.elements.iter().zip(std::iter::repeat(&scalar));
+
+                                    elementwise(&grid, elements, operator)
+                                }
+                            }
+                        })
+                    }),
+            );
+        }
+
+        module.export(
+            "mult",
+            FunctionBuilder::new("mult")
+                .param("id1", matrix())
+                .param("id2", any())
+                .overload([matrix(), array()], array())
+                .overload([matrix(), any()], matrix())
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let grid = grid(args, "id1")?;
+
+                        match args.get("id2") {
+                            Some(Value::Matrix(other)) => {
+                                let other = other.to_grid();
+
+                                if grid.columns != other.rows {
+                                    return Err(mismatch("mult", &grid, &other));
+                                }
+
+                                let product = Dense::new(&grid, "mult")?
+                                    .product(&Dense::new(&other, "mult")?);
+
+                                Ok(product.into_value())
+                            }
+                            Some(Value::Array(elements, _)) => {
+                                let vector = Grid::from_rows(
+                                    elements
+                                        .to_vec()
+                                        .into_iter()
+                                        .map(|value| vec![value])
+                                        .collect(),
+                                );
+
+                                if grid.columns != vector.rows {
+                                    return Err(mismatch("mult", &grid, &vector));
+                                }
+
+                                let product = Dense::new(&grid, "mult")?
+                                    .product(&Dense::new(&vector, "mult")?);
+
+                                Ok(new_array(product.data.into_iter().map(number).collect()))
+                            }
+                            scalar => {
+                                let scalar = scalar.unwrap_or(Value::None);
+                                let elements = grid.elements.iter().zip(std::iter::repeat(&scalar));
+
+                                elementwise(&grid, elements, BinaryOperator::Multiply)
+                            }
+                        }
+                    })
+                }),
+        );
+
+        module.export(
+            "pow",
+            FunctionBuilder::new("pow")
+                .param("id", matrix())
+                .param("power", DataType::Int)
+                .returns(DataType::Matrix(Box::new(DataType::Float)))
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = square(args, "pow")?;
+                        let power = size(args, "power")?;
+                        let result = (0..power).fold(Dense::identity(matrix.rows), |result, _| {
+                            result.product(&matrix)
+                        });
+
+                        Ok(result.into_value())
+                    })
+                }),
+        );
+
+        module.export(
+            "kron",
+            FunctionBuilder::new("kron")
+                .param("id1", matrix())
+                .param("id2", matrix())
+                .returns(DataType::Matrix(Box::new(DataType::Float)))
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let first = Dense::new(&grid(args, "id1")?, "kron")?;
+                        let second = Dense::new(&grid(args, "id2")?, "kron")?;
+                        let mut product =
+                            Dense::zeros(first.rows * second.rows, first.columns * second.columns);
+
+                        for row in 0..product.rows {
+                            for column in 0..product.columns {
+                                product.set(
+                                    row,
+                                    column,
+                                    first.at(row / second.rows, column / second.columns)
+                                        * second.at(row % second.rows, column % second.columns),
+                                );
+                            }
+                        }
+
+                        Ok(product.into_value())
+                    })
+                }),
+        );
+
+        module.export(
+            "det",
+            FunctionBuilder::new("det")
+                .param("id", matrix())
+                .returns(DataType::Float)
+                .build(|args| evaluate(args, |args| Ok(number(determinant(square(args, "det")?))))),
+        );
+
+        module.export(
+            "inv",
+            FunctionBuilder::new("inv")
+                .param("id", matrix())
+                .returns(DataType::Matrix(Box::new(DataType::Float)))
+                .build(|args| {
+                    evaluate(args, |args| {
+                        Ok(inverse(&square(args, "inv")?).map_or(Value::None, Dense::into_value))
+                    })
+                }),
+        );
+
+        module.export(
+            "pinv",
+            FunctionBuilder::new("pinv")
+                .param("id", matrix())
+                .returns(DataType::Matrix(Box::new(DataType::Float)))
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = Dense::new(&grid(args, "id")?, "pinv")?;
+
+                        Ok(pseudo_inverse(&matrix).into_value())
+                    })
+                }),
+        );
+
+        module.export(
+            "rank",
+            FunctionBuilder::new("rank")
+                .param("id", matrix())
+                .returns(DataType::Int)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = Dense::new(&grid(args, "id")?, "rank")?;
+
+                        Ok(Value::Number(rank(matrix) as i64))
+                    })
+                }),
+        );
+
+        module.export(
+            "trace",
+            FunctionBuilder::new("trace")
+                .param("id", matrix())
+                .returns(DataType::Float)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = square(args, "trace")?;
+
+                        Ok(number(
+                            (0..matrix.rows).map(|index| matrix.at(index, index)).sum(),
+                        ))
+                    })
+                }),
+        );
+
+        module.export(
+            "eigenvalues",
+            FunctionBuilder::new("eigenvalues")
+                .param("id", matrix())
+                .returns(DataType::Array(Box::new(DataType::Float)))
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = square(args, "eigenvalues")?;
+                        let values = match matrix.is_symmetric() {
+                            true => symmetric_eigen(&matrix).0,
+                            false => eigenvalues(&matrix),
+                        };
+
+                        Ok(new_array(values.into_iter().map(number).collect()))
+                    })
+                }),
+        );
+
+        module.export(
+            "eigenvectors",
+            FunctionBuilder::new("eigenvectors")
+                .param("id", matrix())
+                .returns(DataType::Matrix(Box::new(DataType::Float)))
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = square(args, "eigenvectors")?;
+
+                        match matrix.is_symmetric() {
+                            true => Ok(symmetric_eigen(&matrix).1.into_value()),
+                            false => Err(ArgumentError(
+                                ErrorCode::Implementing,
+                                "matrix.eigenvectors is only implemented for symmetric matrices"
+                                    .into(),
+                            )),
+                        }
+                    })
+                }),
+        );
+
+        module.export(
+            "avg",
+            FunctionBuilder::new("avg")
+                .param("id", matrix())
+                .returns(DataType::Float)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        Ok(statistic(&grid(args, "id")?.elements, mean))
+                    })
+                }),
+        );
+
+        module.export(
+            "median",
+            FunctionBuilder::new("median")
+                .param("id", matrix())
+                .returns(DataType::Float)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        Ok(statistic(&grid(args, "id")?.elements, median))
+                    })
+                }),
+        );
+
+        for (name, descending) in [("min", false), ("max", true)] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param("id", matrix())
+                    .build(move |args| {
+                        evaluate(args, |args| {
+                            Ok(grid(args, "id")?
+                                .elements
+                                .into_iter()
+                                .filter(|element| element.as_float().is_some())
+                                .min_by(|a, b| ordered(compare(a, b), descending))
+                                .unwrap_or(Value::None))
+                        })
+                    }),
+            );
+        }
+
+        module.export(
+            "sort",
+            FunctionBuilder::new("sort")
+                .param("id", matrix())
+                .param_default("column", DataType::Int, Value::Number(0))
+                .param_default("order", DataType::String, Value::String(ASCENDING.into()))
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = matrix_arg(args, "id")?;
+                        let mut rows = matrix.read().to_rows();
+                        let columns = matrix.read().columns;
+                        let column = position(args, "column", columns, "column")?;
+                        let descending = descending(args);
+
+                        rows.sort_by(|a, b| ordered(compare(&a[column], &b[column]), descending));
+                        *matrix.write() = reshaped(rows, columns);
+
+                        Ok(Value::None)
+                    })
+                }),
+        );
+
+        module.export(
+            "is_square",
+            FunctionBuilder::new("is_square")
+                .param("id", matrix())
+                .returns(DataType::Bool)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let grid = grid(args, "id")?;
+
+                        Ok(Value::Boolean(grid.rows == grid.columns))
+                    })
+                }),
+        );
+
+        for (name, test) in [
+            ("is_symmetric", Dense::is_symmetric as fn(&Dense) -> bool),
+            ("is_diagonal", |matrix| {
+                matrix
+                    .cells()
+                    .all(|(row, column, value)| row == column || value == 0.0)
+            }),
+            ("is_identity", |matrix| {
+                matrix
+                    .cells()
+                    .all(|(row, column, value)| value == f64::from(u8::from(row == column)))
+            }),
+        ] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param("id", matrix())
+                    .returns(DataType::Bool)
+                    .build(move |args| {
+                        evaluate(args, |args| {
+                            let grid = grid(args, "id")?;
+                            let square = grid.rows == grid.columns;
+
+                            Ok(Value::Boolean(square && test(&Dense::new(&grid, name)?)))
+                        })
+                    }),
+            );
+        }
+
+        module.export(
+            "is_zero",
+            FunctionBuilder::new("is_zero")
+                .param("id", matrix())
+                .returns(DataType::Bool)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let matrix = Dense::new(&grid(args, "id")?, "is_zero")?;
+
+                        Ok(Value::Boolean(
+                            matrix.data.iter().all(|value| *value == 0.0),
+                        ))
+                    })
+                }),
+        );
+
+        module
+    }
+}
+
+fn new_matrix(grid: Grid) -> Value {
+    Value::Matrix(grid.into())
+}
+
+/// Float result of a numeric function; NaN, from na elements, becomes na.
+fn number(number: f64) -> Value {
+    match number.is_nan() {
+        true => Value::None,
+        false => Value::Float(number),
+    }
+}
+
+fn matrix_arg(args: &FArguments, name: &str) -> Result<value::Matrix, ArgumentError> {
+    match args.get(name) {
+        Some(Value::Matrix(matrix)) => Ok(matrix),
+        None | Some(Value::None) => Err(ArgumentError(ErrorCode::Reference, "matrix is na".into())),
+        Some(value) => Err(ArgumentError(
+            ErrorCode::Type,
+            format!("matrix expected, but {} given", value.type_of()),
+        )),
+    }
+}
+
+/// A snapshot of the matrix passed as `name`.
+fn grid(args: &FArguments, name: &str) -> Result<Grid, ArgumentError> {
+    Ok(matrix_arg(args, name)?.to_grid())
+}
+
+/// The numeric square matrix passed as `id`.
+fn square(args: &FArguments, function: &str) -> Result<Dense, ArgumentError> {
+    let grid = grid(args, "id")?;
+
+    match grid.rows == grid.columns {
+        true => Dense::new(&grid, function),
+        false => Err(ArgumentError(
+            ErrorCode::Type,
+            format!(
+                "matrix.{function} needs a square matrix, but it is {}x{}",
+                grid.rows, grid.columns
+            ),
+        )),
+    }
+}
+
+fn size(args: &FArguments, name: &str) -> Result<usize, ArgumentError> {
+    match int(args, name) {
+        Some(size) if size >= 0 => Ok(size as usize),
+        _ => Err(ArgumentError(
+            ErrorCode::Type,
+            format!("{name} must be a non-negative int"),
+        )),
+    }
+}
+
+fn out_of_bounds(index: i64, len: usize, what: &str) -> ArgumentError {
+    ArgumentError::out_of_bounds(format!(
+        "{what} {index} is out of bounds, the matrix has {len} {what}s"
+    ))
+}
+
+/// Row or column argument `name` among `len` of them.
+fn position(args: &FArguments, name: &str, len: usize, what: &str) -> Result<usize, ArgumentError> {
+    let index = int(args, name)
+        .ok_or_else(|| ArgumentError(ErrorCode::Type, format!("{name} must be an int")))?;
+
+    match 0 <= index && index < len as i64 {
+        true => Ok(index as usize),
+        false => Err(out_of_bounds(index, len, what)),
+    }
+}
+
+/// The `row` and `column` arguments of `get`/`set`.
+fn cell(args: &FArguments, grid: &Grid) -> Result<(usize, usize), ArgumentError> {
+    let row = position(args, "row", grid.rows, "row")?;
+    let column = position(args, "column", grid.columns, "column")?;
+
+    Ok((row, column))
+}
+
+/// Where `add_row`/`add_col` insert: before `name`, or at the end when it is na.
+fn insertion(
+    args: &FArguments,
+    name: &str,
+    len: usize,
+    what: &str,
+) -> Result<usize, ArgumentError> {
+    match args.get(name) {
+        None | Some(Value::None) => Ok(len),
+        Some(_) => position(args, name, len + 1, what),
+    }
+}
+
+/// What `remove_row`/`remove_col` remove: `name`, or the last one when it is na.
+fn removal(args: &FArguments, name: &str, len: usize, what: &str) -> Result<usize, ArgumentError> {
+    match args.get(name) {
+        None | Some(Value::None) if len > 0 => Ok(len - 1),
+        None | Some(Value::None) => Err(out_of_bounds(0, len, what)),
+        Some(_) => position(args, name, len, what),
+    }
+}
+
+/// `from` (inclusive) to `to` (exclusive, the end when na) among `len` rows or columns.
+fn span(
+    args: &FArguments,
+    from: &str,
+    to: &str,
+    len: usize,
+) -> Result<(usize, usize), ArgumentError> {
+    let start = int(args, from).unwrap_or(0);
+    let end = int(args, to).unwrap_or(len as i64);
+
+    match 0 <= start && start <= end && end <= len as i64 {
+        true => Ok((start as usize, end as usize)),
+        false => Err(ArgumentError::out_of_bounds(format!(
+            "range {start}..{end} is out of bounds, the matrix has {len} of them"
+        ))),
+    }
+}
+
+/// The row or column added by `add_row`/`add_col`: `array_id`, which must have `len` elements
+/// unless the matrix is empty, or na values when it is na.
+fn line(args: &FArguments, len: usize, empty: bool) -> Result<Vec<Value>, ArgumentError> {
+    let values = match args.get("array_id") {
+        None | Some(Value::None) => return Ok(vec![Value::None; len]),
+        Some(Value::Array(elements, _)) => elements.to_vec(),
+        Some(Value::Tuple(elements)) => elements,
+        Some(value) => {
+            return Err(ArgumentError(
+                ErrorCode::Type,
+                format!("array expected, but {} given", value.type_of()),
+            ))
+        }
+    };
+
+    match empty || values.len() == len {
+        true => Ok(values),
+        false => Err(ArgumentError::out_of_bounds(format!(
+            "{} elements given where the matrix needs {len}",
+            values.len()
+        ))),
+    }
+}
+
+/// A grid from `rows`, keeping `columns` when there are none left.
+fn reshaped(rows: Vec<Vec<Value>>, columns: usize) -> Grid {
+    match rows.is_empty() {
+        true => Grid::new(0, columns, Value::None),
+        false => Grid::from_rows(rows),
+    }
+}
+
+fn transposed(grid: &Grid) -> Grid {
+    let columns = (0..grid.columns)
+        .map(|column| grid.column(column))
+        .collect();
+
+    reshaped(columns, grid.rows)
+}
+
+fn mismatch(function: &str, first: &Grid, second: &Grid) -> ArgumentError {
+    ArgumentError(
+        ErrorCode::Type,
+        format!(
+            "matrix.{function} can't be applied to {}x{} and {}x{} matrices",
+            first.rows, first.columns, second.rows, second.columns
+        ),
+    )
+}
+
+/// Applies `operator` to each pair of elements, giving a matrix shaped like `grid`.
+fn elementwise<'a>(
+    grid: &Grid,
+    pairs: impl Iterator<Item = (&'a Value, &'a Value)>,
+    operator: BinaryOperator,
+) -> Result<Value, ArgumentError> {
+    let elements = pairs
+        .map(|(a, b)| {
+            match Runtime::binary_operation(operator, a.clone(), b.clone(), Span::empty()) {
+                Value::Error(_, code, message) => Err(ArgumentError(code, message)),
+                value => Ok(value),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(new_matrix(Grid { elements, ..*grid }))
+}
+
+/// A numeric copy of a matrix for linear algebra, with na elements as NaN.
+#[derive(Clone, Debug)]
+struct Dense {
+    rows: usize,
+    columns: usize,
+    data: Vec<f64>,
+}
+
+impl Dense {
+    fn new(grid: &Grid, function: &str) -> Result<Self, ArgumentError> {
+        let data = grid
+            .elements
+            .iter()
+            .map(|element| match element {
+                Value::None => Ok(f64::NAN),
+                element => element.as_float().ok_or_else(|| {
+                    ArgumentError(
+                        ErrorCode::Type,
+                        format!(
+                            "matrix.{function} needs a numeric matrix, but it holds a {}",
+                            element.type_of()
+                        ),
+                    )
+                }),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            rows: grid.rows,
+            columns: grid.columns,
+            data,
+        })
+    }
+
+    fn zeros(rows: usize, columns: usize) -> Self {
+        Self {
+            rows,
+            columns,
+            data: vec![0.0; rows * columns],
+        }
+    }
+
+    fn identity(size: usize) -> Self {
+        let mut identity = Self::zeros(size, size);
+
+        for index in 0..size {
+            identity.set(index, index, 1.0);
+        }
+
+        identity
+    }
+
+    fn at(&self, row: usize, column: usize) -> f64 {
+        self.data[row * self.columns + column]
+    }
+
+    fn set(&mut self, row: usize, column: usize, value: f64) {
+        self.data[row * self.columns + column] = value;
+    }
+
+    fn cells(&self) -> impl Iterator<Item = (usize, usize, f64)> + '_ {
+        self.data
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (index / self.columns, index % self.columns, *value))
+    }
+
+    fn swap_rows(&mut self, first: usize, second: usize) {
+        for column in 0..self.columns {
+            self.data.swap(
+                first * self.columns + column,
+                second * self.columns + column,
+            );
+        }
+    }
+
+    fn transpose(&self) -> Self {
+        let mut transpose = Self::zeros(self.columns, self.rows);
+
+        for (row, column, value) in self.cells() {
+            transpose.set(column, row, value);
+        }
+
+        transpose
+    }
+
+    fn product(&self, other: &Dense) -> Self {
+        let mut product = Self::zeros(self.rows, other.columns);
+
+        for row in 0..self.rows {
+            for column in 0..other.columns {
+                let value = (0..self.columns)
+                    .map(|index| self.at(row, index) * other.at(index, column))
+                    .sum();
+
+                product.set(row, column, value);
+            }
+        }
+
+        product
+    }
+
+    fn is_symmetric(&self) -> bool {
+        self.rows == self.columns
+            && self
+                .cells()
+                .all(|(row, column, value)| value == self.at(column, row))
+    }
+
+    /// Below this, a pivot or eigenvalue is treated as zero.
+    fn tolerance(&self) -> f64 {
+        let largest = self
+            .data
+            .iter()
+            .fold(0.0, |largest: f64, value| largest.max(value.abs()));
+
+        largest * self.rows.max(self.columns) as f64 * f64::EPSILON
+    }
+
+    fn into_value(self) -> Value {
+        new_matrix(Grid {
+            rows: self.rows,
+            columns: self.columns,
+            elements: self.data.into_iter().map(number).collect(),
+        })
+    }
+}
+
+/// Index of the row at or below `column` with the largest absolute value in that column.
+fn pivot(matrix: &Dense, column: usize, from: usize) -> usize {
+    (from..matrix.rows)
+        .max_by(|a, b| {
+            matrix
+                .at(*a, column)
+                .abs()
+                .total_cmp(&matrix.at(*b, column).abs())
+        })
+        .unwrap_or(from)
+}
+
+/// Determinant by Gaussian elimination with partial pivoting.
+fn determinant(mut matrix: Dense) -> f64 {
+    let size = matrix.rows;
+    let mut determinant = 1.0;
+
+    for column in 0..size {
+        let pivot = pivot(&matrix, column, column);
+
+        if matrix.at(pivot, column) == 0.0 {
+            return 0.0;
+        }
+
+        if pivot != column {
+            matrix.swap_rows(pivot, column);
+            determinant = -determinant;
+        }
+
+        determinant *= matrix.at(column, column);
+
+        for row in column + 1..size {
+            let factor = matrix.at(row, column) / matrix.at(column, column);
+
+            for index in column..size {
+                let value = matrix.at(row, index) - factor * matrix.at(column, index);
+
+                matrix.set(row, index, value);
+            }
+        }
+    }
+
+    determinant
+}
+
+/// Inverse by Gauss-Jordan elimination, `None` when the matrix is singular.
+fn inverse(matrix: &Dense) -> Option<Dense> {
+    let size = matrix.rows;
+    let tolerance = matrix.tolerance();
+    let mut matrix = matrix.clone();
+    let mut inverse = Dense::identity(size);
+
+    for column in 0..size {
+        let pivot = pivot(&matrix, column, column);
+
+        if matrix.at(pivot, column).abs() <= tolerance || matrix.at(pivot, column).is_nan() {
+            return None;
+        }
+
+        matrix.swap_rows(pivot, column);
+        inverse.swap_rows(pivot, column);
+
+        let scale = matrix.at(column, column);
+
+        for index in 0..size {
+            matrix.set(column, index, matrix.at(column, index) / scale);
+            inverse.set(column, index, inverse.at(column, index) / scale);
+        }
+
+        for row in (0..size).filter(|row| *row != column) {
+            let factor = matrix.at(row, column);
+
+            for index in 0..size {
+                matrix.set(
+                    row,
+                    index,
+                    matrix.at(row, index) - factor * matrix.at(column, index),
+                );
+                inverse.set(
+                    row,
+                    index,
+                    inverse.at(row, index) - factor * inverse.at(column, index),
+                );
+            }
+        }
+    }
+
+    Some(inverse)
+}
+
+/// Number of linearly independent rows, by row reduction.
+fn rank(mut matrix: Dense) -> usize {
+    let tolerance = matrix.tolerance();
+    let mut rank = 0;
+
+    for column in 0..matrix.columns {
+        if rank == matrix.rows {
+            break;
+        }
+
+        let pivot = pivot(&matrix, column, rank);
+
+        if matrix.at(pivot, column).abs() <= tolerance {
+            continue;
+        }
+
+        matrix.swap_rows(pivot, rank);
+
+        for row in rank + 1..matrix.rows {
+            let factor = matrix.at(row, column) / matrix.at(rank, column);
+
+            for index in column..matrix.columns {
+                let value = matrix.at(row, index) - factor * matrix.at(rank, index);
+
+                matrix.set(row, index, value);
+            }
+        }
+
+        rank += 1;
+    }
+
+    rank
+}
+
+/// Eigenvalues in descending order and the matching eigenvectors as columns of a symmetric
+/// matrix, by cyclic Jacobi rotations.
+fn symmetric_eigen(matrix: &Dense) -> (Vec<f64>, Dense) {
+    let size = matrix.rows;
+    let mut matrix = matrix.clone();
+    let mut vectors = Dense::identity(size);
+
+    for _ in 0..100 {
+        let off_diagonal = matrix
+            .cells()
+            .filter(|(row, column, _)| row != column)
+            .map(|(_, _, value)| value * value)
+            .sum::<f64>();
+
+        if off_diagonal <= f64::MIN_POSITIVE || off_diagonal.is_nan() {
+            break;
+        }
+
+        for p in 0..size {
+            for q in p + 1..size {
+                let apq = matrix.at(p, q);
+
+                if apq == 0.0 {
+                    continue;
+                }
+
+                let theta = (matrix.at(q, q) - matrix.at(p, p)) / (2.0 * apq);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for k in 0..size {
+                    let (kp, kq) = (matrix.at(k, p), matrix.at(k, q));
+
+                    matrix.set(k, p, c * kp - s * kq);
+                    matrix.set(k, q, s * kp + c * kq);
+                }
+
+                for k in 0..size {
+                    let (pk, qk) = (matrix.at(p, k), matrix.at(q, k));
+
+                    matrix.set(p, k, c * pk - s * qk);
+                    matrix.set(q, k, s * pk + c * qk);
+                }
+
+                for k in 0..size {
+                    let (kp, kq) = (vectors.at(k, p), vectors.at(k, q));
+
+                    vectors.set(k, p, c * kp - s * kq);
+                    vectors.set(k, q, s * kp + c * kq);
+                }
+            }
+        }
+    }
+
+    let mut order = (0..size).collect::<Vec<_>>();
+
+    order.sort_by(|a, b| matrix.at(*b, *b).total_cmp(&matrix.at(*a, *a)));
+
+    let values = order
+        .iter()
+        .map(|index| matrix.at(*index, *index))
+        .collect();
+    let mut sorted = Dense::zeros(size, size);
+
+    for (column, index) in order.into_iter().enumerate() {
+        for row in 0..size {
+            sorted.set(row, column, vectors.at(row, index));
+        }
+    }
+
+    (values, sorted)
+}
+
+/// Real eigenvalues of a general square matrix in descending order, by QR iteration.
+/// Complex conjugate pairs don't converge and come out as approximations.
+fn eigenvalues(matrix: &Dense) -> Vec<f64> {
+    let size = matrix.rows;
+    let mut matrix = matrix.clone();
+
+    for _ in 0..500 {
+        let (q, r) = decompose(&matrix);
+
+        matrix = r.product(&q);
+
+        let converged = (1..size)
+            .all(|row| (0..row).all(|column| matrix.at(row, column).abs() <= matrix.tolerance()));
+
+        if converged {
+            break;
+        }
+    }
+
+    let mut values = (0..size)
+        .map(|index| matrix.at(index, index))
+        .collect::<Vec<_>>();
+
+    values.sort_by(|a, b| b.total_cmp(a));
+    values
+}
+
+/// QR decomposition by modified Gram-Schmidt.
+fn decompose(matrix: &Dense) -> (Dense, Dense) {
+    let size = matrix.columns;
+    let mut q = matrix.clone();
+    let mut r = Dense::zeros(size, size);
+
+    for column in 0..size {
+        let norm = (0..q.rows)
+            .map(|row| q.at(row, column).powi(2))
+            .sum::<f64>()
+            .sqrt();
+
+        r.set(column, column, norm);
+
+        for row in 0..q.rows {
+            let value = match norm == 0.0 {
+                true => 0.0,
+                false => q.at(row, column) / norm,
+            };
+
+            q.set(row, column, value);
+        }
+
+        for next in column + 1..size {
+            let dot = (0..q.rows)
+                .map(|row| q.at(row, column) * q.at(row, next))
+                .sum::<f64>();
+
+            r.set(column, next, dot);
+
+            for row in 0..q.rows {
+                q.set(row, next, q.at(row, next) - dot * q.at(row, column));
+            }
+        }
+    }
+
+    (q, r)
+}
+
+/// Moore-Penrose pseudo-inverse `VΣ⁺Uᵀ` from the singular value decomposition `UΣVᵀ`, leaving
+/// out singular values below the tolerance so rank-deficient matrices are handled too.
+fn pseudo_inverse(matrix: &Dense) -> Dense {
+    if matrix.rows < matrix.columns {
+        return pseudo_inverse(&matrix.transpose()).transpose();
+    }
+
+    let (columns, values, vectors) = singular_decompose(matrix);
+    let largest = values
+        .iter()
+        .fold(0.0, |largest: f64, value| largest.max(*value));
+    let tolerance = largest * matrix.rows.max(matrix.columns) as f64 * f64::EPSILON;
+    let mut inverse = Dense::zeros(matrix.columns, matrix.rows);
+
+    for (index, value) in values.iter().enumerate() {
+        if *value <= tolerance {
+            continue;
+        }
+
+        for row in 0..matrix.columns {
+            for column in 0..matrix.rows {
+                let term = vectors.at(row, index) * columns.at(column, index) / (value * value);
+
+                inverse.set(row, column, inverse.at(row, column) + term);
+            }
+        }
+    }
+
+    inverse
+}
+
+/// Singular value decomposition of a matrix with at least as many rows as columns, by one-sided
+/// Jacobi rotations: `UΣ` as columns, the singular values `Σ` and `V`. It works on the matrix
+/// itself rather than on `AᵀA`, so it doesn't square the condition number.
+fn singular_decompose(matrix: &Dense) -> (Dense, Vec<f64>, Dense) {
+    let size = matrix.columns;
+    let mut columns = matrix.clone();
+    let mut vectors = Dense::identity(size);
+
+    for _ in 0..100 {
+        let mut rotated = false;
+
+        for p in 0..size {
+            for q in p + 1..size {
+                let (mut alpha, mut beta, mut gamma) = (0.0, 0.0, 0.0);
+
+                for row in 0..columns.rows {
+                    let (rp, rq) = (columns.at(row, p), columns.at(row, q));
+
+                    alpha += rp * rp;
+                    beta += rq * rq;
+                    gamma += rp * rq;
+                }
+
+                if gamma.abs() <= f64::EPSILON * (alpha * beta).sqrt() || gamma.is_nan() {
+                    continue;
+                }
+
+                rotated = true;
+
+                let zeta = (beta - alpha) / (2.0 * gamma);
+                let t = zeta.signum() / (zeta.abs() + (zeta * zeta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for row in 0..columns.rows {
+                    let (rp, rq) = (columns.at(row, p), columns.at(row, q));
+
+                    columns.set(row, p, c * rp - s * rq);
+                    columns.set(row, q, s * rp + c * rq);
+                }
+
+                for row in 0..size {
+                    let (rp, rq) = (vectors.at(row, p), vectors.at(row, q));
+
+                    vectors.set(row, p, c * rp - s * rq);
+                    vectors.set(row, q, s * rp + c * rq);
+                }
+            }
+        }
+
+        if !rotated {
+            break;
+        }
+    }
+
+    let values = (0..size)
+        .map(|column| {
+            (0..columns.rows)
+                .map(|row| columns.at(row, column).powi(2))
+                .sum::<f64>()
+                .sqrt()
+        })
+        .collect();
+
+    (columns, values, vectors)
+}
@@ -1,3 +1,48 @@
+pub mod array;
+pub mod cast;
+pub mod color;
+pub mod drawing;
 pub mod events;
+pub mod input;
+pub mod map;
+pub mod math;
+pub mod matrix;
+pub mod na;
+pub mod plot;
 pub mod reflection;
+pub mod str;
+pub mod strategy;
+pub mod table;
 pub mod util;
+
+use parse::Span;
+
+use crate::value::{ErrorCode, Value};
+use crate::FArguments;
+
+/// Invalid argument of a namespace function, turned into a `Value::Error` by the caller.
+pub(super) struct ArgumentError(pub(super) ErrorCode, pub(super) String);
+
+impl ArgumentError {
+    /// An index, range or cell outside the collection it addresses.
+    pub(super) fn out_of_bounds(message: impl Into<String>) -> Self {
+        Self(ErrorCode::OutOfBounds, message.into())
+    }
+}
+
+impl From<ArgumentError> for Value {
+    fn from(ArgumentError(code, message): ArgumentError) -> Self {
+        Value::error(Span::empty(), code, message)
+    }
+}
+
+/// Returns the value `body` computes from the arguments; an argument it rejects becomes the
+/// returned error value instead.
+pub(super) fn evaluate(
+    args: &mut FArguments,
+    body: impl FnOnce(&FArguments) -> Result<Value, ArgumentError>,
+) {
+    let value = body(args).unwrap_or_else(Value::from);
+
+    args.returns(value);
+}
@@ -0,0 +1,66 @@
+use ast::datatype::DataType;
+
+use super::array::any;
+use crate::value::native::{Module, NativeModule};
+use crate::value::Value;
+use crate::FunctionBuilder;
+
+/// `na(x)`, `nz(x)` and `fixnan(x)`, the global functions for handling `na` values.
+pub struct Na;
+
+impl NativeModule for Na {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("na");
+
+        module.export(
+            "na",
+            FunctionBuilder::new("na")
+                .param("x", any())
+                .returns(DataType::Bool)
+                .build(|args| {
+                    let na = args.get("x").unwrap_or(Value::None).is_na();
+
+                    args.returns(na);
+                }),
+        );
+
+        module.export(
+            "nz",
+            FunctionBuilder::new("nz")
+                .param("source", any())
+                .param_default("replacement", any(), Value::Number(0))
+                .build(|args| {
+                    let value = match args.get("source").unwrap_or(Value::None) {
+                        source if source.is_na() => {
+                            args.get("replacement").unwrap_or(Value::None)
+                        }
+                        source => source,
+                    };
+
+                    args.returns(value);
+                }),
+        );
+
+        // Without bar history, the last non-na value is the one seen by previous calls from the
+        // same place in the script, such as earlier iterations of a loop.
+        module.export(
+            "fixnan",
+            FunctionBuilder::new("fixnan")
+                .param("source", any())
+                .build(|args| {
+                    let value = match args.get("source").unwrap_or(Value::None) {
+                        source if source.is_na() => args.state().unwrap_or(Value::None),
+                        source => {
+                            args.set_state(source.clone());
+
+                            source
+                        }
+                    };
+
+                    args.returns(value);
+                }),
+        );
+
+        module
+    }
+}
@@ -0,0 +1,53 @@
+use ast::datatype::DataType;
+
+use super::array::any;
+use super::color::{color, named};
+use super::{evaluate, ArgumentError};
+use crate::plot::PlotPoint;
+use crate::value::native::{Module, NativeModule};
+use crate::value::{ErrorCode, Value};
+use crate::FunctionBuilder;
+
+/// `plot(series, title, color, linewidth)`, the global function drawing a value per bar, which
+/// the host reads back with [`crate::Runtime::plots`].
+pub struct Plot;
+
+impl NativeModule for Plot {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("plot");
+
+        module.export(
+            "plot",
+            FunctionBuilder::new("plot")
+                .param("series", any())
+                .param_default("title", DataType::String, Value::String("".into()))
+                .param_default("color", DataType::Color, named("blue"))
+                .param_default("linewidth", DataType::Int, Value::Number(1))
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let value = args.get("series").unwrap_or(Value::None);
+
+                        if !value.is_na() && value.as_float().is_none() {
+                            return Err(ArgumentError(
+                                ErrorCode::Type,
+                                format!("series must be a number, but {} given", value.type_of()),
+                            ));
+                        }
+
+                        let point = PlotPoint {
+                            value,
+                            color: color(args, "color")?,
+                        };
+                        let title = args.get_string_opt("title").unwrap_or_default();
+                        let linewidth = args.get_number_opt("linewidth").unwrap_or(1);
+
+                        args.plots(|plots| plots.record(args.call_site, title, linewidth, point));
+
+                        Ok(Value::None)
+                    })
+                }),
+        );
+
+        module
+    }
+}
@@ -0,0 +1,492 @@
+use ast::datatype::DataType;
+use parse::Span;
+
+use super::array::{any, int, new_array};
+use super::ArgumentError;
+use crate::value::native::{Module, NativeModule};
+use crate::value::{ErrorCode, Value};
+use crate::{FArguments, FunctionBuilder};
+
+/// The `str` namespace. Every function takes the string as its first argument, so
+/// `str.length(s)` can also be written `s.length()`. Positions count characters, not bytes.
+pub struct Str;
+
+/// `format.inherit`, `format.mintick`, `format.percent` and `format.volume`, the named formats
+/// accepted by `str.tostring`.
+pub struct Format;
+
+const INHERIT: &str = "inherit";
+const MINTICK_FORMAT: &str = "mintick";
+const PERCENT: &str = "percent";
+const PRICE: &str = "price";
+const VOLUME: &str = "volume";
+
+/// Format of numbers without one, like Pine's default of at most ten decimals.
+const DEFAULT_MASK: &str = "#.##########";
+
+/// Format of numbers in `str.format` placeholders without a style.
+const NUMBER_MASK: &str = "#,##0.###";
+
+impl NativeModule for Format {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("format");
+
+        for format in [INHERIT, MINTICK_FORMAT, PERCENT, PRICE, VOLUME] {
+            module.export(format, Value::String(format.into()));
+        }
+
+        module
+    }
+}
+
+impl NativeModule for Str {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("str");
+
+        module.export(
+            "tostring",
+            FunctionBuilder::new("tostring")
+                .param("value", any())
+                .param_default("format", DataType::String, Value::None)
+                .returns(DataType::String)
+                .build(|args| {
+                    let value = args.get("value").unwrap_or(Value::None);
+                    let string = match args.get_string_opt("format") {
+                        Some(format) => format_value(&value, &format, args.mintick()),
+                        None => to_string(&value),
+                    };
+
+                    args.returns(Value::String(string));
+                }),
+        );
+
+        module.export(
+            "format",
+            FunctionBuilder::new("format")
+                .param("formatString", DataType::String)
+                .rest("arguments", any())
+                .returns(DataType::String)
+                .build(|args| {
+                    with_strings(args, ["formatString"], |args, [format]| {
+                        let arguments = match args.get("arguments") {
+                            Some(Value::Array(elements, _)) => elements.to_vec(),
+                            _ => Vec::new(),
+                        };
+
+                        message_format(&format, &arguments).map_or_else(Value::from, Value::String)
+                    })
+                }),
+        );
+
+        module.export(
+            "length",
+            FunctionBuilder::new("length")
+                .param("string", DataType::String)
+                .returns(DataType::Int)
+                .build(|args| {
+                    with_strings(args, ["string"], |_, [string]| {
+                        Value::Number(string.chars().count() as i64)
+                    })
+                }),
+        );
+
+        for (name, test) in [
+            (
+                "contains",
+                (|source, string| source.contains(string)) as Test,
+            ),
+            ("startswith", |source, string| source.starts_with(string)),
+            ("endswith", |source, string| source.ends_with(string)),
+        ] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param("source", DataType::String)
+                    .param("str", DataType::String)
+                    .returns(DataType::Bool)
+                    .build(move |args| {
+                        with_strings(args, ["source", "str"], |_, [source, string]| {
+                            Value::Boolean(test(&source, &string))
+                        })
+                    }),
+            );
+        }
+
+        module.export(
+            "pos",
+            FunctionBuilder::new("pos")
+                .param("source", DataType::String)
+                .param("str", DataType::String)
+                .returns(DataType::Int)
+                .build(|args| {
+                    with_strings(args, ["source", "str"], |_, [source, string]| {
+                        source.find(&string).map_or(Value::None, |byte| {
+                            Value::Number(source[..byte].chars().count() as i64)
+                        })
+                    })
+                }),
+        );
+
+        // `end_pos` is exclusive, and the end of the string when na.
+        module.export(
+            "substring",
+            FunctionBuilder::new("substring")
+                .param("source", DataType::String)
+                .param("begin_pos", DataType::Int)
+                .param_default("end_pos", DataType::Int, Value::None)
+                .returns(DataType::String)
+                .build(|args| {
+                    with_strings(args, ["source"], |args, [source]| {
+                        let characters = source.chars().collect::<Vec<_>>();
+                        let begin = int(args, "begin_pos").unwrap_or(0);
+                        let end = int(args, "end_pos").unwrap_or(characters.len() as i64);
+
+                        match 0 <= begin && begin <= end && end <= characters.len() as i64 {
+                            true => Value::String(String::from_iter(
+                                &characters[begin as usize..end as usize],
+                            )),
+                            false => Value::error(
+                                Span::empty(),
+                                ErrorCode::OutOfBounds,
+                                format!(
+                                    "range {begin}..{end} is out of bounds, the string has {} \
+                                     characters",
+                                    characters.len()
+                                ),
+                            ),
+                        }
+                    })
+                }),
+        );
+
+        // Replaces the `occurrence`th match of `target`, counting from 0.
+        module.export(
+            "replace",
+            FunctionBuilder::new("replace")
+                .param("source", DataType::String)
+                .param("target", DataType::String)
+                .param("replacement", DataType::String)
+                .param_default("occurrence", DataType::Int, Value::Number(0))
+                .returns(DataType::String)
+                .build(|args| {
+                    with_strings(
+                        args,
+                        ["source", "target", "replacement"],
+                        |args, [source, target, replacement]| {
+                            let occurrence = int(args, "occurrence").unwrap_or(0);
+                            let found = match target.is_empty() || occurrence < 0 {
+                                true => None,
+                                false => source.match_indices(&target).nth(occurrence as usize),
+                            };
+
+                            Value::String(match found {
+                                Some((start, _)) => format!(
+                                    "{}{}{}",
+                                    &source[..start],
+                                    replacement,
+                                    &source[start + target.len()..]
+                                ),
+                                None => source,
+                            })
+                        },
+                    )
+                }),
+        );
+
+        module.export(
+            "replace_all",
+            FunctionBuilder::new("replace_all")
+                .param("source", DataType::String)
+                .param("target", DataType::String)
+                .param("replacement", DataType::String)
+                .returns(DataType::String)
+                .build(|args| {
+                    with_strings(
+                        args,
+                        ["source", "target", "replacement"],
+                        |_, [source, target, replacement]| match target.is_empty() {
+                            true => Value::String(source),
+                            false => Value::String(source.replace(&target, &replacement)),
+                        },
+                    )
+                }),
+        );
+
+        // An empty separator splits the string into its characters.
+        module.export(
+            "split",
+            FunctionBuilder::new("split")
+                .param("string", DataType::String)
+                .param("separator", DataType::String)
+                .returns(DataType::Array(Box::new(DataType::String)))
+                .build(|args| {
+                    with_strings(args, ["string", "separator"], |_, [string, separator]| {
+                        let parts = match separator.is_empty() {
+                            true => string
+                                .chars()
+                                .map(|character| Value::String(character.into()))
+                                .collect(),
+                            false => string
+                                .split(&separator)
+                                .map(|part| Value::String(part.into()))
+                                .collect(),
+                        };
+
+                        new_array(parts)
+                    })
+                }),
+        );
+
+        for (name, convert) in [
+            ("upper", str::to_uppercase as Convert),
+            ("lower", str::to_lowercase),
+            ("trim", |string| string.trim().to_string()),
+        ] {
+            module.export(
+                name,
+                FunctionBuilder::new(name)
+                    .param("source", DataType::String)
+                    .returns(DataType::String)
+                    .build(move |args| {
+                        with_strings(args, ["source"], |_, [source]| {
+                            Value::String(convert(&source))
+                        })
+                    }),
+            );
+        }
+
+        // na when the string isn't a number.
+        module.export(
+            "tonumber",
+            FunctionBuilder::new("tonumber")
+                .param("string", DataType::String)
+                .returns(DataType::Float)
+                .build(|args| {
+                    with_strings(args, ["string"], |_, [string]| {
+                        match string.trim().parse::<f64>() {
+                            Ok(number) if number.is_finite() => Value::Float(number),
+                            _ => Value::None,
+                        }
+                    })
+                }),
+        );
+
+        module
+    }
+}
+
+type Test = fn(&str, &str) -> bool;
+
+type Convert = fn(&str) -> String;
+
+/// Returns the result of `body` for the string arguments `names`, na when one of them is na, or
+/// the type error.
+fn with_strings<const N: usize>(
+    args: &mut FArguments,
+    names: [&str; N],
+    body: impl FnOnce(&FArguments, [String; N]) -> Value,
+) {
+    let mut strings = Vec::with_capacity(N);
+    let mut value = None;
+
+    for name in names {
+        match args.get(name).unwrap_or(Value::None) {
+            Value::String(string) => strings.push(string),
+            Value::None => {
+                value = value.or(Some(Value::None));
+            }
+            other => {
+                value = value.or(Some(Value::error(
+                    Span::empty(),
+                    ErrorCode::Type,
+                    format!("{name} must be a string, but {} given", other.type_of()),
+                )));
+            }
+        }
+    }
+
+    let value = match (value, <[String; N]>::try_from(strings)) {
+        (Some(value), _) => value,
+        (None, Ok(strings)) => body(args, strings),
+        (None, Err(_)) => Value::None,
+    };
+
+    args.returns(value);
+}
+
+/// `value` as `str.tostring` shows it without a format.
+fn to_string(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        value if value.is_na() => "NaN".into(),
+        Value::Float(float) => mask(*float, DEFAULT_MASK),
+        value => value.to_string(),
+    }
+}
+
+/// `value` formatted by one of the `format.*` constants or a mask such as `"#.##"`, with
+/// `format.mintick` rounding to `mintick` and showing as many decimals as it has.
+fn format_value(value: &Value, format: &str, mintick: f64) -> String {
+    let number = match value {
+        value if value.is_na() => return "NaN".into(),
+        value => match value.as_float() {
+            Some(number) => number,
+            None => return to_string(value),
+        },
+    };
+
+    match format {
+        INHERIT => to_string(value),
+        MINTICK_FORMAT => {
+            let decimals = mintick
+                .to_string()
+                .split_once('.')
+                .map_or(0, |(_, decimals)| decimals.len());
+            let pattern = match decimals {
+                0 => "0".to_string(),
+                decimals => format!("0.{}", "0".repeat(decimals)),
+            };
+
+            mask((number / mintick).round() * mintick, &pattern)
+        }
+        PERCENT => format!("{}%", mask(number, "0.00")),
+        VOLUME => volume(number),
+        mask_pattern => mask(number, mask_pattern),
+    }
+}
+
+/// Volumes abbreviated with `K`, `M` and `B`.
+fn volume(number: f64) -> String {
+    let (scaled, suffix) = match number.abs() {
+        size if size >= 1e9 => (number / 1e9, "B"),
+        size if size >= 1e6 => (number / 1e6, "M"),
+        size if size >= 1e3 => (number / 1e3, "K"),
+        _ => (number, ""),
+    };
+
+    format!("{}{suffix}", mask(scaled, "#.###"))
+}
+
+/// Formats `number` with a decimal format mask: `0` is a digit that is always shown, `#` one
+/// that is shown when it isn't a trailing zero, `,` groups thousands and a `%` around the digits
+/// shows the number as a percentage. Other characters around the digits are kept as they are.
+fn mask(number: f64, mask: &str) -> String {
+    let is_pattern = |character: char| "#0,.".contains(character);
+    let start = mask.find(is_pattern).unwrap_or(mask.len());
+    let end = mask.rfind(is_pattern).map_or(start, |end| end + 1);
+    let (prefix, pattern, suffix) = (&mask[..start], &mask[start..end], &mask[end..]);
+    let (integer, fraction) = pattern.split_once('.').unwrap_or((pattern, ""));
+    let number = match prefix.contains('%') || suffix.contains('%') {
+        true => number * 100.0,
+        false => number,
+    };
+
+    let required = fraction
+        .chars()
+        .filter(|character| *character == '0')
+        .count();
+    let decimals = fraction
+        .chars()
+        .filter(|character| "#0".contains(*character))
+        .count();
+    let digits = format!("{:.*}", decimals, number.abs());
+    let (whole, decimal) = digits.split_once('.').unwrap_or((&digits, ""));
+    let decimal = decimal.trim_end_matches('0');
+    let decimal = format!("{decimal:0<required$}");
+
+    let minimum = integer
+        .chars()
+        .filter(|character| *character == '0')
+        .count()
+        .max(1);
+    let mut whole = format!("{whole:0>minimum$}");
+
+    if integer.contains(',') {
+        whole = group_thousands(&whole);
+    }
+
+    let negative = number < 0.0
+        && (whole.chars().chain(decimal.chars())).any(|digit| digit != '0' && digit != ',');
+    let sign = if negative { "-" } else { "" };
+
+    match decimal.is_empty() {
+        true => format!("{prefix}{sign}{whole}{suffix}"),
+        false => format!("{prefix}{sign}{whole}.{decimal}{suffix}"),
+    }
+}
+
+fn group_thousands(digits: &str) -> String {
+    let mut grouped = String::new();
+
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+/// `str.format`: `{index}` and `{index,number[,style]}` placeholders, where the style is
+/// `integer`, `percent`, `currency` or a mask. Text between single quotes is kept as it is, and
+/// `''` is a quote.
+fn message_format(format: &str, arguments: &[Value]) -> Result<String, ArgumentError> {
+    let mut output = String::new();
+    let mut characters = format.chars().peekable();
+    let mut quoted = false;
+
+    while let Some(character) = characters.next() {
+        match character {
+            '\'' if characters.peek() == Some(&'\'') => {
+                characters.next();
+                output.push('\'');
+            }
+            '\'' => quoted = !quoted,
+            '{' if !quoted => {
+                let placeholder = characters
+                    .by_ref()
+                    .take_while(|c| *c != '}')
+                    .collect::<String>();
+
+                output.push_str(&placeholder_value(&placeholder, arguments)?);
+            }
+            character => output.push(character),
+        }
+    }
+
+    Ok(output)
+}
+
+fn placeholder_value(placeholder: &str, arguments: &[Value]) -> Result<String, ArgumentError> {
+    let mut parts = placeholder.splitn(3, ',').map(str::trim);
+    let index = parts.next().unwrap_or_default();
+    let value = index
+        .parse::<usize>()
+        .ok()
+        .and_then(|index| arguments.get(index))
+        .ok_or_else(|| {
+            ArgumentError::out_of_bounds(format!(
+                "placeholder {{{placeholder}}} has no argument, {} given",
+                arguments.len()
+            ))
+        })?;
+
+    let number = match value.as_float() {
+        Some(number) if !value.is_na() => number,
+        _ => return Ok(to_string(value)),
+    };
+
+    match (parts.next(), parts.next()) {
+        (None, _) | (Some("number"), None) => Ok(mask(number, NUMBER_MASK)),
+        (Some("number"), Some("integer")) => Ok(mask(number, "#,##0")),
+        (Some("number"), Some("percent")) => Ok(mask(number, "#,##0%")),
+        (Some("number"), Some("currency")) => Ok(mask(number, "$#,##0.00")),
+        (Some("number"), Some(pattern)) => Ok(mask(number, pattern)),
+        (Some(kind), _) => Err(ArgumentError(
+            ErrorCode::Type,
+            format!("unknown format type `{kind}` in {{{placeholder}}}"),
+        )),
+    }
+}
@@ -0,0 +1,50 @@
+use crate::metadata::{ScriptMetadata, COMMISSION_TYPES, QUANTITY_TYPES};
+use crate::value::builders::ObjectBuilder;
+use crate::value::native::{Module, NativeModule};
+use crate::value::Value;
+
+/// The `strategy` namespace. Its constants are there for every script, since declarations use
+/// them; the account of the strategy is only added by `strategy(...)`, see [`account`].
+pub struct Strategy;
+
+impl NativeModule for Strategy {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("strategy");
+        let constants = |names: &[&str]| {
+            names
+                .iter()
+                .fold(ObjectBuilder::default(), |object, name| {
+                    object.prop(name, Value::String(name.to_string()))
+                })
+                .build()
+        };
+
+        for name in QUANTITY_TYPES.into_iter().chain(["long", "short"]) {
+            module.export(name, Value::String(name.into()));
+        }
+
+        module.export("commission", constants(&COMMISSION_TYPES));
+        module.export("direction", constants(&["all", "long", "short"]));
+        module.export("oca", constants(&["none", "cancel", "reduce"]));
+
+        module
+    }
+}
+
+/// The account of a strategy before any order is filled.
+pub(crate) fn account(metadata: &ScriptMetadata) -> Vec<(&'static str, Value)> {
+    let Some(strategy) = &metadata.strategy else {
+        return vec![];
+    };
+
+    vec![
+        ("initial_capital", Value::Float(strategy.initial_capital)),
+        ("account_currency", Value::String(strategy.currency.clone())),
+        ("equity", Value::Float(strategy.initial_capital)),
+        ("netprofit", Value::Float(0.0)),
+        ("openprofit", Value::Float(0.0)),
+        ("position_size", Value::Float(0.0)),
+        ("opentrades", Value::Number(0)),
+        ("closedtrades", Value::Number(0)),
+    ]
+}
@@ -0,0 +1,337 @@
+use ast::datatype::DataType;
+
+use super::array::{any, int, new_array};
+use super::color::named;
+use super::{evaluate, ArgumentError};
+use crate::table::{Cell, Merge, Table as Handle, TableObject};
+use crate::value::native::{Module, NativeModule};
+use crate::value::{ErrorCode, Value};
+use crate::{FArguments, FunctionBuilder};
+
+/// The `table` namespace. Tables are grids of cells the host renders after a run, see
+/// [`TableObject::render_text`] and [`TableObject::render_html`].
+pub struct Table;
+
+/// The properties of a table after its position and size, with their defaults.
+fn table_properties() -> Vec<(&'static str, DataType, Value)> {
+    vec![
+        ("bgcolor", DataType::Color, Value::None),
+        ("frame_color", DataType::Color, Value::None),
+        ("frame_width", DataType::Int, Value::Number(0)),
+        ("border_color", DataType::Color, Value::None),
+        ("border_width", DataType::Int, Value::Number(0)),
+    ]
+}
+
+/// The properties of a cell, with their defaults.
+fn cell_properties() -> Vec<(&'static str, DataType, Value)> {
+    let text = |value: &str| Value::String(value.into());
+
+    vec![
+        ("text", DataType::String, text("")),
+        ("width", DataType::Float, Value::Number(0)),
+        ("height", DataType::Float, Value::Number(0)),
+        ("text_color", DataType::Color, named("black")),
+        ("text_halign", DataType::String, text("align_center")),
+        ("text_valign", DataType::String, text("align_center")),
+        ("text_size", DataType::String, text("normal")),
+        ("bgcolor", DataType::Color, Value::None),
+        ("tooltip", DataType::String, text("")),
+    ]
+}
+
+impl NativeModule for Table {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("table");
+
+        let new = table_properties().into_iter().fold(
+            FunctionBuilder::new("new")
+                .param("position", DataType::String)
+                .param("columns", DataType::Int)
+                .param("rows", DataType::Int),
+            |builder, (name, ty, default)| builder.param_default(name, ty, default),
+        );
+
+        module.export(
+            "new",
+            new.returns(DataType::Table).build(|args| {
+                evaluate(args, |args| {
+                    let columns = size(args, "columns")?;
+                    let rows = size(args, "rows")?;
+                    let properties = ["position"]
+                        .into_iter()
+                        .chain(table_properties().into_iter().map(|(name, ..)| name))
+                        .map(|name| (name, args.get(name).unwrap_or(Value::None)))
+                        .collect();
+
+                    Ok(Value::Table(
+                        args.tables(|tables| tables.create(columns, rows, properties)),
+                    ))
+                })
+            }),
+        );
+
+        module.export(
+            "delete",
+            FunctionBuilder::new("delete")
+                .param("table_id", DataType::Table)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        if let Some(table) = table(args)? {
+                            args.tables(|tables| tables.delete(&table));
+                        }
+
+                        Ok(Value::None)
+                    })
+                }),
+        );
+
+        module.export(
+            "all",
+            FunctionBuilder::new("all")
+                .returns(DataType::Array(Box::new(DataType::Table)))
+                .build(|args| {
+                    let all = args.tables(|tables| {
+                        tables
+                            .objects()
+                            .iter()
+                            .map(|object| Value::Table(Handle { id: object.id }))
+                            .collect()
+                    });
+
+                    args.returns(new_array(all));
+                }),
+        );
+
+        for name in ["position"]
+            .into_iter()
+            .chain(table_properties().into_iter().map(|(name, ..)| name))
+        {
+            let setter = format!("set_{name}");
+
+            module.export(
+                &setter,
+                FunctionBuilder::new(&setter)
+                    .param("table_id", DataType::Table)
+                    .param(name, any())
+                    .build(move |args| {
+                        evaluate(args, |args| {
+                            let value = args.get(name).unwrap_or(Value::None);
+
+                            with_table(args, |object| {
+                                object.set(name, value);
+                                Ok(())
+                            })
+                        })
+                    }),
+            );
+        }
+
+        let cell = cell_properties().into_iter().fold(
+            FunctionBuilder::new("cell")
+                .param("table_id", DataType::Table)
+                .param("column", DataType::Int)
+                .param("row", DataType::Int),
+            |builder, (name, ty, default)| builder.param_default(name, ty, default),
+        );
+
+        // Setting a cell replaces it, along with the properties set on it before.
+        module.export(
+            "cell",
+            cell.build(|args| {
+                evaluate(args, |args| {
+                    let (column, row) = (index(args, "column")?, index(args, "row")?);
+                    let properties = cell_properties()
+                        .into_iter()
+                        .map(|(name, ..)| (name, args.get(name).unwrap_or(Value::None)))
+                        .collect();
+
+                    with_table(args, |object| {
+                        *cell_at(object, column, row)? = Some(Cell { properties });
+                        Ok(())
+                    })
+                })
+            }),
+        );
+
+        for (name, ..) in cell_properties() {
+            let setter = format!("cell_set_{name}");
+
+            module.export(
+                &setter,
+                FunctionBuilder::new(&setter)
+                    .param("table_id", DataType::Table)
+                    .param("column", DataType::Int)
+                    .param("row", DataType::Int)
+                    .param(name, any())
+                    .build(move |args| {
+                        evaluate(args, |args| {
+                            let (column, row) = (index(args, "column")?, index(args, "row")?);
+                            let value = args.get(name).unwrap_or(Value::None);
+
+                            with_table(args, |object| {
+                                cell_at(object, column, row)?
+                                    .get_or_insert_with(default_cell)
+                                    .set(name, value);
+                                Ok(())
+                            })
+                        })
+                    }),
+            );
+        }
+
+        module.export(
+            "merge_cells",
+            FunctionBuilder::new("merge_cells")
+                .param("table_id", DataType::Table)
+                .param("start_column", DataType::Int)
+                .param("start_row", DataType::Int)
+                .param("end_column", DataType::Int)
+                .param("end_row", DataType::Int)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let range = range(args)?;
+
+                        with_table(args, |object| {
+                            check(object, range)?;
+                            object.merge(range).map_err(ArgumentError::out_of_bounds)
+                        })
+                    })
+                }),
+        );
+
+        // Without an end, only the start cell is cleared.
+        module.export(
+            "clear",
+            FunctionBuilder::new("clear")
+                .param("table_id", DataType::Table)
+                .param("start_column", DataType::Int)
+                .param("start_row", DataType::Int)
+                .param_default("end_column", DataType::Int, Value::None)
+                .param_default("end_row", DataType::Int, Value::None)
+                .build(|args| {
+                    evaluate(args, |args| {
+                        let range = range(args)?;
+
+                        with_table(args, |object| {
+                            check(object, range)?;
+                            object.clear(range);
+                            Ok(())
+                        })
+                    })
+                }),
+        );
+
+        module
+    }
+}
+
+fn default_cell() -> Cell {
+    Cell {
+        properties: cell_properties()
+            .into_iter()
+            .map(|(name, _, default)| (name, default))
+            .collect(),
+    }
+}
+
+/// The `table_id` argument, or `None` when it's na.
+fn table(args: &FArguments) -> Result<Option<Handle>, ArgumentError> {
+    match args.get("table_id").unwrap_or(Value::None) {
+        Value::Table(table) => Ok(Some(table)),
+        Value::None => Ok(None),
+        value => Err(ArgumentError(
+            ErrorCode::Type,
+            format!("table expected, but {} given", value.type_of()),
+        )),
+    }
+}
+
+/// Runs `body` with the table of the `table_id` argument; na and deleted tables are skipped.
+fn with_table(
+    args: &FArguments,
+    body: impl FnOnce(&mut TableObject) -> Result<(), ArgumentError>,
+) -> Result<Value, ArgumentError> {
+    if let Some(table) = table(args)? {
+        args.tables(|tables| tables.get_mut(&table).map_or(Ok(()), body))?;
+    }
+
+    Ok(Value::None)
+}
+
+/// A number of columns or rows, which must be positive.
+fn size(args: &FArguments, name: &str) -> Result<usize, ArgumentError> {
+    match int(args, name) {
+        Some(size) if size > 0 => Ok(size as usize),
+        _ => Err(ArgumentError(
+            ErrorCode::Type,
+            format!("{name} must be a positive int"),
+        )),
+    }
+}
+
+/// A column or row index; it's checked against the table when it's used.
+fn index(args: &FArguments, name: &str) -> Result<usize, ArgumentError> {
+    match int(args, name) {
+        Some(index) if index >= 0 => Ok(index as usize),
+        Some(index) => Err(ArgumentError::out_of_bounds(format!(
+            "{name} {index} is outside the table"
+        ))),
+        None => Err(ArgumentError(
+            ErrorCode::Type,
+            format!("{name} must be an int"),
+        )),
+    }
+}
+
+/// The cells from `start_column`, `start_row` to `end_column`, `end_row`; a missing end is the
+/// start.
+fn range(args: &FArguments) -> Result<Merge, ArgumentError> {
+    let (start_column, start_row) = (index(args, "start_column")?, index(args, "start_row")?);
+    let end = |name: &str, start: usize| match args.get(name) {
+        None | Some(Value::None) => Ok(start),
+        Some(_) => index(args, name),
+    };
+    let range = Merge {
+        start_column,
+        start_row,
+        end_column: end("end_column", start_column)?,
+        end_row: end("end_row", start_row)?,
+    };
+
+    match range.end_column >= start_column && range.end_row >= start_row {
+        true => Ok(range),
+        false => Err(ArgumentError::out_of_bounds(
+            "the end of the range must not be before its start",
+        )),
+    }
+}
+
+/// The cell at `column`, `row` of `object`, or an error when it's outside the table.
+fn cell_at(
+    object: &mut TableObject,
+    column: usize,
+    row: usize,
+) -> Result<&mut Option<Cell>, ArgumentError> {
+    check(
+        object,
+        Merge {
+            start_column: column,
+            start_row: row,
+            end_column: column,
+            end_row: row,
+        },
+    )?;
+
+    Ok(object.cell_mut(column, row))
+}
+
+fn check(object: &TableObject, range: Merge) -> Result<(), ArgumentError> {
+    match range.end_column < object.columns && range.end_row < object.rows {
+        true => Ok(()),
+        false => Err(ArgumentError::out_of_bounds(format!(
+            "cell {}:{} is outside table#{}, which has {} columns and {} rows",
+            range.end_column, range.end_row, object.id, object.columns, object.rows
+        ))),
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use crate::value::Value;
+
+/// The kinds of objects a script can draw on the chart.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum DrawingKind {
+    Line,
+    Label,
+    Box,
+    Linefill,
+    Polyline,
+}
+
+impl DrawingKind {
+    /// The namespace of the kind, which is also its type name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DrawingKind::Line => "line",
+            DrawingKind::Label => "label",
+            DrawingKind::Box => "box",
+            DrawingKind::Linefill => "linefill",
+            DrawingKind::Polyline => "polyline",
+        }
+    }
+
+    /// How many objects of the kind are kept before the oldest are deleted, by default.
+    fn default_limit(&self) -> Option<usize> {
+        match self {
+            DrawingKind::Line | DrawingKind::Label | DrawingKind::Box => Some(50),
+            DrawingKind::Polyline => Some(100),
+            // Linefills go away with their lines.
+            DrawingKind::Linefill => None,
+        }
+    }
+}
+
+impl Display for DrawingKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The value scripts hold for a drawing object. The object itself lives in the [`Drawings`] of
+/// the runtime, so copies of the handle see the same object.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Drawing {
+    pub kind: DrawingKind,
+    pub id: usize,
+}
+
+impl Display for Drawing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}#{}", self.kind, self.id)
+    }
+}
+
+/// A live drawing object and its properties, in the order the constructor takes them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrawingObject {
+    pub id: usize,
+    pub kind: DrawingKind,
+    pub properties: Vec<(&'static str, Value)>,
+}
+
+impl DrawingObject {
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.properties
+            .iter()
+            .find(|(property, _)| *property == name)
+            .map(|(_, value)| value)
+    }
+
+    fn set(&mut self, name: &str, value: Value) {
+        if let Some((_, property)) = self
+            .properties
+            .iter_mut()
+            .find(|(property, _)| *property == name)
+        {
+            *property = value;
+        }
+    }
+
+    fn handle(&self) -> Drawing {
+        Drawing {
+            kind: self.kind,
+            id: self.id,
+        }
+    }
+
+    /// Whether the object is a linefill between `line` and another line.
+    fn fills(&self, line: &Drawing) -> bool {
+        let line = Value::Drawing(*line);
+
+        self.kind == DrawingKind::Linefill
+            && (self.get("line1") == Some(&line) || self.get("line2") == Some(&line))
+    }
+}
+
+/// The drawing objects of a run, oldest first. Creating an object beyond the limit of its kind
+/// deletes the oldest one of that kind.
+#[derive(Clone, Debug, Default)]
+pub struct Drawings {
+    objects: Vec<DrawingObject>,
+    next_id: usize,
+    limits: HashMap<DrawingKind, usize>,
+}
+
+impl Drawings {
+    pub fn limit(&self, kind: DrawingKind) -> Option<usize> {
+        self.limits.get(&kind).copied().or(kind.default_limit())
+    }
+
+    pub fn set_limit(&mut self, kind: DrawingKind, limit: usize) {
+        self.limits.insert(kind, limit);
+    }
+
+    pub fn create(&mut self, kind: DrawingKind, properties: Vec<(&'static str, Value)>) -> Drawing {
+        let id = self.next_id;
+
+        self.next_id += 1;
+        self.objects.push(DrawingObject {
+            id,
+            kind,
+            properties,
+        });
+
+        if let Some(limit) = self.limit(kind) {
+            while self.all(kind).len() > limit {
+                let oldest = self.all(kind)[0];
+
+                self.delete(&oldest);
+            }
+        }
+
+        Drawing { kind, id }
+    }
+
+    /// The object behind `drawing`, or `None` once it's deleted.
+    pub fn get(&self, drawing: &Drawing) -> Option<&DrawingObject> {
+        self.objects.iter().find(|object| object.id == drawing.id)
+    }
+
+    /// Sets a property of the object behind `drawing`; deleted objects are left as they are.
+    pub fn set(&mut self, drawing: &Drawing, name: &str, value: Value) {
+        if let Some(object) = self
+            .objects
+            .iter_mut()
+            .find(|object| object.id == drawing.id)
+        {
+            object.set(name, value);
+        }
+    }
+
+    /// Deletes the object behind `drawing`, and the linefills of a line.
+    pub fn delete(&mut self, drawing: &Drawing) {
+        self.objects
+            .retain(|object| object.id != drawing.id && !object.fills(drawing));
+    }
+
+    /// The live objects of `kind`, oldest first.
+    pub fn all(&self, kind: DrawingKind) -> Vec<Drawing> {
+        self.objects
+            .iter()
+            .filter(|object| object.kind == kind)
+            .map(DrawingObject::handle)
+            .collect()
+    }
+
+    /// Every live object, oldest first.
+    pub fn objects(&self) -> &[DrawingObject] {
+        &self.objects
+    }
+}
@@ -1,26 +1,47 @@
 use std::cmp::Reverse;
+use std::collections::HashMap;
 use std::iter::Peekable;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::vec::IntoIter;
 
+use ast::datatype::DataType;
 use parse::util::VecExt;
+use parse::Span;
 
+use super::drawing::Drawings;
+use super::input::ScriptInputs;
+use super::metadata::ScriptMetadata;
+use super::plot::Plots;
+use super::table::Tables;
 use super::value::Value;
 
 pub type Context = Arc<Mutex<Environment>>;
 pub type Scope = Vec<String>;
 
+/// Tick size of the symbol, until the host sets one with [`Environment::set_mintick`].
+pub const DEFAULT_MINTICK: f64 = 0.01;
+
 #[derive(Clone, Debug)]
 pub struct Variable {
     pub name: String,
     pub scope: Scope,
     pub value: Value,
+    /// Type the variable is declared with, such as `float` in `float x = 1`.
+    pub ty: Option<DataType>,
 }
 
 #[derive(Default, Clone, Debug)]
 pub struct Environment {
     store: Vec<Variable>,
+    /// Values native functions such as `fixnan` keep between calls, by function and call site.
+    call_states: HashMap<(String, Span), Value>,
+    drawings: Drawings,
+    plots: Plots,
+    tables: Tables,
+    inputs: ScriptInputs,
+    metadata: Option<ScriptMetadata>,
+    mintick: Option<f64>,
 }
 
 unsafe impl Send for Environment {}
@@ -31,6 +52,63 @@ impl Environment {
         Arc::new(Mutex::new(Self::default()))
     }
 
+    pub fn call_state(&self, function: &str, call_site: Span) -> Option<&Value> {
+        self.call_states.get(&(function.to_string(), call_site))
+    }
+
+    pub fn set_call_state(&mut self, function: &str, call_site: Span, value: Value) {
+        self.call_states.insert((function.to_string(), call_site), value);
+    }
+
+    pub fn drawings(&self) -> &Drawings {
+        &self.drawings
+    }
+
+    pub fn drawings_mut(&mut self) -> &mut Drawings {
+        &mut self.drawings
+    }
+
+    pub fn plots(&self) -> &Plots {
+        &self.plots
+    }
+
+    pub fn plots_mut(&mut self) -> &mut Plots {
+        &mut self.plots
+    }
+
+    pub fn tables(&self) -> &Tables {
+        &self.tables
+    }
+
+    pub fn tables_mut(&mut self) -> &mut Tables {
+        &mut self.tables
+    }
+
+    pub fn inputs(&self) -> &ScriptInputs {
+        &self.inputs
+    }
+
+    pub fn inputs_mut(&mut self) -> &mut ScriptInputs {
+        &mut self.inputs
+    }
+
+    pub fn metadata(&self) -> Option<&ScriptMetadata> {
+        self.metadata.as_ref()
+    }
+
+    pub fn metadata_mut(&mut self) -> &mut Option<ScriptMetadata> {
+        &mut self.metadata
+    }
+
+    /// Tick size of the symbol the script runs against.
+    pub fn mintick(&self) -> f64 {
+        self.mintick.unwrap_or(DEFAULT_MINTICK)
+    }
+
+    pub fn set_mintick(&mut self, mintick: f64) {
+        self.mintick = Some(mintick);
+    }
+
     pub fn extend(&mut self, context: Context) {
         self.store.extend(context.lock().unwrap().store.clone())
     }
@@ -53,7 +131,12 @@ impl Environment {
                     }
                 }
                 Value::ClassInstance(instance) => {
-                    match (next.is_some(), instance.get_field_mut(current)) {
+                    let mut fields = instance.fields.borrow_mut();
+
+                    match (
+                        next.is_some(),
+                        fields.iter_mut().find(|field| field.prop.name == current),
+                    ) {
                         (true, Some(field)) => Self::modify(path, &mut field.value, value),
                         (false, Some(field)) => {
                             field.value = value;
@@ -79,6 +162,7 @@ impl Environment {
                         name: name.into(),
                         scope,
                         value,
+                        ty: None,
                     });
                 }
             }
@@ -97,6 +181,33 @@ impl Environment {
         }
     }
 
+    /// Binds `name` in exactly `scope`, shadowing any variable of the same name in outer scopes.
+    pub fn declare<N: AsRef<str>>(&mut self, name: N, scope: Scope, value: Value) {
+        let name = name.as_ref();
+
+        if let Some(variable) = self
+            .store
+            .iter_mut()
+            .find(|variable| variable.name == name && variable.scope == scope)
+        {
+            variable.value = value;
+        } else {
+            self.store.push(Variable {
+                name: name.into(),
+                scope,
+                value,
+                ty: None,
+            });
+        }
+    }
+
+    /// Records the type the variable `name` is declared with.
+    pub fn set_type<N: AsRef<str>>(&mut self, name: N, scope: Scope, ty: DataType) {
+        if let Some(variable) = self.get_mut(name, scope) {
+            variable.ty = Some(ty);
+        }
+    }
+
     pub fn exists<N: AsRef<str>>(&self, name: N, scope: Scope) -> bool {
         let name: &str = name.as_ref();
 
@@ -130,6 +241,7 @@ impl Environment {
 
             if position.is_some() {
                 index = position;
+                break;
             }
         }
 
@@ -0,0 +1,120 @@
+use ast::{Assignment, AssignmentOperator, BinaryOperator};
+use parse::Span;
+
+use crate::{
+    value::{ErrorCode, Value},
+    Runtime,
+};
+
+impl Runtime {
+    /// Evaluates `name := value` and the compound `+=`, `-=`, `*=`, `/=` forms. The innermost
+    /// existing binding, or the field of the object it refers to, is updated; the new value is
+    /// the result.
+    pub fn eval_assignment(&mut self, assignment: Assignment) -> Value {
+        let span = Span::from(assignment.position());
+        let current = self
+            .context
+            .lock()
+            .unwrap()
+            .get(assignment.name(), self.scope.clone())
+            .map(|variable| (variable.value.clone(), variable.ty.clone()));
+
+        let Some((mut current, ty)) = current else {
+            return Value::error(
+                span,
+                ErrorCode::Reference,
+                format!("`{}` is assigned before it is declared", assignment.name()),
+            );
+        };
+
+        // Walk down to the object owning the assigned field.
+        let mut target = None;
+
+        for field in assignment.fields() {
+            let object = match current {
+                Value::ClassInstance(instance) => instance,
+                value => {
+                    return Value::error(
+                        span,
+                        ErrorCode::Type,
+                        format!("can't assign field `{field}` of {}", value.type_of()),
+                    )
+                }
+            };
+
+            current = match object.get_field(field) {
+                Some(value) => value,
+                None => {
+                    return Value::error(
+                        span,
+                        ErrorCode::Reference,
+                        format!("`{}` has no field `{field}`", object.name),
+                    )
+                }
+            };
+            target = Some((object, field));
+        }
+
+        let value = self.eval_expression(assignment.value().clone());
+
+        if value.is_error() || value.is_loop_control() {
+            return value;
+        }
+
+        let operator = match assignment.operator() {
+            AssignmentOperator::Reassign => None,
+            AssignmentOperator::Add => Some(BinaryOperator::Add),
+            AssignmentOperator::Subtract => Some(BinaryOperator::Subtract),
+            AssignmentOperator::Multiply => Some(BinaryOperator::Multiply),
+            AssignmentOperator::Divide => Some(BinaryOperator::Divide),
+        };
+
+        let value = match operator {
+            Some(operator) => Self::binary_operation(operator, current, value, span),
+            None => value,
+        };
+
+        if value.is_error() {
+            return value;
+        }
+
+        match target {
+            Some((object, field)) => {
+                let prop = object.property(field).unwrap();
+                let Some(value) = prop.coerce(value.clone()) else {
+                    return Value::error(
+                        span,
+                        ErrorCode::Type,
+                        format!(
+                            "field `{field}` of {} is {}, but {} was assigned",
+                            object.name,
+                            prop.ty
+                                .as_ref()
+                                .map(ToString::to_string)
+                                .unwrap_or_default(),
+                            value.type_of()
+                        ),
+                    );
+                };
+
+                object.set_field(field, value.clone());
+
+                value
+            }
+            None => {
+                let value = match ty {
+                    Some(ty) => value.promote(&ty),
+                    None => value,
+                };
+
+                self.context.lock().unwrap().set(
+                    &[assignment.name()],
+                    self.scope.clone(),
+                    value.clone(),
+                );
+
+                value
+            }
+        }
+    }
+}
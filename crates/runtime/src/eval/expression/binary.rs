@@ -1,106 +1,145 @@
+use ast::{BinaryOperation, BinaryOperator, UnaryOperation, UnaryOperator};
+use parse::Span;
 
-use crate::{value::Value, Runtime};
+use crate::{
+    value::{ErrorCode, Value},
+    Runtime,
+};
 
 impl Runtime {
-    pub fn eval_binary_expression(&mut self, expression: BinaryExpression) -> Value {
-        let left = self.eval_expression(expression.left);
-        let right = self.eval_expression(expression.right);
+    pub fn eval_binary_expression(&mut self, expression: BinaryOperation) -> Value {
+        let span = Span::from(expression.position());
+        let left = self.eval_expression(expression.lhs().clone());
 
-        println!("{left} {:?} {right}", expression.operator.value);
+        if left.is_error() {
+            return left;
+        }
 
-        match expression.operator.value {
-            Operator::And => todo!(),
-            Operator::AndAnd => todo!(),
-            Operator::Plus => match (left, right) {
-                (Value::String(first), Value::String(second)) => Value::String(first + &second),
-                (Value::Number(first), Value::Number(second)) => Value::Number(first + second),
-                (_, _) => todo!(),
-            },
-            Operator::Star => match (left, right) {
-                (Value::String(data), Value::Number(times)) => {
-                    Value::String(data.repeat(times as usize))
+        // `and`/`or` only evaluate the right-hand side when it can change the result.
+        match expression.operator() {
+            BinaryOperator::And | BinaryOperator::Or => {
+                let left = match self.eval_condition(left, Span::from(expression.lhs().position()))
+                {
+                    Value::Boolean(left) => left,
+                    error => return error,
+                };
+
+                if left == (expression.operator() == BinaryOperator::Or) {
+                    return Value::Boolean(left);
                 }
-                (Value::Number(first), Value::Number(second)) => Value::Number(first * second),
-                (_, _) => todo!(),
-            },
-            Operator::Slash => match (left, right) {
-                (Value::Number(first), Value::Number(second)) => Value::Number(first / second),
-                (_, _) => todo!(),
-            },
-            Operator::Or => todo!(),
-            Operator::OrOr => todo!(),
-            Operator::PlusPlus => match left {
-                Value::Reference(path, scope) => {
-                    let mut context = self.context.lock().unwrap();
 
-                    if let Some(Value::Number(value)) =
-                        context.get(&path[0], scope.clone()).map(|var| &var.value)
-                    {
-                        let value = Value::Number(value + 1);
+                let right = self.eval_expression(expression.rhs().clone());
 
-                        context.set(&path, scope, value);
-                    }
+                return self.eval_condition(right, Span::from(expression.rhs().position()));
+            }
+            _ => {}
+        }
 
-                    Value::None
-                }
-                _ => todo!(),
-            },
-            Operator::Minus => match (left, right) {
-                (Value::Number(first), Value::Number(second)) => Value::Number(first - second),
-                (_, _) => todo!(),
-            },
-            Operator::MinusMinus => match left {
-                Value::Reference(path, scope) => {
-                    let mut context = self.context.lock().unwrap();
+        let right = self.eval_expression(expression.rhs().clone());
 
-                    if let Some(Value::Number(value)) =
-                        context.get(&path[0], scope.clone()).map(|var| &var.value)
-                    {
-                        let value = Value::Number(value - 1);
+        if right.is_error() {
+            return right;
+        }
 
-                        context.set(&path, scope, value);
-                    }
+        Self::binary_operation(expression.operator(), left, right, span)
+    }
 
-                    Value::None
-                }
-                _ => todo!(),
-            },
-            Operator::EqEq => Value::Boolean(left == right),
-            Operator::Eq => match (left, right) {
-                (Value::Reference(path, scope), value) => {
-                    let value = match value {
-                        Value::Reference(path, scope) => todo!(),
-                        value => value,
-                    };
+    /// Applies an arithmetic or comparison operator to two evaluated operands. `and`/`or` are
+    /// short-circuited by the caller and never reach here.
+    pub(crate) fn binary_operation(
+        operator: BinaryOperator,
+        left: Value,
+        right: Value,
+        span: Span,
+    ) -> Value {
+        // `na` propagates through arithmetic, and every comparison with it is false.
+        if left.is_na() || right.is_na() {
+            return match operator {
+                BinaryOperator::Add
+                | BinaryOperator::Subtract
+                | BinaryOperator::Multiply
+                | BinaryOperator::Divide => Value::None,
+                _ => Value::Boolean(false),
+            };
+        }
 
-                    self.context.lock().unwrap().set(&path, scope, value);
+        match (operator, left, right) {
+            (BinaryOperator::Add, Value::String(first), Value::String(second)) => {
+                Value::String(first + &second)
+            }
+            (
+                operator @ (BinaryOperator::Add
+                | BinaryOperator::Subtract
+                | BinaryOperator::Multiply),
+                Value::Number(first),
+                Value::Number(second),
+            ) => {
+                let result = match operator {
+                    BinaryOperator::Add => first.checked_add(second),
+                    BinaryOperator::Subtract => first.checked_sub(second),
+                    _ => first.checked_mul(second),
+                };
 
-                    Value::None
+                // An int that overflows has no value, like any other undefined result.
+                result.map_or(Value::None, Value::Number)
+            }
+            // Dividing ints only stays an int when nothing is lost: `5 / 2` is `2.5`. Division by
+            // zero and `i64::MIN / -1` are na.
+            (BinaryOperator::Divide, Value::Number(first), Value::Number(second)) => {
+                match (first.checked_rem(second), first.checked_div(second)) {
+                    (Some(0), Some(quotient)) => Value::Number(quotient),
+                    (Some(_), _) => Value::Float(first as f64 / second as f64),
+                    (None, _) => Value::None,
                 }
-                (_, _) => todo!(),
+            }
+            (operator, left, right) => match (left.as_float(), right.as_float()) {
+                (Some(first), Some(second)) => match operator {
+                    // Results that overflow to an infinity, or have no value, are na.
+                    BinaryOperator::Add => Value::finite(first + second),
+                    BinaryOperator::Subtract => Value::finite(first - second),
+                    BinaryOperator::Multiply => Value::finite(first * second),
+                    BinaryOperator::Divide => Value::finite(first / second),
+                    BinaryOperator::Equal => Value::Boolean(first == second),
+                    BinaryOperator::NotEqual => Value::Boolean(first != second),
+                    BinaryOperator::LessThan => Value::Boolean(first < second),
+                    BinaryOperator::LessThanOrEqual => Value::Boolean(first <= second),
+                    BinaryOperator::GreaterThan => Value::Boolean(first > second),
+                    BinaryOperator::GreaterThanOrEqual => Value::Boolean(first >= second),
+                    BinaryOperator::And | BinaryOperator::Or => unreachable!(),
+                },
+                _ => match operator {
+                    BinaryOperator::Equal => Value::Boolean(left == right),
+                    BinaryOperator::NotEqual => Value::Boolean(left != right),
+                    operator => Value::error(
+                        span,
+                        ErrorCode::Type,
+                        format!(
+                            "operator {:?} can't be applied to {} and {}",
+                            operator,
+                            left.type_of(),
+                            right.type_of()
+                        ),
+                    ),
+                },
             },
-            Operator::Ne => Value::Boolean(left != right),
-            Operator::Le => todo!(),
-            Operator::Ge => todo!(),
-            Operator::Lt => todo!(),
-            Operator::Gt => todo!(),
-            Operator::Not => match left {
-                Value::Reference(path, scope) => {
-                    if let Some(Value::Boolean(value)) = self
-                        .context
-                        .lock()
-                        .unwrap()
-                        .get(&path[0], scope.clone())
-                        .map(|var| &var.value)
-                    {
-                        return Value::Boolean(!value);
-                    }
+        }
+    }
 
-                    Value::None
-                }
+    pub fn eval_unary_expression(&mut self, expression: UnaryOperation) -> Value {
+        let span = Span::from(expression.position());
+        let operand = Span::from(expression.expression().position());
+        let value = self.eval_expression(expression.expression().clone());
+
+        match expression.operator() {
+            UnaryOperator::Not => match self.eval_condition(value, operand) {
                 Value::Boolean(value) => Value::Boolean(!value),
-                _ => todo!(),
+                error => error,
             },
+            UnaryOperator::Try => Value::error(
+                span,
+                ErrorCode::Implementing,
+                "`?` operator is not supported",
+            ),
         }
     }
-}
\ No newline at end of file
+}
@@ -1,122 +1,92 @@
-use tsr_lexer::globals::Positioned;
-use tsr_parser::ast::{FunctionCallExpression, Type};
+use ast::datatype::DataType;
+use ast::{Call, Expression};
+use parse::util::ArraySize;
+use parse::{call_site, Span};
 
 use crate::{
-    value::{self, ArrowParameter, ErrorCode, NativeFunction, Value}, FArguments, Runtime
+    value::{ErrorCode, NativeFunction, Parameter, Value},
+    FArguments, Runtime,
 };
 
 impl Runtime {
-    pub fn eval_call(&mut self, call: Positioned<FunctionCallExpression>) -> Value {
-        let (span, call) = call.unpack();
-        let func = match self.eval_expression(*call.function) {
-            Value::Reference(path, scope) => match todo!() {
-                Value::Reference(path, scope) => self
-                    .context
-                    .lock()
-                    .unwrap()
-                    .get(&path[0], scope.clone())
-                    .unwrap()
-                    .value
-                    .clone(),
-                value => value,
-            },
-            value => value,
+    pub fn eval_call(&mut self, call: Call) -> Value {
+        let span = Span::from(call.position());
+
+        // `receiver.name(...)`: constructors, `copy()` and methods are looked up on the receiver,
+        // which is then evaluated in place of the function.
+        let (callee, member) = match call.function() {
+            Expression::RecordDeconstruction(member) => (
+                self.eval_expression(member.expression().clone()),
+                Some((member.name(), member.type_arguments())),
+            ),
+            function => (self.eval_expression(function.clone()), None),
         };
-        let mut args = call
-            .arguments
-            .into_iter()
-            .map(|argument| {
-                (
-                    argument.span,
-                    match self.eval_expression(argument) {
-                        Value::Reference(path, scope) => todo!(),
-                        value => value,
-                    },
-                )
-            })
-            .collect::<Vec<_>>();
-
-        if let Some(lambda) = call.lambda.as_ref() {
-            args.push((lambda.span, Value::None));
+
+        if callee.is_error() {
+            return callee;
+        }
+
+        let mut args = Vec::with_capacity(call.arguments().len());
+
+        for argument in call.arguments() {
+            match self.eval_expression(argument.clone()) {
+                Value::Error(span, code, message) => return Value::Error(span, code, message),
+                value => args.push(value),
+            }
+        }
+
+        let mut named = Vec::with_capacity(call.named_arguments().len());
+
+        for argument in call.named_arguments() {
+            match self.eval_expression(argument.value().clone()) {
+                Value::Error(span, code, message) => return Value::Error(span, code, message),
+                value => named.push((argument.name().to_string(), value)),
+            }
         }
 
-        if matches!(func, Value::Error { .. }) {
-            return func.clone();
+        match member {
+            Some(member) => {
+                let site = call_site(call.position());
+
+                self.eval_member_call(span, site, callee, member, args, named)
+            }
+            None => self.call_value(span, callee, &[], args, named),
         }
+    }
 
-        match &func {
+    /// Calls `func` with the positional `args`, then the `named` ones; `type_arguments`, such as
+    /// `float` in `array.new<float>()`, are passed on to native functions.
+    pub(crate) fn call_value(
+        &mut self,
+        span: Span,
+        func: Value,
+        type_arguments: &[DataType],
+        args: Vec<Value>,
+        named: Vec<(String, Value)>,
+    ) -> Value {
+        match func {
             Value::NativeFunction(NativeFunction {
                 name,
                 parameters,
                 body,
-                ty,
                 ..
             }) => {
-                let arg_count = parameters
-                    .iter()
-                    .filter(|parameter| parameter.default.is_none())
-                    .count();
-
-                if args.len() < arg_count {
-                    return Value::error(
-                        span,
-                        ErrorCode::Type,
-                        format!(
-                            "wrong number of arguments: {} expected but {} given",
-                            arg_count,
-                            args.len()
-                        ),
-                    );
-                }
-
-                let zipped = parameters.iter().zip(args);
-
                 self.add_scope(format!("func:{}", name));
 
-                for (argument, (span, value)) in zipped {
-                    if value.is_none() && call.lambda.is_some() {
-                        if let Type::FunctionType(_, params, ty) = argument.ty.clone() {
-                            let value = span.wrap(Value::ArrowFunction(value::ArrowFunction {
-                                is_async: false,
-                                parameters: params
-                                    .into_iter()
-                                    .map(|param| ArrowParameter {
-                                        name: param.name.value.0,
-                                        nullable: param.nullable.value,
-                                        ty: Some(param.ty.value),
-                                        default: param
-                                            .default
-                                            .map(|default| Box::new(self.eval_expression(default))),
-                                    })
-                                    .collect(),
-                                ty: Some(*ty),
-                                body: call.lambda.unwrap(),
-                            }));
-
-                            self.set_variable(&argument.name, value);
-
-                            break;
-                        }
-                    } else {
-                        if !value.is_type_of(&argument.ty) {
-                            return Value::error(
-                                span,
-                                ErrorCode::Type,
-                                format!(
-                                    "{} expected but {} given",
-                                    argument.ty,
-                                    value.value_type_of()
-                                ),
-                            );
-                        }
-
-                        self.set_variable(&argument.name, span.wrap(value));
-                    }
+                if let error @ Value::Error(..) =
+                    self.bind_arguments(span, &parameters, args, named)
+                {
+                    self.remove_scope();
+
+                    return error;
                 }
 
                 let mut args = FArguments {
                     context: self.get_context(),
                     scope: self.scope.clone(),
+                    function: name,
+                    call_site: span,
+                    type_arguments: type_arguments.to_vec(),
                     returns: None,
                 };
 
@@ -125,67 +95,121 @@ impl Runtime {
                 self.clear_scope_variables();
                 self.remove_scope();
 
-                if let Some(value) = args.returns {
-                    if value.is_type_of(&ty) {
-                        return value;
-                    }
+                // Native functions don't know where they were called from.
+                match args.returns.unwrap_or(Value::None) {
+                    Value::Error(_, code, message) => Value::Error(span, code, message),
+                    value => value,
                 }
             }
-            Value::Function(func) => match func.call(span, self, args, call.lambda) {
-                Value::Error(span, code, message) => {
-                    self.error = Some(Value::Error(span, code, message))
-                }
-                value => return value,
-            },
-            Value::ArrowFunction(func) => {
-                let arg_count = func
-                    .parameters
-                    .iter()
-                    .filter(|parameter| parameter.default.is_none())
-                    .count();
-
-                if args.len() < arg_count {
-                    return Value::error(
-                        span,
-                        ErrorCode::Type,
-                        format!(
-                            "wrong number of arguments: {} expected but {} given",
-                            arg_count,
-                            args.len()
-                        ),
-                    );
-                }
+            Value::Function(func) => {
+                self.add_scope(format!("func:{}", func.name));
 
-                let zipped = func.parameters.iter().zip(args);
+                if let error @ Value::Error(..) =
+                    self.bind_arguments(span, &func.parameters, args, named)
+                {
+                    self.remove_scope();
 
-                self.add_scope("closure");
-
-                for (argument, (span, value)) in zipped {
-                    if argument.ty.as_ref().is_some_and(|ty| !value.is_type_of(ty)) {
-                        return Value::error(
-                            span,
-                            ErrorCode::Type,
-                            format!(
-                                "{} expected but {} given",
-                                argument.ty.as_ref().unwrap(),
-                                value.value_type_of()
-                            ),
-                        );
-                    }
-
-                    self.set_variable(&argument.name, span.wrap(value));
+                    return error;
                 }
 
-                let value = self.eval_code_block(func.body.clone());
+                let value = self.eval_code_block(func.body);
 
                 self.clear_scope_variables();
                 self.remove_scope();
 
-                if func.ty.is_none() || func.ty.as_ref().is_some_and(|ty| value.is_type_of(ty)) {
-                    return value;
-                }
+                self.outside_loop(self.returned(value), span)
             }
-            _ => {}
+            value => Value::error(
+                span,
+                ErrorCode::Type,
+                format!("{} is not a function", value.type_of()),
+            ),
+        }
+    }
+
+    /// Declares every parameter in the current (call) scope, from the positional arguments, then
+    /// the one named after it, then its default value. A variadic last parameter receives the
+    /// remaining positional arguments as an array.
+    /// Returns `Value::None`, or the arity error.
+    fn bind_arguments(
+        &mut self,
+        span: Span,
+        parameters: &[Parameter],
+        args: Vec<Value>,
+        mut named: Vec<(String, Value)>,
+    ) -> Value {
+        let variadic = parameters.last().is_some_and(|parameter| parameter.variadic);
+        let arg_count = parameters
+            .iter()
+            .filter(|parameter| parameter.default.is_none() && !parameter.variadic)
+            .count();
+
+        for (index, (name, _)) in named.iter().enumerate() {
+            let message = match parameters
+                .iter()
+                .position(|parameter| &parameter.name == name)
+            {
+                None => format!("no parameter is named `{name}`"),
+                Some(position) if parameters[position].variadic => {
+                    format!("`{name}` takes the remaining arguments and can't be named")
+                }
+                Some(position)
+                    if position < args.len()
+                        || named[..index].iter().any(|(other, _)| other == name) =>
+                {
+                    format!("`{name}` is given more than once")
+                }
+                Some(_) => continue,
+            };
+
+            return Value::error(span, ErrorCode::Type, message);
+        }
+
+        let missing = parameters.iter().enumerate().find(|(position, parameter)| {
+            parameter.default.is_none()
+                && !parameter.variadic
+                && *position >= args.len()
+                && !named.iter().any(|(name, _)| name == &parameter.name)
+        });
+
+        if let (Some((_, parameter)), false) = (missing, named.is_empty()) {
+            return Value::error(
+                span,
+                ErrorCode::Type,
+                format!("argument `{}` is missing", parameter.name),
+            );
+        }
+
+        if missing.is_some() || (!variadic && args.len() > parameters.len()) {
+            return Value::error(
+                span,
+                ErrorCode::Type,
+                format!(
+                    "wrong number of arguments: {} expected but {} given",
+                    arg_count,
+                    args.len()
+                ),
+            );
+        }
+
+        let mut args = args.into_iter();
+
+        for parameter in parameters {
+            let value = match parameter.variadic {
+                true => Value::Array(args.by_ref().collect::<Vec<_>>().into(), ArraySize::Dynamic),
+                false => args
+                    .next()
+                    .or_else(|| {
+                        let position =
+                            named.iter().position(|(name, _)| name == &parameter.name)?;
+
+                        Some(named.swap_remove(position).1)
+                    })
+                    .or_else(|| parameter.default.as_deref().cloned())
+                    .unwrap_or(Value::None),
+            };
+
+            self.declare_local(&parameter.name, parameter.take(value));
         }
 
         Value::None
@@ -57,7 +57,7 @@ impl Runtime {
             (Value::ReturnValue(value), _) => *value,
             (Value::Enum(enumeration), Value::String(key)) => enumeration
                 .get_moved(key)
-                .map(|member| *member.init)
+                .map(Value::EnumMember)
                 .unwrap_or(Value::error(
                     target_span,
                     ErrorCode::Implementing,
@@ -1,40 +1,27 @@
-use ast::List;
+use ast::{List, ListElement};
 use parse::util::ArraySize;
+
 use crate::{value::Value, Runtime};
 
 impl Runtime {
-    pub fn eval_list(
-        &mut self,
-        list: List,
-    ) -> Value {
-
-        let elements=list.elements()
-            .iter()
-            .map(|element| {
-                match element {
-                    ast::ListElement::Multiple(element) => {
-                        match self.eval_expression(**element) {
-                            Value::Reference(path, scope) => todo!(),
-                            value => value,
-                        }
-                    }
-                    ast::ListElement::Single(element) => {
-                        match self.eval_expression(**element) {
-                            Value::Reference(path, scope) => todo!(),
-                            value => value,
-                        }
-                    }
-                }
-            })
-            .collect::<Vec<_>>();
-
+    pub fn eval_list(&mut self, list: List) -> Value {
+        let mut elements = Vec::with_capacity(list.elements().len());
 
-        let size = elements.len();
+        for element in list.elements() {
+            match element {
+                ListElement::Multiple(expression) => match self.eval_expression(expression.clone())
+                {
+                    Value::Array(values, _) => elements.extend(values.to_vec()),
+                    Value::Error(span, code, message) => return Value::Error(span, code, message),
+                    value => elements.push(value),
+                },
+                ListElement::Single(expression) => match self.eval_expression(expression.clone()) {
+                    Value::Error(span, code, message) => return Value::Error(span, code, message),
+                    value => elements.push(value),
+                },
+            }
+        }
 
-        Value::Array(
-            elements,
-            elements
-                .map(ArraySize::Dynamic, ArraySize::Fixed(size)),
-        )
+        Value::Array(elements.into(), ArraySize::Dynamic)
     }
 }
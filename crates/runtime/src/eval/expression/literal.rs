@@ -1,14 +1,32 @@
+use ast::{Number, NumberRepresentation};
+use parse::Span;
 
-
-use crate::{value::Value, Runtime};
+use crate::{
+    value::{ErrorCode, Value},
+    Runtime,
+};
 
 impl Runtime {
-    // pub fn eval_literal(&self, literal: Literal) -> Value {
-    //     match literal {
-    //         Literal::String(string) => Value::String(string.value),
-    //         Literal::Number(number) => Value::Number(number.value),
-    //         Literal::Float(float) => Value::Float(float.value),
-    //         Literal::Boolean(boolean) => Value::Boolean(boolean.value),
-    //     }
-    // }
-}
\ No newline at end of file
+    pub fn eval_number(&self, number: Number) -> Value {
+        let value = match number.value() {
+            NumberRepresentation::Binary(digits) => {
+                i64::from_str_radix(digits, 2).map(Value::Number).ok()
+            }
+            NumberRepresentation::Hexadecimal(digits) => {
+                i64::from_str_radix(digits, 16).map(Value::Number).ok()
+            }
+            NumberRepresentation::Integer(digits) => digits.parse::<i64>().map(Value::Number).ok(),
+            NumberRepresentation::FloatingPoint(digits) => {
+                digits.parse::<f64>().map(Value::Float).ok()
+            }
+        };
+
+        value.unwrap_or_else(|| {
+            Value::error(
+                Span::from(number.position()),
+                ErrorCode::Type,
+                "number literal out of range",
+            )
+        })
+    }
+}
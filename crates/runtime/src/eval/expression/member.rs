@@ -0,0 +1,251 @@
+use ast::datatype::DataType;
+use ast::RecordDeconstruction;
+use parse::{CallSite, MethodCall, Span};
+
+use crate::{
+    value::{ClassInstance, ErrorCode, Field, Property, Value},
+    Runtime,
+};
+
+impl Runtime {
+    /// `object.field`, `Enum.member` and `namespace.name`
+    pub fn eval_member(&mut self, member: RecordDeconstruction) -> Value {
+        let span = Span::from(member.position());
+
+        match self.eval_expression(member.expression().clone()) {
+            Value::ClassInstance(instance) => instance.get_field(member.name()).unwrap_or_else(|| {
+                Value::error(
+                    span,
+                    ErrorCode::Reference,
+                    format!("`{}` has no field `{}`", instance.name, member.name()),
+                )
+            }),
+            Value::Enum(enumeration) => match enumeration.get(member.name()) {
+                Some(variant) => Value::EnumMember(variant.clone()),
+                None => Value::error(
+                    span,
+                    ErrorCode::Reference,
+                    format!("enum `{}` has no member `{}`", enumeration.name, member.name()),
+                ),
+            },
+            Value::Object(properties) => properties
+                .get(&Value::String(member.name().to_string()))
+                .cloned()
+                .unwrap_or_else(|| {
+                    Value::error(
+                        span,
+                        ErrorCode::Reference,
+                        format!("`{}` is not defined", member.name()),
+                    )
+                }),
+            error @ Value::Error(..) => error,
+            Value::None => Value::error(
+                span,
+                ErrorCode::Reference,
+                format!("can't read field `{}` of na", member.name()),
+            ),
+            value => Value::error(
+                span,
+                ErrorCode::Type,
+                format!("{} has no field `{}`", value.type_of(), member.name()),
+            ),
+        }
+    }
+
+    /// `Type.new(...)`, `object.copy()`, `namespace.function(...)` and method calls of `name`,
+    /// with the type arguments given to it.
+    pub(crate) fn eval_member_call(
+        &mut self,
+        span: Span,
+        site: CallSite,
+        receiver: Value,
+        (name, type_arguments): (&str, &[DataType]),
+        args: Vec<Value>,
+        named: Vec<(String, Value)>,
+    ) -> Value {
+        match receiver {
+            Value::Class {
+                name: type_name,
+                fields,
+                ..
+            } if name == "new" => Self::instantiate(span, type_name, fields, args, named),
+            Value::ClassInstance(instance) if name == "copy" && args.is_empty() => {
+                Value::ClassInstance(instance.copy())
+            }
+            Value::Object(properties) => match properties.get(&Value::String(name.to_string())) {
+                Some(function) => {
+                    self.call_value(span, function.clone(), type_arguments, args, named)
+                }
+                None => Value::error(
+                    span,
+                    ErrorCode::Reference,
+                    format!("`{name}` is not defined"),
+                ),
+            },
+            Value::None => Value::error(
+                span,
+                ErrorCode::Reference,
+                format!("can't call `{name}` on na"),
+            ),
+            receiver => self.eval_method_call(span, site, receiver, name, args, named),
+        }
+    }
+
+    /// `receiver.name(args)` calls `name(receiver, args)`: a method of the receiver's
+    /// user-defined type, or the target the resolver picked from the static type of the
+    /// receiver, either a `method` declared for the type or the function of the built-in
+    /// namespace of the type, so `arr.push(x)` is `array.push(arr, x)`.
+    fn eval_method_call(
+        &mut self,
+        span: Span,
+        site: CallSite,
+        receiver: Value,
+        name: &str,
+        mut args: Vec<Value>,
+        named: Vec<(String, Value)>,
+    ) -> Value {
+        let target = self.method_calls.get(&site).cloned();
+        let method = {
+            let context = self.context.lock().unwrap();
+            let lookup = |name: &str| {
+                context
+                    .get(name, self.scope.clone())
+                    .map(|variable| &variable.value)
+            };
+
+            let user_method = match (&receiver, lookup(type_name(&receiver))) {
+                (Value::ClassInstance(_), Some(Value::Class { methods, .. })) => methods
+                    .iter()
+                    .find(|method| method.name == name)
+                    .cloned()
+                    .map(Value::Function),
+                _ => None,
+            };
+
+            user_method.or_else(|| match &target {
+                Some(MethodCall::Method) => match lookup(name) {
+                    Some(function @ (Value::Function(_) | Value::NativeFunction(_))) => {
+                        Some(function.clone())
+                    }
+                    _ => None,
+                },
+                Some(MethodCall::Namespace(namespace)) => match lookup(namespace) {
+                    Some(Value::Object(properties)) => {
+                        properties.get(&Value::String(name.to_string())).cloned()
+                    }
+                    _ => None,
+                },
+                None => None,
+            })
+        };
+
+        match method {
+            Some(method) => {
+                args.insert(0, receiver);
+
+                self.call_value(span, method, &[], args, named)
+            }
+            None => Value::error(
+                span,
+                ErrorCode::Reference,
+                match target {
+                    Some(MethodCall::Namespace(namespace)) => format!(
+                        "{} has no method `{name}` and `{namespace}.{name}` doesn't exist",
+                        type_name(&receiver)
+                    ),
+                    Some(MethodCall::Method) | None
+                        if matches!(receiver, Value::ClassInstance(_)) =>
+                    {
+                        format!("{} has no method `{name}`", type_name(&receiver))
+                    }
+                    Some(MethodCall::Method) => {
+                        format!("method `{name}` is not declared")
+                    }
+                    None => format!(
+                        "can't call `{name}` on {}: the type of the receiver isn't known before \
+                         the script runs",
+                        type_name(&receiver)
+                    ),
+                },
+            ),
+        }
+    }
+
+    /// Fields take the positional arguments in declaration order, then the argument named after
+    /// them, then their defaults, then na.
+    fn instantiate(
+        span: Span,
+        name: String,
+        fields: Vec<Property>,
+        args: Vec<Value>,
+        mut named: Vec<(String, Value)>,
+    ) -> Value {
+        if args.len() > fields.len() {
+            return Value::error(
+                span,
+                ErrorCode::Type,
+                format!(
+                    "{name}.new takes at most {} arguments, but {} were given",
+                    fields.len(),
+                    args.len()
+                ),
+            );
+        }
+
+        for (index, (field, _)) in named.iter().enumerate() {
+            let message = match fields.iter().position(|prop| &prop.name == field) {
+                None => format!("{name} has no field `{field}`"),
+                Some(position)
+                    if position < args.len()
+                        || named[..index].iter().any(|(other, _)| other == field) =>
+                {
+                    format!("field `{field}` of {name} is given more than once")
+                }
+                Some(_) => continue,
+            };
+
+            return Value::error(span, ErrorCode::Type, message);
+        }
+
+        let mut args = args.into_iter();
+        let mut instance_fields = Vec::with_capacity(fields.len());
+
+        for prop in fields {
+            let given = args.next().or_else(|| {
+                let position = named.iter().position(|(field, _)| field == &prop.name)?;
+
+                Some(named.swap_remove(position).1)
+            });
+            let value = match given {
+                Some(arg) => match prop.coerce(arg.clone()) {
+                    Some(value) => value,
+                    None => {
+                        return Value::error(
+                            span,
+                            ErrorCode::Type,
+                            format!(
+                                "field `{}` of {name} is {}, but {} was given",
+                                prop.name,
+                                prop.ty.as_ref().map(ToString::to_string).unwrap_or_default(),
+                                arg.type_of()
+                            ),
+                        )
+                    }
+                },
+                None => prop.init.as_deref().cloned().unwrap_or(Value::None),
+            };
+
+            instance_fields.push(Field { prop, value });
+        }
+
+        Value::ClassInstance(ClassInstance::new(name, instance_fields))
+    }
+}
+
+fn type_name(value: &Value) -> &str {
+    match value {
+        Value::NativeFunction(_) => "function",
+        Value::Reference(..) => "reference",
+        value => value.type_of(),
+    }
+}
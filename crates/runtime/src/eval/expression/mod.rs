@@ -1,40 +1,77 @@
 use ast::Expression;
-use crate::{value::Value, Runtime};
+use parse::Span;
 
+use crate::{
+    value::{Color, ErrorCode, Value},
+    Runtime,
+};
+
+pub mod assignment;
 // pub mod array;
 // pub mod arrow_function;
-// pub mod binary;
-// pub mod call;
+pub mod binary;
+pub mod call;
 // pub mod index;
-// pub mod literal;
+pub mod literal;
+pub mod member;
 // pub mod new;
-// pub mod switch;
+pub mod switch;
+pub mod tuple;
 mod list;
-mod literal;
 
 impl Runtime {
     pub fn eval_expression(&mut self, expression: Expression) -> Value {
         match expression {
-            // Expression::BinaryExpression(expression) => {
-            //     self.eval_binary_expression(expression.value)
-            // }
+            Expression::Assignment(assignment) => self.eval_assignment(assignment),
+            Expression::BinaryOperation(operation) => self.eval_binary_expression(operation),
+            Expression::UnaryOperation(operation) => self.eval_unary_expression(operation),
+            Expression::Boolean(boolean) => Value::Boolean(boolean.value()),
+            Expression::Na(_) => Value::None,
+            Expression::Color(color) => Value::Color(Color::new(
+                color.red(),
+                color.green(),
+                color.blue(),
+                color.alpha(),
+            )),
+            Expression::Number(number) => self.eval_number(number),
+            Expression::String(string) => Value::String(string.value().into()),
+            Expression::List(list) => self.eval_list(list),
+            Expression::Tuple(tuple) => self.eval_tuple(tuple),
+            Expression::TupleDefinition(definition) => self.eval_tuple_definition(definition),
+            Expression::Call(call) => self.eval_call(call),
+            Expression::Variable(variable) => self.eval_variable(variable),
+            Expression::RecordDeconstruction(member) => self.eval_member(member),
+            Expression::IfDecl(if_) => self.eval_if(*if_),
+            Expression::Conditional(conditional) => self.eval_conditional(conditional),
+            Expression::For(for_) => self.eval_for(*for_),
+            Expression::Switch(switch) => self.eval_switch(*switch),
+            Expression::Break(_) => Value::Break,
+            Expression::Continue(_) => Value::Continue,
             // Expression::IndexExpression(expression) => self.eval_index_expression(expression.value),
-            // Expression::MatchExpression(expression) => self.eval_match_expression(expression.value),
-            // Expression::Block(block) => self.eval_program(block),
-            // Expression::Literal(literal) => self.eval_literal(literal.value),
-             Expression::List (list) => self.eval_list(list),
-
             // Expression::ArrowFunction(func) => self.eval_arrow_function(func.value),
-            // Expression::Null => Value::Null,
-            // Expression::FunctionCallExpression(call) => self.eval_call(*call),
             // Expression::NewExpression(expression) => self.eval_new_expression(expression),
-            // Expression::Ident(name) => Value::Reference(vec![name.value.0], self.scope.clone()),
-            // Expression::This => Value::Reference(vec!["this".into()], self.scope.clone()),
-            //
-
-            _ => {
-                todo!()
-            }
+            expression => Value::error(
+                Span::from(expression.position()),
+                ErrorCode::Implementing,
+                "expression can't be evaluated yet",
+            ),
         }
     }
+
+    pub fn eval_variable(&mut self, variable: ast::Variable) -> Value {
+        let value = self
+            .context
+            .lock()
+            .unwrap()
+            .get(variable.name(), self.scope.clone())
+            .map(|variable| variable.value.clone());
+
+        value.unwrap_or_else(|| {
+            Value::error(
+                Span::from(variable.position()),
+                ErrorCode::Reference,
+                format!("`{}` is not defined", variable.name()),
+            )
+        })
+    }
 }
@@ -1,19 +1,45 @@
-use tsr_parser::ast::MatchExpression;
+use ast::Switch;
+use parse::Span;
 
 use crate::{value::Value, Runtime};
 
 impl Runtime {
-    pub fn eval_match_expression(&mut self, expression: MatchExpression) -> Value {
-        let target = self.eval_expression(expression.target);
+    /// Runs the first arm whose pattern equals the subject, or, without a subject, the first arm
+    /// whose condition holds. Falls back to the default arm, then to `na` (`Value::None`).
+    pub fn eval_switch(&mut self, switch: Switch) -> Value {
+        let subject = match switch.subject {
+            Some(subject) => match self.eval_expression(subject) {
+                Value::Error(span, code, message) => return Value::Error(span, code, message),
+                subject => Some(subject),
+            },
+            None => None,
+        };
 
-        for variant in expression.variants {
-            let value = self.eval_expression(variant.value.value);
+        for arm in switch.arms {
+            let span = Span::from(arm.pattern.position());
+            let pattern = self.eval_expression(arm.pattern);
 
-            if target == value {
-                return self.eval_statement(variant.value.callback);
+            let matched = match &subject {
+                Some(subject) if !pattern.is_error() => {
+                    match (subject.as_float(), pattern.as_float()) {
+                        (Some(subject), Some(pattern)) => subject == pattern,
+                        _ => subject == &pattern,
+                    }
+                }
+                _ => match self.eval_condition(pattern, span) {
+                    Value::Boolean(matched) => matched,
+                    error => return error,
+                },
+            };
+
+            if matched {
+                return self.eval_scoped_block("switch", arm.body);
             }
         }
 
-        Value::None
+        match switch.default {
+            Some(body) => self.eval_scoped_block("switch", body),
+            None => Value::None,
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,59 @@
+use ast::{Tuple, TupleDefinition};
+use parse::Span;
+
+use crate::{
+    value::{ErrorCode, Value},
+    Runtime,
+};
+
+impl Runtime {
+    pub fn eval_tuple(&mut self, tuple: Tuple) -> Value {
+        let mut elements = Vec::with_capacity(tuple.elements().len());
+
+        for element in tuple.elements() {
+            let value = self.eval_expression(element.clone());
+
+            if value.is_error() || value.is_loop_control() {
+                return value;
+            }
+
+            elements.push(value);
+        }
+
+        Value::Tuple(elements)
+    }
+
+    /// Declares one variable per name from the elements of a tuple of the same size.
+    pub fn eval_tuple_definition(&mut self, definition: TupleDefinition) -> Value {
+        let value = self.eval_expression(definition.value().clone());
+
+        let elements = match value {
+            Value::Tuple(elements) if elements.len() == definition.names().len() => elements,
+            Value::Tuple(elements) => {
+                return Value::error(
+                    Span::from(definition.position()),
+                    ErrorCode::Type,
+                    format!(
+                        "tuple of {} values can't be destructured into {} names",
+                        elements.len(),
+                        definition.names().len()
+                    ),
+                )
+            }
+            value if value.is_error() || value.is_loop_control() => return value,
+            value => {
+                return Value::error(
+                    Span::from(definition.position()),
+                    ErrorCode::Type,
+                    format!("expected a tuple, but got {}", value.type_of()),
+                )
+            }
+        };
+
+        for (name, element) in definition.names().iter().zip(&elements) {
+            self.declare_local(&name.name, element.clone());
+        }
+
+        Value::Tuple(elements)
+    }
+}
@@ -0,0 +1,77 @@
+use ast::Declaration;
+use parse::Span;
+
+use crate::{
+    api::strategy,
+    metadata::ScriptMetadata,
+    value::{ErrorCode, Value},
+    Runtime,
+};
+
+impl Runtime {
+    /// Evaluates the arguments of the declaration statement and applies the metadata they make
+    /// up to the run.
+    pub fn eval_declaration(&mut self, declaration: Declaration) -> Value {
+        let span = Span::from(declaration.position());
+
+        if self.context.lock().unwrap().metadata().is_some() {
+            return Value::error(
+                span,
+                ErrorCode::Declaration,
+                format!(
+                    "{}() comes after the declaration of the script",
+                    declaration.kind().name()
+                ),
+            );
+        }
+
+        let mut arguments = vec![];
+
+        for (parameter, argument) in declaration.parameters() {
+            let value = self.eval_expression(argument.value().clone());
+
+            if value.is_error() {
+                return value;
+            }
+
+            if let Some(parameter) = parameter {
+                arguments.push((parameter, value));
+            }
+        }
+
+        match ScriptMetadata::new(declaration.kind(), &arguments) {
+            Ok(metadata) => {
+                self.apply_metadata(metadata);
+
+                Value::None
+            }
+            Err(message) => Value::error(span, ErrorCode::Declaration, message),
+        }
+    }
+
+    /// Sets the drawing limits of the script and, for strategies, opens their account in the
+    /// `strategy` namespace.
+    fn apply_metadata(&mut self, metadata: ScriptMetadata) {
+        for (kind, count) in &metadata.max_counts {
+            self.set_drawing_limit(*kind, *count);
+        }
+
+        let account = strategy::account(&metadata);
+        let namespace = self
+            .context
+            .lock()
+            .unwrap()
+            .get("strategy", self.scope.clone())
+            .map(|variable| variable.value.clone());
+
+        if let Some(Value::Object(mut namespace)) = namespace {
+            for (name, value) in account {
+                namespace.insert(Value::String(name.into()), value);
+            }
+
+            self.set_variable("strategy", Value::Object(namespace));
+        }
+
+        *self.context.lock().unwrap().metadata_mut() = Some(metadata);
+    }
+}
@@ -1,58 +1,55 @@
-use ast::{FunctionDecl};
-use ast::types::Type;
-use parse::Span;
-use crate::{value::{Function,   Value, Visibility}, Runtime};
-use crate::value::Parameter;
+use ast::FunctionDecl;
 
+use crate::value::Parameter;
+use crate::{
+    value::{Function, Value, Visibility},
+    Runtime,
+};
 
 impl Runtime {
     pub fn declare_function(&mut self, function: FunctionDecl) -> Value {
-        //let (span, function) = function.unpack();
-        let mut visibility = Visibility::Private;
-        let mut is_async = false;
-        let mut is_static = false;
+        match self.build_function(function) {
+            Value::Function(function) => {
+                self.set_variable(function.name.clone(), Value::Function(function))
+            }
+            error => error,
+        }
+    }
 
-        // for modifier in function.modifiers {
-        //     let modifier = modifier.value;
-        //
-        //     match modifier {
-        //         Modifier::Public => visibility = Visibility::Public,
-        //         Modifier::Private => visibility = Visibility::Private,
-        //         Modifier::Protected => visibility = Visibility::Protected,
-        //         Modifier::Async => is_async = true,
-        //         Modifier::Static => is_static = true,
-        //     }
-        // }
+    /// Evaluates parameter defaults and returns the `Value::Function`, or the error.
+    pub(crate) fn build_function(&mut self, function: FunctionDecl) -> Value {
+        let visibility = Visibility::Private;
+        let is_async = false;
+        let is_static = false;
 
-        if let Some(body) = function.body {
-            let parameters = function
-                .arguments
-                .into_iter()
-                .map(|param| Parameter {
-                    name: param.name.name,
-                    nullable: true,
-                   // ty: Type::Function(function),
-                    default: param
-                        .default_value.map(|expression| Box::new(self.eval_expression(expression))),
+        let mut parameters = Vec::with_capacity(function.arguments.len());
 
-                })
-                .collect();
+        for param in function.arguments {
+            let default = match param.default_value {
+                Some(expression) => match self.eval_expression(expression) {
+                    Value::Error(span, code, message) => return Value::Error(span, code, message),
+                    value => Some(Box::new(value)),
+                },
+                None => None,
+            };
 
-            self.set_variable(
-                function.name.name.clone(),
-                Span::empty().wrap(Value::Function(Function {
-                    visibility,
-                    overloads: Vec::default(),
-                    is_async,
-                    is_static,
-                    name: function.name.name,
-                    parameters,
-                    ty: Type::Function(Type::Function),
-                    body,
-                })),
-            );
+            parameters.push(Parameter {
+                name: param.name.name,
+                nullable: true,
+                ty: param.data_type,
+                default,
+                variadic: false,
+            });
         }
 
-        Value::None
+        Value::Function(Function {
+            visibility,
+            overloads: Vec::default(),
+            is_async,
+            is_static,
+            name: function.name.name,
+            parameters,
+            body: function.body,
+        })
     }
-}
\ No newline at end of file
+}
@@ -1,10 +1,35 @@
-use tsr_lexer::globals::Positioned;
-use tsr_parser::ast::IfStatement;
+use ast::{Conditional, Else, IfDecl};
+use parse::Span;
 
 use crate::{value::Value, Runtime};
 
 impl Runtime {
-    pub fn eval_if(&mut self, statement: Positioned<IfStatement>) -> Value {
-        Value::None
+    /// Evaluates an `if`/`else if`/`else` chain; the taken branch's last value is the result,
+    /// and `na` (`Value::None`) when no branch is taken.
+    pub fn eval_if(&mut self, statement: IfDecl) -> Value {
+        let span = Span::from(statement.predicat.position());
+        let condition = self.eval_expression(statement.predicat);
+
+        match self.eval_condition(condition, span) {
+            Value::Boolean(true) => self.eval_scoped_block("if", statement.body),
+            Value::Boolean(false) => match statement.else_.map(|else_| *else_) {
+                Some(Else::If(if_)) => self.eval_if(if_),
+                Some(Else::Body(body)) => self.eval_scoped_block("else", body),
+                None => Value::None,
+            },
+            error => error,
+        }
+    }
+
+    /// Evaluates `condition ? then : else`; only the selected branch is evaluated.
+    pub fn eval_conditional(&mut self, conditional: Conditional) -> Value {
+        let span = Span::from(conditional.condition().position());
+        let condition = self.eval_expression(conditional.condition().clone());
+
+        match self.eval_condition(condition, span) {
+            Value::Boolean(true) => self.eval_expression(conditional.then().clone()),
+            Value::Boolean(false) => self.eval_expression(conditional.else_().clone()),
+            error => error,
+        }
     }
 }
@@ -1,77 +1,146 @@
-use tsr_lexer::globals::Positioned;
-use tsr_parser::ast::{ImportClause, ImportDeclaration, Literal};
+use std::collections::BTreeMap;
 
-use crate::{value::{builders::ObjectBuilder, ErrorCode, Value}, Runtime};
+use ast::{Import, ModulePath, TopLevel};
+use parse::Span;
+
+use crate::{
+    value::{builders::ObjectBuilder, ErrorCode, Value},
+    FunctionBuilder, Runtime,
+};
 
 impl Runtime {
-    pub fn eval_import(&mut self, import: Positioned<ImportDeclaration>) -> Value {
-        let (span, import) = (import.span, import.value);
+    /// `import owner/name/version as alias`. The library runs in a scope of its own, so it
+    /// shares the script's environment but not its names, and `alias` becomes a namespace of its
+    /// exports. Exported methods are also declared by name so `object.method()` finds them, and
+    /// the names listed in `{...}` are declared unqualified.
+    pub fn eval_import(&mut self, import: Import) -> Value {
+        let span = Span::from(import.position());
 
-        let Literal::String(module) = import.module_specifier.value else {
-            unreachable!()
+        let ModulePath::Library(path) = import.module_path() else {
+            return Value::error(
+                span,
+                ErrorCode::Implementing,
+                format!("module `{}` can't be imported yet", import.module_path()),
+            );
+        };
+        let Some(library) = self.libraries.get(path).cloned() else {
+            return Value::error(
+                span,
+                ErrorCode::Reference,
+                format!("library `{path}` is not loaded"),
+            );
         };
 
-        if let Some(module) = self
-            .modules
+        let mut runtime = Runtime::new_in_scope(self.get_context(), format!("library:{path}"));
+
+        runtime.libraries = self.libraries.clone();
+        runtime.method_calls = self.method_calls.clone();
+
+        // The library's own declaration would replace the script's metadata.
+        let top_levels = library
+            .top_levels
             .iter()
-            .find(|native| native.name == module.value)
-        {
-            if let Some(clause) = import.import_clause {
-                match clause.value {
-                    ImportClause::Named(_) => {}
-                    ImportClause::NamedImports(value) => {
-                        for specifier in value {
-                            let specifier = specifier.value;
-                            let (name_span, name) = specifier
-                                .property_name
-                                .map(|alias| alias.unpack())
-                                .unwrap_or(specifier.name.clone().unpack());
-
-                            if let Some(value) = module
-                                .exports
-                                .iter()
-                                .find(|export| export.0 == specifier.name.value.0)
-                            {
-                                self.set_variable(name.0, span.wrap(value.1.clone()));
-                            } else {
-                                self.error = Some(Value::error(
-                                    name_span,
-                                    ErrorCode::Reference,
-                                    format!(
-                                        "found \"{}\" while excepted one of next values: {}",
-                                        specifier.name.value,
-                                        module
-                                            .exports
-                                            .iter()
-                                            .map(|specifier| specifier.0.as_str())
-                                            .collect::<Vec<_>>()
-                                            .join(", ")
-                                    ),
-                                ));
-
-                                break;
-                            }
-                        }
-                    }
-                    ImportClause::NamespaceImport(value) => {
-                        let mut object = ObjectBuilder::default();
-
-                        for (key, value) in &module.exports {
-                            object = object.prop(key, value.clone());
-                        }
-
-                        self.set_variable(value.value.0, value.span.wrap(object.build()));
-                    }
+            .filter(|top_level| !matches!(top_level, TopLevel::Declaration(_)))
+            .cloned()
+            .collect();
+
+        if let error @ Value::Error(..) = runtime.eval_app_block(top_levels) {
+            return error;
+        }
+
+        let mut namespace = ObjectBuilder::default();
+
+        for top_level in &library.top_levels {
+            let (name, is_method) = match top_level {
+                TopLevel::Function(function) if function.exported => (&*function.name.name, false),
+                TopLevel::Method(method) if method.exported => (&*method.name.name, true),
+                TopLevel::Type(definition) if definition.is_exported() => {
+                    (definition.name(), false)
                 }
+                TopLevel::Enum(definition) if definition.is_exported() => {
+                    (definition.name(), false)
+                }
+                _ => continue,
+            };
+            let value = exported(&runtime, name);
+
+            if is_method
+                || import
+                    .unqualified_names()
+                    .iter()
+                    .any(|unqualified| unqualified.name() == name)
+            {
+                self.set_variable(name, value.clone());
             }
-        } else {
-            self.error = Some(Value::error(
-                module.span,
-                ErrorCode::Reference,
-                format!("failed to resolve module \"{}\"", module.value),
-            ));
+
+            namespace = namespace.prop(name, value);
         }
 
+        self.set_variable(import.prefix().unwrap_or(path.name()), namespace.build());
+
         Value::None
     }
 }
+
+/// The value the library declared as `name`, with functions turned into native ones calling
+/// them in the library's scope, where the names they use are declared.
+fn exported(library: &Runtime, name: &str) -> Value {
+    let value = library
+        .context
+        .lock()
+        .unwrap()
+        .get(name, library.scope.clone())
+        .map(|variable| variable.value.clone());
+
+    match value {
+        Some(Value::Function(function)) => {
+            let context = library.get_context();
+            let scope = library.scope.clone();
+            let method_calls = library.method_calls.clone();
+
+            FunctionBuilder::new(&function.name)
+                .params(function.parameters.clone())
+                .build(move |args| {
+                    let values = function
+                        .parameters
+                        .iter()
+                        .map(|parameter| args.get(&parameter.name).unwrap_or(Value::None))
+                        .collect();
+                    let mut runtime = Runtime {
+                        context: context.clone(),
+                        modules: vec![],
+                        error: None,
+                        scope: scope.clone(),
+                        libraries: BTreeMap::new(),
+                        method_calls: method_calls.clone(),
+                    };
+                    let value = runtime.call_value(
+                        args.call_site,
+                        Value::Function(function.clone()),
+                        &[],
+                        values,
+                        vec![],
+                    );
+
+                    args.returns(value);
+                })
+        }
+        // Methods would run in the script's scope; the exported ones are declared by name.
+        Some(Value::Class {
+            name,
+            extends,
+            implements,
+            constructors,
+            fields,
+            ..
+        }) => Value::Class {
+            name,
+            extends,
+            implements,
+            constructors,
+            fields,
+            methods: vec![],
+        },
+        value => value.unwrap_or(Value::None),
+    }
+}
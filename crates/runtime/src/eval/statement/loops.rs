@@ -0,0 +1,178 @@
+use ast::{For, ForIn, ForRange, While};
+use parse::Span;
+
+use crate::{
+    value::{ErrorCode, Value},
+    Runtime,
+};
+
+impl Runtime {
+    pub fn eval_for(&mut self, statement: For) -> Value {
+        match statement {
+            For::In(for_in) => self.eval_for_in(for_in),
+            For::Range(for_range) => self.eval_for_range(*for_range),
+            For::While(while_) => self.eval_while(while_),
+        }
+    }
+
+    pub fn eval_while(&mut self, statement: While) -> Value {
+        let mut last = Value::None;
+
+        loop {
+            let span = Span::from(statement.predicat.position());
+            let condition = self.eval_expression(statement.predicat.clone());
+
+            match self.eval_condition(condition, span) {
+                Value::Boolean(true) => {}
+                Value::Boolean(false) => break,
+                error => return error,
+            }
+
+            match self.eval_scoped_block("while", statement.body.clone()) {
+                Value::Break => break,
+                Value::Continue => continue,
+                value if value.is_returned() || value.is_error() => return value,
+                value => last = value,
+            }
+        }
+
+        last
+    }
+
+    pub fn eval_for_in(&mut self, statement: ForIn) -> Value {
+        let span = Span::from(&statement.position);
+
+        let elements = match self.eval_expression(statement.expr) {
+            Value::Array(elements, _) => elements.to_vec(),
+            Value::Error(span, code, message) => return Value::Error(span, code, message),
+            value => {
+                return Value::error(
+                    span,
+                    ErrorCode::Type,
+                    format!("array expected but {} given", value.type_of()),
+                )
+            }
+        };
+
+        let mut last = Value::None;
+
+        for element in elements {
+            self.add_scope("for");
+            self.declare_local(&statement.value.name, element);
+
+            let value = self.eval_code_block(statement.body.clone());
+
+            self.clear_scope_variables();
+            self.remove_scope();
+
+            match value {
+                Value::Break => break,
+                Value::Continue => continue,
+                value if value.is_returned() || value.is_error() => return value,
+                value => last = value,
+            }
+        }
+
+        last
+    }
+
+    /// Counts from `start` to `end` inclusive. Without `by` the step is `1` or `-1` depending on
+    /// the direction; a step pointing away from `end` runs the body zero times.
+    pub fn eval_for_range(&mut self, statement: ForRange) -> Value {
+        let span = Span::from(&statement.position);
+
+        let start = self.eval_expression(statement.start);
+        let end = self.eval_expression(statement.end);
+        let step = match statement.step {
+            Some(step) => self.eval_expression(step),
+            None => Value::None,
+        };
+
+        for value in [&start, &end, &step] {
+            if value.is_error() {
+                return value.clone();
+            }
+        }
+
+        let (Some(from), Some(to)) = (start.as_float(), end.as_float()) else {
+            return Value::error(
+                span,
+                ErrorCode::Type,
+                format!(
+                    "loop bounds must be numbers but {} and {} given",
+                    start.type_of(),
+                    end.type_of()
+                ),
+            );
+        };
+
+        let step = match step {
+            Value::None if from <= to => Value::Number(1),
+            Value::None => Value::Number(-1),
+            step => step,
+        };
+
+        match step.as_float() {
+            Some(by) if by != 0.0 => {}
+            _ => {
+                return Value::error(
+                    span,
+                    ErrorCode::Type,
+                    format!("loop step must be a non-zero number but {step} given"),
+                )
+            }
+        }
+
+        let mut counter = start;
+        let mut last = Value::None;
+
+        loop {
+            let (current, by) = (counter.as_float().unwrap(), step.as_float().unwrap());
+
+            if (by > 0.0 && current > to) || (by < 0.0 && current < to) {
+                break;
+            }
+
+            self.add_scope("for");
+            self.declare_local(&statement.value.name, counter.clone());
+
+            let value = self.eval_code_block(statement.body.clone());
+
+            self.clear_scope_variables();
+            self.remove_scope();
+
+            match value {
+                Value::Break => break,
+                Value::Continue => {}
+                value if value.is_returned() || value.is_error() => return value,
+                value => last = value,
+            }
+
+            counter = match (&counter, &step) {
+                (Value::Number(current), Value::Number(by)) => match current.checked_add(*by) {
+                    Some(next) => Value::Number(next),
+                    None => break,
+                },
+                _ => Value::Float(current + by),
+            };
+        }
+
+        last
+    }
+
+    /// Converts a condition to `Value::Boolean`, an na bool being `false`. Like the checker,
+    /// it takes nothing else: a number isn't a bool, and becomes a type error at `span`, the
+    /// condition's.
+    pub(crate) fn eval_condition(&self, condition: Value, span: Span) -> Value {
+        match condition {
+            Value::Boolean(value) => Value::Boolean(value),
+            Value::None | Value::Null => Value::Boolean(false),
+            Value::Error(span, code, message) => Value::Error(span, code, message),
+            value => Value::error(
+                span,
+                ErrorCode::Type,
+                format!("bool expected but {} given", value.type_of()),
+            ),
+        }
+    }
+}
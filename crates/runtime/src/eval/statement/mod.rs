@@ -1,59 +1,99 @@
-use ast::{Statement, Statements, TopLevel};
+use ast::{Statement, TopLevel};
+use parse::Span;
 
-use crate::{value::Value, Runtime};
+use crate::{
+    value::{ErrorCode, Value},
+    Runtime,
+};
 //
 // pub mod class;
-// pub mod enumeration;
+pub mod declaration;
+pub mod enumeration;
 // pub mod export;
 pub mod function;
-// pub mod if_else;
-// pub mod import;
+pub mod if_else;
+pub mod import;
 // pub mod interface;
+pub mod loops;
+pub mod user_type;
 // pub mod type_alias;
 pub mod variable;
 
 impl Runtime {
-      pub fn eval_top_level(&mut self, stmt:TopLevel ) -> Value {
-          match stmt {
-              TopLevel::Variable(variable) => self.declare_variable(variable),
-              TopLevel::Function(function) => self.declare_function(function),
-              // TopLevel::Comment(_) => {}
-              // TopLevel::Import(_) => {}
-              // TopLevel::If(_) => {}
-              // TopLevel::While(_) => {}
-              // TopLevel::For(_) => {}
-              // TopLevel::Mod(_, _) => {}
-              _ => {
-                  todo!()
-              }
-          }
-
-      }
-
-    // pub fn eval_statement(&mut self, stmt:Statement ) -> Value {
-    //     match stmt {
-    //
-    //         // Statement::
-    //         // Statement::ImportDeclaration(import) => self.eval_import(*import),
-    //         // Statement::TypeAliasDeclaration(type_alias) => self.declare_type_alias(type_alias),
-    //         // Statement::InterfaceDeclaration(interface) => self.declare_interface(interface),
-    //         // Statement::FunctionDeclaration(function) => self.declare_function(function),
-    //         // Statement::EnumDeclaration(enumeration) => self.declare_enum(enumeration),
-    //         // Statement::ExportDeclaration(export) => self.eval_export(export),
-    //         // Statement::ClassDeclaration(class) => self.declare_class(class),
-    //         // Statement::VariableStatement(variable) => self.declare_variable(variable),
-    //         // Statement::IfStatement(statement) => self.eval_if(*statement),
-    //         // Statement::ReturnStatement(statement) => self.eval_expression(statement),
-    //         // Statement::Expression(expression) => self.eval_expression(expression),
-    //         // Statements::Compound(_) => {}
-    //         // Statements::Record(_) => {}
-    //         // Statements::Import(_) => {}
-    //         // Statements::Map(_) => {}
-    //         Statements::Func(function) =>self.declare_function(function),
-    //         Statements::Expression(expression) => self.eval_expression(expression),
-    //         _ => {
-    //             todo!()
-    //         }
-    //     }
-    // }
+    pub fn eval_top_level(&mut self, stmt: TopLevel) -> Value {
+        match stmt {
+            TopLevel::Variable(variable) => self.declare_variable(variable),
+            TopLevel::Assignment(assignment) => {
+                let position = assignment.position().clone();
+                let value = self.eval_assignment(assignment);
+
+                self.outside_loop(value, Span::from(&position))
+            }
+            TopLevel::TupleDefinition(definition) => {
+                let position = definition.position().clone();
+                let value = self.eval_tuple_definition(definition);
+
+                self.outside_loop(value, Span::from(&position))
+            }
+            TopLevel::Function(function) => self.declare_function(function),
+            TopLevel::Method(method) => self.declare_method(method),
+            TopLevel::Type(definition) => self.declare_type(definition),
+            TopLevel::Enum(definition) => self.declare_enum(definition),
+            TopLevel::Declaration(declaration) => self.eval_declaration(declaration),
+            TopLevel::Comment(_) | TopLevel::Annotation(_) => Value::None,
+            TopLevel::If(if_) => {
+                let position = if_.position().clone();
+                let value = self.eval_if(if_);
+
+                self.outside_loop(value, Span::from(&position))
+            }
+            TopLevel::While(while_) => self.eval_while(while_),
+            TopLevel::For(for_) => self.eval_for(for_),
+            TopLevel::Switch(switch) => {
+                let position = switch.position().clone();
+                let value = self.eval_switch(switch);
+
+                self.outside_loop(value, Span::from(&position))
+            }
+            TopLevel::Expression(expression) => {
+                let position = expression.position().clone();
+                let value = self.eval_expression(expression);
+
+                self.outside_loop(value, Span::from(&position))
+            }
+            TopLevel::Import(import) => self.eval_import(import),
+            TopLevel::Mod(name, _, position) => Value::error(
+                Span::from(&position),
+                ErrorCode::Implementing,
+                format!("module `{}` can't be evaluated yet", name.name),
+            ),
+        }
+    }
+
+    pub fn eval_statement(&mut self, stmt: Statement) -> Value {
+        let value = self.eval_expression(stmt.expression().clone());
+
+        // `name = value` always declares in the current scope; reassigning an outer variable
+        // goes through `:=` instead.
+        match stmt.name() {
+            Some(name) if !(value.is_error() || value.is_loop_control()) => {
+                self.declare_local(name, value.clone());
+
+                value
+            }
+            _ => value,
+        }
+    }
+
+    /// Turns a `break`/`continue` that escaped every enclosing loop into an error.
+    pub(crate) fn outside_loop(&self, value: Value, span: Span) -> Value {
+        match value {
+            Value::Break | Value::Continue => Value::error(
+                span,
+                ErrorCode::ControlFlow,
+                format!("`{value}` outside of a loop"),
+            ),
+            value => value,
+        }
+    }
 }
@@ -0,0 +1,93 @@
+use ast::datatype::DataType;
+use ast::{FunctionDecl, UserTypeDefinition};
+use parse::Span;
+
+use crate::{
+    value::{ErrorCode, Property, Value},
+    Runtime,
+};
+
+impl Runtime {
+    /// Declares a user-defined type. Field defaults are evaluated once, at declaration.
+    pub fn declare_type(&mut self, definition: UserTypeDefinition) -> Value {
+        let mut fields = Vec::with_capacity(definition.fields().len());
+
+        for field in definition.fields() {
+            let init = match field.default() {
+                Some(expression) => match self.eval_expression(expression.clone()) {
+                    error @ Value::Error(..) => return error,
+                    value => Some(Box::new(value)),
+                },
+                None => None,
+            };
+
+            fields.push(Property {
+                name: field.name().to_string(),
+                nullable: true,
+                ty: Some(field.data_type().clone()),
+                init,
+            });
+        }
+
+        self.set_variable(
+            definition.name(),
+            Value::Class {
+                name: definition.name().to_string(),
+                extends: vec![],
+                implements: vec![],
+                constructors: vec![],
+                fields,
+                methods: vec![],
+            },
+        )
+    }
+
+    /// Declares `method` as a plain function and, when its receiver is a user-defined type,
+    /// attaches it to that type so it can be called as `object.method(...)`.
+    pub fn declare_method(&mut self, method: FunctionDecl) -> Value {
+        let span = Span::from(&method.position);
+        let receiver = method
+            .arguments
+            .first()
+            .and_then(|parameter| parameter.data_type.clone());
+
+        let Some(receiver) = receiver else {
+            return Value::error(
+                span,
+                ErrorCode::Declaration,
+                format!(
+                    "method `{}` needs a typed first parameter",
+                    method.name.name
+                ),
+            );
+        };
+
+        let function = match self.build_function(method) {
+            Value::Function(function) => function,
+            error => return error,
+        };
+
+        if let DataType::Custom(type_name) = receiver {
+            let mut context = self.context.lock().unwrap();
+
+            match context
+                .get_mut(&type_name, self.scope.clone())
+                .map(|variable| &mut variable.value)
+            {
+                Some(Value::Class { methods, .. }) => {
+                    methods.retain(|existing| existing.name != function.name);
+                    methods.push(function.clone());
+                }
+                _ => {
+                    return Value::error(
+                        span,
+                        ErrorCode::Reference,
+                        format!("`{type_name}` is not a type"),
+                    )
+                }
+            }
+        }
+
+        self.set_variable(function.name.clone(), Value::Function(function))
+    }
+}
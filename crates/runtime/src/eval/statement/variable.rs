@@ -1,44 +1,33 @@
-use ast::VariableStatement;
-use crate::{
-    value::{ErrorCode, Value},
-    Runtime,
-};
+use ast::VariableDefinition;
+use parse::Span;
 
-impl Runtime {
-    pub fn declare_variable(&mut self, variables: VariableStatement) -> Value {
-         let mut last = Value::None;
+use crate::{value::Value, Runtime};
 
-        for variable in variables.declarations {
+impl Runtime {
+    /// `name = value`, or `type name = value`, whose value is then promoted to `type`, as are the
+    /// values later assigned with `:=`.
+    pub fn declare_variable(&mut self, variable: VariableDefinition) -> Value {
+        let value = self.eval_expression(variable.value);
 
-            // if !variable.nullable.value && variable.initializer.is_none() {
-            //     return Value::error(span, ErrorCode::Type, "expected anything, but got nothing");
-            // }
+        if value.is_error() {
+            return value;
+        }
 
-            let (value_span, value) = if let Some(expression) = variable.value {
-                (
-                    expression.span,
-                    match self.eval_expression(expression) {
-                        Value::Reference(path, scope) => todo!(),
-                        value => value,
-                    },
-                )
-            } else {
-                ("", Value::None)
-            };
+        if value.is_loop_control() {
+            return self.outside_loop(value, Span::from(&variable.position));
+        }
 
-            // if let Some(ty) = variable.ty {
-            //     if !value.is_type_of(&ty.value) {
-            //         return Value::error(
-            //             value_span,
-            //             ErrorCode::Type,
-            //             format!("expected {}, but got {}", ty.value, value.value_type_of()),
-            //         );
-            //     }
-            // }
+        let name = variable.identifier.name;
+        let Some(ty) = variable.var_type else {
+            return self.set_variable(name, value);
+        };
+        let result = self.set_variable(&name, value.promote(&ty));
 
-            last = self.set_variable(variable.identifier.name,  value);
-        }
+        self.context
+            .lock()
+            .unwrap()
+            .set_type(name, self.scope.clone(), ty);
 
-        last
+        result
     }
 }
@@ -0,0 +1,337 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use parse::Span;
+
+use crate::value::{Color, Value};
+
+/// The kinds of `input.*` declarations.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum InputKind {
+    Int,
+    Float,
+    Bool,
+    String,
+    Source,
+    Timeframe,
+    Session,
+    Color,
+    Enum,
+}
+
+impl InputKind {
+    /// The function declaring the kind, in the `input` namespace.
+    pub fn name(&self) -> &'static str {
+        match self {
+            InputKind::Int => "int",
+            InputKind::Float => "float",
+            InputKind::Bool => "bool",
+            InputKind::String => "string",
+            InputKind::Source => "source",
+            InputKind::Timeframe => "timeframe",
+            InputKind::Session => "session",
+            InputKind::Color => "color",
+            InputKind::Enum => "enum",
+        }
+    }
+}
+
+impl Display for InputKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "input.{}", self.name())
+    }
+}
+
+/// The built-in series `input.source` can name.
+pub const SOURCES: [&str; 9] = [
+    "open", "high", "low", "close", "volume", "hl2", "hlc3", "ohlc4", "hlcc4",
+];
+
+/// An `input.*` declaration of a script, and the value the host set for it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptInput {
+    /// The variable the input is assigned to, which is how hosts refer to it.
+    pub name: Option<String>,
+    pub kind: InputKind,
+    pub title: Option<String>,
+    pub default: Value,
+    pub minval: Option<f64>,
+    pub maxval: Option<f64>,
+    pub step: Option<f64>,
+    pub options: Vec<Value>,
+    pub tooltip: Option<String>,
+    pub inline: Option<String>,
+    pub group: Option<String>,
+    /// Set by the host in place of the default.
+    pub value: Option<Value>,
+    pub(crate) call_site: Span,
+}
+
+impl ScriptInput {
+    /// The value the script sees: the host's, or the default.
+    pub fn value(&self) -> &Value {
+        self.value.as_ref().unwrap_or(&self.default)
+    }
+
+    /// `value` as the kind of the input, or why it can't be one; ints are taken as floats.
+    pub fn validate(&self, value: Value) -> Result<Value, String> {
+        let value = match (self.kind, value) {
+            (_, value) if value.is_na() => return Ok(value),
+            (InputKind::Int, Value::Float(float)) if float.fract() == 0.0 => {
+                Value::Number(float as i64)
+            }
+            (InputKind::Float, Value::Number(number)) => Value::Float(number as f64),
+            (InputKind::Enum, Value::EnumMember(member))
+                if matches!(&self.default, Value::EnumMember(default)
+                    if default.enumeration != member.enumeration) =>
+            {
+                return Err(format!(
+                    "{} is not a member of {}",
+                    member.name,
+                    self.default.type_of()
+                ))
+            }
+            (InputKind::Int, value @ Value::Number(_))
+            | (InputKind::Float, value @ Value::Float(_))
+            | (InputKind::Bool, value @ Value::Boolean(_))
+            | (InputKind::Color, value @ Value::Color(_))
+            | (InputKind::Enum, value @ (Value::EnumMember(_) | Value::String(_))) => value,
+            (InputKind::String, value @ Value::String(_)) => value,
+            (InputKind::Source, Value::String(source)) if SOURCES.contains(&source.as_str()) => {
+                Value::String(source)
+            }
+            (InputKind::Timeframe, Value::String(timeframe)) if is_timeframe(&timeframe) => {
+                Value::String(timeframe)
+            }
+            (InputKind::Session, Value::String(session)) if is_session(&session) => {
+                Value::String(session)
+            }
+            (
+                InputKind::Source | InputKind::Timeframe | InputKind::Session,
+                Value::String(text),
+            ) => return Err(format!("{text:?} is not a valid {}", self.kind.name())),
+            (kind, value) => return Err(format!("{kind} can't take a {}", value.type_of())),
+        };
+
+        if let Some(number) = value.as_float() {
+            if let Some(minval) = self.minval.filter(|minval| number < *minval) {
+                return Err(format!("{value} is less than {minval}"));
+            }
+
+            if let Some(maxval) = self.maxval.filter(|maxval| number > *maxval) {
+                return Err(format!("{value} is greater than {maxval}"));
+            }
+        }
+
+        let option = |option: &Value| match (option, &value) {
+            (Value::EnumMember(option), Value::String(name)) => option.name == *name,
+            (Value::Number(option), Value::Float(float)) => *option as f64 == *float,
+            (option, value) => option == value,
+        };
+
+        match self.options.is_empty() || self.options.iter().any(option) {
+            true => Ok(value),
+            false => Err(format!("{value} is not one of the options")),
+        }
+    }
+
+    /// Parses `text` as a value of the kind of the input, as given on a command line.
+    pub fn parse(&self, text: &str) -> Result<Value, String> {
+        let value = match self.kind {
+            InputKind::Int => text.parse().map(Value::Number).ok(),
+            InputKind::Float => text.parse().map(Value::Float).ok(),
+            InputKind::Bool => text.parse().map(Value::Boolean).ok(),
+            InputKind::Color => parse_color(text).map(Value::Color),
+            _ => Some(Value::String(text.to_string())),
+        };
+
+        value
+            .ok_or_else(|| format!("{text:?} is not a {}", self.kind.name()))
+            .and_then(|value| self.validate(value))
+    }
+}
+
+/// Why the host can't set an input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputError {
+    pub name: String,
+    pub message: String,
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "input `{}`: {}", self.name, self.message)
+    }
+}
+
+impl Error for InputError {}
+
+/// The inputs a script declares, in order, with the values set by the host.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScriptInputs {
+    inputs: Vec<ScriptInput>,
+}
+
+impl ScriptInputs {
+    pub fn inputs(&self) -> &[ScriptInput] {
+        &self.inputs
+    }
+
+    /// The input assigned to variable `name`, or else titled `name`.
+    pub fn get(&self, name: &str) -> Option<&ScriptInput> {
+        self.position(name).map(|index| &self.inputs[index])
+    }
+
+    /// Sets the value of input `name` instead of its default.
+    pub fn set(&mut self, name: &str, value: Value) -> Result<(), InputError> {
+        let index = self.find(name)?;
+        let input = &mut self.inputs[index];
+
+        input.value = Some(input.validate(value).map_err(|message| InputError {
+            name: name.to_string(),
+            message,
+        })?);
+
+        Ok(())
+    }
+
+    /// Sets the value of input `name` from its text, such as `21` for an int.
+    pub fn set_str(&mut self, name: &str, text: &str) -> Result<(), InputError> {
+        let index = self.find(name)?;
+        let input = &mut self.inputs[index];
+
+        input.value = Some(input.parse(text).map_err(|message| InputError {
+            name: name.to_string(),
+            message,
+        })?);
+
+        Ok(())
+    }
+
+    /// Sets the inputs given as `--input name=value` or `--input=name=value` among `args`, as
+    /// the `pine` command takes them; other arguments are left for the caller.
+    pub fn apply_args<I, S>(&mut self, args: I) -> Result<(), InputError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let assignment = match arg.as_ref() {
+                "--input" => args.next().map(|next| next.as_ref().to_string()),
+                arg => match arg.strip_prefix("--input=") {
+                    Some(assignment) => Some(assignment.to_string()),
+                    None => continue,
+                },
+            };
+
+            match assignment.as_deref().and_then(|text| text.split_once('=')) {
+                Some((name, text)) => self.set_str(name.trim(), text)?,
+                None => {
+                    return Err(InputError {
+                        name: assignment.unwrap_or_default(),
+                        message: "expected `--input name=value`".into(),
+                    })
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records `input` as declared by the script and returns its value. An input declared again
+    /// from the same place keeps its name and the value set by the host.
+    pub(crate) fn declare(&mut self, input: ScriptInput) -> Value {
+        match self
+            .inputs
+            .iter_mut()
+            .find(|declared| declared.call_site == input.call_site)
+        {
+            Some(declared) => {
+                *declared = ScriptInput {
+                    name: declared.name.take(),
+                    value: declared.value.take(),
+                    ..input
+                };
+
+                declared.value().clone()
+            }
+            None => {
+                let value = input.value().clone();
+
+                self.inputs.push(input);
+                value
+            }
+        }
+    }
+
+    /// Names the input declared at `call_site` after the variable it's assigned to.
+    pub(crate) fn name(&mut self, call_site: Span, name: &str) {
+        if let Some(input) = self
+            .inputs
+            .iter_mut()
+            .find(|input| input.call_site == call_site)
+        {
+            input.name = Some(name.to_string());
+        }
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        self.inputs
+            .iter()
+            .position(|input| input.name.as_deref() == Some(name))
+            .or_else(|| {
+                self.inputs
+                    .iter()
+                    .position(|input| input.title.as_deref() == Some(name))
+            })
+    }
+
+    fn find(&self, name: &str) -> Result<usize, InputError> {
+        self.position(name).ok_or_else(|| InputError {
+            name: name.to_string(),
+            message: "the script has no such input".into(),
+        })
+    }
+}
+
+/// `#RRGGBB` or `#RRGGBBAA`.
+fn parse_color(text: &str) -> Option<Color> {
+    let digits = text.strip_prefix('#')?;
+    let byte = |index: usize| u8::from_str_radix(digits.get(index..index + 2)?, 16).ok();
+
+    match digits.len() {
+        6 => Some(Color::new(byte(0)?, byte(2)?, byte(4)?, 0xFF)),
+        8 => Some(Color::new(byte(0)?, byte(2)?, byte(4)?, byte(6)?)),
+        _ => None,
+    }
+}
+
+/// A chart resolution such as `60` (minutes), `1D`, `W` or `""` for the chart's own.
+fn is_timeframe(timeframe: &str) -> bool {
+    let digits = timeframe.chars().take_while(char::is_ascii_digit).count();
+
+    match &timeframe[digits..] {
+        "" => digits > 0 || timeframe.is_empty(),
+        "S" | "D" | "W" | "M" => true,
+        _ => false,
+    }
+}
+
+/// Trading hours such as `0930-1600`, optionally followed by the days as in `:23456`.
+fn is_session(session: &str) -> bool {
+    let (hours, days) = session.split_once(':').unwrap_or((session, "1234567"));
+    let time = |time: &str| {
+        time.len() == 4
+            && time.chars().all(|c| c.is_ascii_digit())
+            && time[..2] < *"24"
+            && time[2..] < *"60"
+    };
+    let days = !days.is_empty() && days.chars().all(|day| ('1'..='7').contains(&day));
+
+    session == "24x7"
+        || hours
+            .split_once('-')
+            .is_some_and(|(start, end)| time(start) && time(end) && days)
+}
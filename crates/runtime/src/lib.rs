@@ -1,27 +1,42 @@
 pub use self::value::builders::FunctionBuilder;
 use self::{
+    drawing::{DrawingKind, DrawingObject, Drawings},
     environment::{Context, Environment, Scope},
+    input::ScriptInputs,
+    metadata::ScriptMetadata,
+    plot::{PlotOutput, Plots},
+    table::{TableObject, Tables},
     value::{
         native::{Module, NativeModule},
         ErrorCode, Function, NativeFunction, Parameter, Signature, Value, Visibility,
     },
 };
-use std::{
-    collections::HashMap,
-    sync::{Arc, RwLock},
-};
-use ast::{Block, Body, TopLevel};
-use ast::types::Type;
-use parse::{Root, Span};
+use std::collections::{BTreeMap, HashMap};
+use ast::datatype::DataType;
+use ast::{Body, Expression, LibraryModulePath, Mod, TopLevel};
+use parse::{CallSite, MethodCall, Overload, Root, Span};
 // use tsr_lexer::globals::Positioned;
 // use tsr_parser::ast::{Block, Type};
 
 pub mod api;
+pub mod drawing;
 pub mod environment;
 pub mod eval;
+pub mod input;
+pub mod metadata;
+pub mod plot;
+pub mod table;
 pub mod value;
 
-pub type FunctionTuple = (Visibility, bool, bool, bool, String, Vec<Parameter>, Type);
+pub type FunctionTuple = (
+    Visibility,
+    bool,
+    bool,
+    bool,
+    String,
+    Vec<Parameter>,
+    Option<DataType>,
+);
 
 macro_rules! try_unpack {
     ($variant:path, $value:expr) => {
@@ -37,6 +52,9 @@ macro_rules! try_unpack {
 pub struct FArguments {
     context: Context,
     scope: Scope,
+    function: String,
+    call_site: Span,
+    type_arguments: Vec<DataType>,
     returns: Option<Value>,
 }
 
@@ -62,6 +80,54 @@ impl FArguments {
         self.get_by_scope(name, self.scope.clone())
     }
 
+    /// Value kept by this function for its current call site, see [`FArguments::set_state`].
+    pub fn state(&self) -> Option<Value> {
+        self.context
+            .lock()
+            .unwrap()
+            .call_state(&self.function, self.call_site)
+            .cloned()
+    }
+
+    /// Types given to the call between `<>`, such as `string, float` in
+    /// `map.new<string, float>()`.
+    pub fn type_arguments(&self) -> &[DataType] {
+        &self.type_arguments
+    }
+
+    /// Keeps `value` until the next call from the same place in the script.
+    pub fn set_state(&self, value: Value) {
+        self.context
+            .lock()
+            .unwrap()
+            .set_call_state(&self.function, self.call_site, value);
+    }
+
+    /// Tick size of the symbol the script runs against.
+    pub fn mintick(&self) -> f64 {
+        self.context.lock().unwrap().mintick()
+    }
+
+    /// Runs `body` with the drawing objects of the run.
+    pub fn drawings<R>(&self, body: impl FnOnce(&mut Drawings) -> R) -> R {
+        body(self.context.lock().unwrap().drawings_mut())
+    }
+
+    /// Runs `body` with the inputs of the script.
+    pub fn inputs<R>(&self, body: impl FnOnce(&mut ScriptInputs) -> R) -> R {
+        body(self.context.lock().unwrap().inputs_mut())
+    }
+
+    /// Runs `body` with the plots of the run.
+    pub fn plots<R>(&self, body: impl FnOnce(&mut Plots) -> R) -> R {
+        body(self.context.lock().unwrap().plots_mut())
+    }
+
+    /// Runs `body` with the tables of the run.
+    pub fn tables<R>(&self, body: impl FnOnce(&mut Tables) -> R) -> R {
+        body(self.context.lock().unwrap().tables_mut())
+    }
+
     pub fn returns<V: Into<Value>>(&mut self, value: V) {
         self.returns = Some(value.into());
     }
@@ -98,9 +164,8 @@ impl FArguments {
                     is_static,
                     name,
                     parameters,
-                    ty,
                     ..
-                }) => Some((visibility, is_async, is_static, false, name, parameters, ty)),
+                }) => Some((visibility, is_async, is_static, false, name, parameters, None)),
                 _ => None,
             }
         } else {
@@ -135,34 +200,92 @@ impl FArguments {
 
 #[derive(Debug, Clone)]
 pub struct Runtime {
-    types: Arc<RwLock<HashMap<String, Type>>>,
     context: Context,
     modules: Vec<Module>,
     error: Option<Value>,
     scope: Scope,
+    /// Modules of the libraries the script can import.
+    libraries: BTreeMap<LibraryModulePath, Mod>,
+    /// How the resolver dispatched each `receiver.name(...)` call.
+    method_calls: HashMap<CallSite, MethodCall>,
 }
 
 impl Default for Runtime {
     fn default() -> Self {
-        Self {
-            types: Arc::new(RwLock::new(HashMap::new())),
-            context: Environment::new(),
-            modules: Default::default(),
-            scope: vec!["root".into()],
-            error: None,
-        }
+        Self::new(Environment::new())
     }
 }
 
 impl Runtime {
     pub fn new(context: Context) -> Self {
-        Self {
-            types: Arc::new(RwLock::new(HashMap::new())),
+        Self::new_in_scope(context, "root".into())
+    }
+
+    /// A runtime declaring its names in `scope` of `context`, such as the one of an imported
+    /// library.
+    fn new_in_scope(context: Context, scope: String) -> Self {
+        let mut runtime = Self {
             context,
             modules: Default::default(),
-            scope: vec!["root".into()],
+            scope: vec![scope],
             error: None,
-        }
+            libraries: BTreeMap::new(),
+            method_calls: HashMap::new(),
+        };
+
+        runtime.add_module(&api::array::Array);
+        runtime.add_module(&api::array::Order);
+        runtime.add_module(&api::matrix::Matrix);
+        runtime.add_module(&api::map::Map);
+        runtime.add_module(&api::math::Math);
+        runtime.add_module(&api::str::Str);
+        runtime.add_module(&api::str::Format);
+        runtime.add_module(&api::color::Color);
+        runtime.add_module(&api::input::Input);
+        runtime.add_module(&api::drawing::DrawingNamespace(DrawingKind::Line));
+        runtime.add_module(&api::drawing::DrawingNamespace(DrawingKind::Label));
+        runtime.add_module(&api::drawing::DrawingNamespace(DrawingKind::Box));
+        runtime.add_module(&api::drawing::DrawingNamespace(DrawingKind::Linefill));
+        runtime.add_module(&api::drawing::DrawingNamespace(DrawingKind::Polyline));
+        runtime.add_module(&api::table::Table);
+        runtime.add_module(&api::strategy::Strategy);
+        runtime.add_module(&api::drawing::Constants("xloc", &["bar_index", "bar_time"]));
+        runtime.add_module(&api::drawing::Constants(
+            "yloc",
+            &["price", "abovebar", "belowbar"],
+        ));
+        runtime.add_module(&api::drawing::Constants(
+            "extend",
+            &["none", "left", "right", "both"],
+        ));
+        runtime.add_module(&api::drawing::Constants(
+            "size",
+            &["auto", "tiny", "small", "normal", "large", "huge"],
+        ));
+        runtime.add_module(&api::drawing::Constants(
+            "text",
+            &["align_left", "align_center", "align_right", "align_top", "align_bottom"],
+        ));
+        runtime.add_module(&api::drawing::Constants(
+            "position",
+            &[
+                "top_left",
+                "top_center",
+                "top_right",
+                "middle_left",
+                "middle_center",
+                "middle_right",
+                "bottom_left",
+                "bottom_center",
+                "bottom_right",
+            ],
+        ));
+        runtime.add_module(&api::drawing::Constants("scale", &["right", "left", "none"]));
+        runtime.add_globals(&api::na::Na);
+        runtime.add_globals(&api::cast::Cast);
+        runtime.add_globals(&api::plot::Plot);
+
+        runtime
     }
 
     fn add_scope<S: AsRef<str>>(&mut self, scope: S) {
@@ -189,16 +312,55 @@ impl Runtime {
             .remove_by_scope_filtered(self.scope.clone(), variables);
     }
 
-    // fn associate_type(&self, name: String, ty: Type) {
-    //     self.types.write().unwrap().insert(name, ty);
-    // }
+    /// Registers a native module and exposes it to scripts as a namespace of the same name.
+    pub fn add_module<M: NativeModule>(&mut self, module: &'static M) {
+        let module = module.build_module();
 
-    // fn resolve_type(&self, name: String) -> Option<Type> {
-    //     self.types.read().unwrap().get(&name).cloned()
-    // }
+        self.set_variable(&module.name, module.to_object());
+        self.modules.push(module);
+    }
 
-    pub fn add_module<M: NativeModule>(&mut self, module: &'static M) {
-        self.modules.push(module.build_module());
+    /// Result types each native `namespace.function` declares, its overloads ahead of the type it
+    /// returns otherwise, for the resolver to type calls and check destructuring against; see
+    /// `Config.overloads`.
+    pub fn overloads(&self) -> HashMap<String, Vec<Overload>> {
+        self.modules
+            .iter()
+            .flat_map(|module| {
+                module.exports.iter().filter_map(|(name, value)| match value {
+                    Value::NativeFunction(NativeFunction { ty, overloads, .. }) => {
+                        let mut overloads = overloads.clone();
+
+                        match ty {
+                            // `any` and `enum` stand for the type of an argument.
+                            Some(DataType::Custom(ty)) if ty == "any" || ty == "enum" => {}
+                            Some(ty) => overloads.push(Overload {
+                                parameters: vec![],
+                                returns: ty.clone(),
+                            }),
+                            None => {}
+                        }
+
+                        let name = format!("{}.{name}", module.name);
+
+                        (!overloads.is_empty()).then_some((name, overloads))
+                    }
+                    _ => None,
+                })
+            })
+            .collect()
+    }
+
+    /// Registers the exports of a native module as global functions, for built-ins such as `nz`
+    /// that aren't part of a namespace.
+    pub fn add_globals<M: NativeModule>(&mut self, module: &'static M) {
+        let module = module.build_module();
+
+        for (name, value) in &module.exports {
+            self.set_variable(name, value.clone());
+        }
+
+        self.modules.push(module);
     }
 
     pub fn set_variable<N: AsRef<str>>(&self, name: N, value: Value) -> Value {
@@ -231,6 +393,24 @@ impl Runtime {
         Value::None
     }
 
+    fn declare_local<N: AsRef<str>>(&self, name: N, value: Value) {
+        self.context
+            .lock()
+            .unwrap()
+            .declare(name, self.scope.clone(), value);
+    }
+
+    fn eval_scoped_block<S: AsRef<str>>(&mut self, scope: S, body: Body) -> Value {
+        self.add_scope(scope);
+
+        let value = self.eval_code_block(body);
+
+        self.clear_scope_variables();
+        self.remove_scope();
+
+        value
+    }
+
     fn eval_code_block(&mut self, mut body: Body) -> Value {
         match body.stmts.len() {
             0 => Value::None,
@@ -241,7 +421,7 @@ impl Runtime {
 
                 if let Some(error) = &self.error {
                     error.clone()
-                } else if value.is_returned() {
+                } else if value.is_returned() || value.is_loop_control() || value.is_error() {
                     value
                 } else {
                     self.eval_code_block(body)
@@ -250,6 +430,105 @@ impl Runtime {
         }
     }
 
+    /// The drawing objects still alive, oldest first, for the host to render after a run.
+    pub fn drawings(&self) -> Vec<DrawingObject> {
+        self.context.lock().unwrap().drawings().objects().to_vec()
+    }
+
+    /// The plots of the runs so far, for the host to render after a run.
+    pub fn plots(&self) -> Vec<PlotOutput> {
+        self.context.lock().unwrap().plots().outputs().to_vec()
+    }
+
+    /// The tables still alive, oldest first, for the host to render after a run with
+    /// [`TableObject::render_text`] or [`TableObject::render_html`].
+    pub fn tables(&self) -> Vec<TableObject> {
+        self.context.lock().unwrap().tables().objects().to_vec()
+    }
+
+    /// Sets how many objects of `kind` are kept before the oldest are deleted.
+    pub fn set_drawing_limit(&mut self, kind: DrawingKind, limit: usize) {
+        self.context
+            .lock()
+            .unwrap()
+            .drawings_mut()
+            .set_limit(kind, limit);
+    }
+
+    /// Sets the tick size of the symbol, used by `math.round_to_mintick` and `format.mintick`.
+    pub fn set_mintick(&mut self, mintick: f64) {
+        self.context.lock().unwrap().set_mintick(mintick);
+    }
+
+    /// Collects the inputs `root` declares, before running it, for the host to list and set.
+    /// Each top-level `name = input.*(...)` is evaluated on its own, along with the enums, so
+    /// an input whose arguments refer to other variables is only known once the script runs.
+    pub fn script_inputs(root: &Root) -> ScriptInputs {
+        let mut scratch = Runtime::default();
+
+        for top_level in &root.r#mod.top_levels {
+            match top_level {
+                TopLevel::Enum(enumeration) => {
+                    scratch.declare_enum(enumeration.clone());
+                }
+                TopLevel::Variable(definition) => {
+                    let Expression::Call(call) = definition.value() else {
+                        continue;
+                    };
+                    let input = matches!(
+                        call.function(),
+                        Expression::RecordDeconstruction(member)
+                            if matches!(member.expression(), Expression::Variable(namespace)
+                                if namespace.name() == "input")
+                    );
+
+                    if input && !scratch.eval_call(call.clone()).is_error() {
+                        scratch.context.lock().unwrap().inputs_mut().name(
+                            Span::from(call.position()),
+                            &definition.identifier().name,
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        scratch.inputs()
+    }
+
+    /// Sets the inputs of the next run, such as those of [`Runtime::script_inputs`] with the
+    /// values the host chose.
+    pub fn set_inputs(&mut self, inputs: ScriptInputs) {
+        *self.context.lock().unwrap().inputs_mut() = inputs;
+    }
+
+    /// The inputs the script declared so far, in order.
+    pub fn inputs(&self) -> ScriptInputs {
+        self.context.lock().unwrap().inputs().clone()
+    }
+
+    /// The metadata of the script's declaration, once it ran.
+    pub fn metadata(&self) -> Option<ScriptMetadata> {
+        self.context.lock().unwrap().metadata().cloned()
+    }
+
+    /// Reads the declaration of `root` before running it, or `None` when the script has no
+    /// valid one, such as when its arguments refer to variables of the script.
+    pub fn script_metadata(root: &Root) -> Option<ScriptMetadata> {
+        let declaration = root
+            .r#mod
+            .top_levels
+            .iter()
+            .find_map(|top_level| match top_level {
+                TopLevel::Declaration(declaration) => Some(declaration.clone()),
+                _ => None,
+            })?;
+        let mut scratch = Runtime::default();
+
+        scratch.eval_declaration(declaration);
+        scratch.metadata()
+    }
+
     pub fn get_context(&self) -> Context {
         self.context.clone()
     }
@@ -267,8 +546,11 @@ impl Runtime {
         self.returned(value)
     }
 
-    pub fn eval_app(&mut self, astRoot: Root) -> Value {
-        let program=astRoot.r#mod.top_levels;
+    pub fn eval_app(&mut self, root: Root) -> Value {
+        let program = root.r#mod.top_levels;
+
+        self.libraries = root.libraries;
+        self.method_calls = root.method_calls;
 
         let value = self.eval_app_block(program);
 
@@ -284,7 +566,7 @@ impl Runtime {
 
                 if let Some(error) = &self.error {
                     error.clone()
-                } else if value.is_returned() {
+                } else if value.is_returned() || value.is_error() {
                     value
                 } else {
                     self.eval_app_block(stmts)
@@ -0,0 +1,213 @@
+use ast::DeclarationKind;
+
+use crate::drawing::DrawingKind;
+use crate::value::Value;
+
+/// What the declaration statement of a script says about it: `indicator(...)`,
+/// `strategy(...)` or `library(...)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptMetadata {
+    pub kind: DeclarationKind,
+    pub title: String,
+    pub shorttitle: Option<String>,
+    pub overlay: bool,
+    /// One of the `format.*` constants.
+    pub format: String,
+    pub precision: Option<i64>,
+    /// One of the `scale.*` constants, when the script isn't on the price scale.
+    pub scale: Option<String>,
+    pub max_bars_back: Option<i64>,
+    /// How many objects of each kind are kept, when the script sets it.
+    pub max_counts: Vec<(DrawingKind, usize)>,
+    pub dynamic_requests: bool,
+    /// Set for `strategy(...)` scripts only.
+    pub strategy: Option<StrategyProperties>,
+}
+
+/// The properties of a `strategy(...)` script, with Pine's defaults.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StrategyProperties {
+    pub pyramiding: i64,
+    pub calc_on_order_fills: bool,
+    pub calc_on_every_tick: bool,
+    /// One of `strategy.fixed`, `strategy.cash` or `strategy.percent_of_equity`.
+    pub default_qty_type: String,
+    pub default_qty_value: f64,
+    pub initial_capital: f64,
+    pub currency: String,
+    pub slippage: i64,
+    /// One of the `strategy.commission.*` constants.
+    pub commission_type: String,
+    pub commission_value: f64,
+    pub process_orders_on_close: bool,
+    pub close_entries_rule: String,
+    pub margin_long: f64,
+    pub margin_short: f64,
+}
+
+impl Default for StrategyProperties {
+    fn default() -> Self {
+        Self {
+            pyramiding: 0,
+            calc_on_order_fills: false,
+            calc_on_every_tick: false,
+            default_qty_type: "fixed".into(),
+            default_qty_value: 1.0,
+            initial_capital: 1_000_000.0,
+            currency: "NONE".into(),
+            slippage: 0,
+            commission_type: "percent".into(),
+            commission_value: 0.0,
+            process_orders_on_close: false,
+            close_entries_rule: "FIFO".into(),
+            margin_long: 100.0,
+            margin_short: 100.0,
+        }
+    }
+}
+
+/// The constants `default_qty_type` and `commission_type` take.
+pub const QUANTITY_TYPES: [&str; 3] = ["fixed", "cash", "percent_of_equity"];
+pub const COMMISSION_TYPES: [&str; 3] = ["percent", "cash_per_contract", "cash_per_order"];
+
+impl ScriptMetadata {
+    /// Checks the evaluated arguments of a declaration, given by parameter name. na arguments
+    /// are left to their defaults.
+    pub fn new(kind: DeclarationKind, arguments: &[(&str, Value)]) -> Result<Self, String> {
+        let argument = |name: &str| {
+            arguments
+                .iter()
+                .find(|(parameter, value)| *parameter == name && !value.is_na())
+                .map(|(_, value)| value)
+        };
+        let text = |name: &str| match argument(name) {
+            None => Ok(None),
+            Some(Value::String(text)) => Ok(Some(text.clone())),
+            Some(value) => Err(format!(
+                "`{name}` must be a string, but {} given",
+                value.type_of()
+            )),
+        };
+        let boolean = |name: &str, default: bool| match argument(name) {
+            None => Ok(default),
+            Some(Value::Boolean(boolean)) => Ok(*boolean),
+            Some(value) => Err(format!(
+                "`{name}` must be a bool, but {} given",
+                value.type_of()
+            )),
+        };
+        let float = |name: &str, default: f64| match argument(name) {
+            None => Ok(default),
+            Some(value) => value
+                .as_float()
+                .ok_or_else(|| format!("`{name}` must be a number, but {} given", value.type_of())),
+        };
+        let int = |name: &str, range: std::ops::RangeInclusive<i64>| match argument(name) {
+            None => Ok(None),
+            Some(Value::Number(number)) if range.contains(number) => Ok(Some(*number)),
+            Some(Value::Number(number)) => Err(format!(
+                "`{name}` must be between {} and {}, but {number} given",
+                range.start(),
+                range.end()
+            )),
+            Some(value) => Err(format!(
+                "`{name}` must be an int, but {} given",
+                value.type_of()
+            )),
+        };
+        let one_of = |name: &str, options: &[&str], default: &str| match text(name)? {
+            None => Ok(default.to_string()),
+            Some(text) if options.contains(&text.as_str()) => Ok(text),
+            Some(text) => Err(format!(
+                "`{name}` must be one of {}, but {text:?} given",
+                options.join(", ")
+            )),
+        };
+
+        let title = text("title")?.ok_or_else(|| format!("{}() needs a title", kind.name()))?;
+        let max_counts = [
+            ("max_lines_count", DrawingKind::Line),
+            ("max_labels_count", DrawingKind::Label),
+            ("max_boxes_count", DrawingKind::Box),
+            ("max_polylines_count", DrawingKind::Polyline),
+        ]
+        .into_iter()
+        .filter_map(|(name, kind)| {
+            int(name, 1..=500)
+                .map(|count| count.map(|count| (kind, count as usize)))
+                .transpose()
+        })
+        .collect::<Result<_, _>>()?;
+        let strategy = match kind {
+            DeclarationKind::Strategy => {
+                let default = StrategyProperties::default();
+
+                Some(StrategyProperties {
+                    pyramiding: int("pyramiding", 0..=i64::MAX)?.unwrap_or(default.pyramiding),
+                    calc_on_order_fills: boolean("calc_on_order_fills", false)?,
+                    calc_on_every_tick: boolean("calc_on_every_tick", false)?,
+                    default_qty_type: one_of(
+                        "default_qty_type",
+                        &QUANTITY_TYPES,
+                        &default.default_qty_type,
+                    )?,
+                    default_qty_value: float("default_qty_value", default.default_qty_value)?,
+                    initial_capital: match float("initial_capital", default.initial_capital)? {
+                        capital if capital > 0.0 => capital,
+                        capital => {
+                            return Err(format!(
+                                "`initial_capital` must be positive, but {capital} given"
+                            ))
+                        }
+                    },
+                    currency: text("currency")?.unwrap_or(default.currency),
+                    slippage: int("slippage", 0..=i64::MAX)?.unwrap_or(default.slippage),
+                    commission_type: one_of(
+                        "commission_type",
+                        &COMMISSION_TYPES,
+                        &default.commission_type,
+                    )?,
+                    commission_value: float("commission_value", default.commission_value)?,
+                    process_orders_on_close: boolean("process_orders_on_close", false)?,
+                    close_entries_rule: one_of(
+                        "close_entries_rule",
+                        &["FIFO", "ANY"],
+                        &default.close_entries_rule,
+                    )?,
+                    margin_long: float("margin_long", default.margin_long)?,
+                    margin_short: float("margin_short", default.margin_short)?,
+                })
+            }
+            _ => None,
+        };
+
+        Ok(Self {
+            kind,
+            title,
+            shorttitle: text("shorttitle")?,
+            overlay: boolean("overlay", false)?,
+            format: one_of(
+                "format",
+                &["inherit", "price", "percent", "volume", "mintick"],
+                "inherit",
+            )?,
+            precision: int("precision", 0..=16)?,
+            scale: match text("scale")? {
+                Some(scale) if !["right", "left", "none"].contains(&scale.as_str()) => {
+                    return Err(format!(
+                        "`scale` must be a scale.* constant, but {scale:?} given"
+                    ))
+                }
+                scale => scale,
+            },
+            max_bars_back: int("max_bars_back", 0..=5000)?,
+            max_counts,
+            dynamic_requests: boolean("dynamic_requests", true)?,
+            strategy,
+        })
+    }
+
+    pub fn is_strategy(&self) -> bool {
+        self.strategy.is_some()
+    }
+}
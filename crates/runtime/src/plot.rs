@@ -0,0 +1,53 @@
+use parse::Span;
+
+use crate::value::{Color, Value};
+
+/// A value a `plot` call drew, in its color; na colors hide the point.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlotPoint {
+    pub value: Value,
+    pub color: Option<Color>,
+}
+
+/// A `plot` of the script, and the points of the calls from its place in the script.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlotOutput {
+    pub title: String,
+    pub linewidth: i64,
+    pub points: Vec<PlotPoint>,
+    pub(crate) call_site: Span,
+}
+
+/// The plots of a run, in the order the script first calls them.
+#[derive(Clone, Debug, Default)]
+pub struct Plots {
+    outputs: Vec<PlotOutput>,
+}
+
+impl Plots {
+    /// Adds `point` to the plot called from `call_site`, whose title and width are those of the
+    /// latest call.
+    pub fn record(&mut self, call_site: Span, title: String, linewidth: i64, point: PlotPoint) {
+        match self
+            .outputs
+            .iter_mut()
+            .find(|output| output.call_site == call_site)
+        {
+            Some(output) => {
+                output.title = title;
+                output.linewidth = linewidth;
+                output.points.push(point);
+            }
+            None => self.outputs.push(PlotOutput {
+                title,
+                linewidth,
+                points: vec![point],
+                call_site,
+            }),
+        }
+    }
+
+    pub fn outputs(&self) -> &[PlotOutput] {
+        &self.outputs
+    }
+}
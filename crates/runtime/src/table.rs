@@ -0,0 +1,563 @@
+use std::fmt::{Display, Formatter, Write};
+
+use owo_colors::{OwoColorize, Style};
+
+use crate::value::{Color, Value};
+
+/// The value scripts hold for a table; the table itself lives in the [`Tables`] of the runtime.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Table {
+    pub id: usize,
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "table#{}", self.id)
+    }
+}
+
+/// A cell of a table and its properties, in the order `table.cell` takes them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cell {
+    pub properties: Vec<(&'static str, Value)>,
+}
+
+impl Cell {
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.properties
+            .iter()
+            .find(|(property, _)| *property == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn set(&mut self, name: &str, value: Value) {
+        if let Some((_, property)) = self
+            .properties
+            .iter_mut()
+            .find(|(property, _)| *property == name)
+        {
+            *property = value;
+        }
+    }
+
+    fn text(&self) -> Vec<String> {
+        match self.get("text") {
+            Some(Value::String(text)) => text.lines().map(str::to_string).collect(),
+            Some(value) if !value.is_na() => vec![value.to_string()],
+            _ => Vec::new(),
+        }
+    }
+
+    fn string(&self, name: &str) -> &str {
+        match self.get(name) {
+            Some(Value::String(value)) => value,
+            _ => "",
+        }
+    }
+}
+
+/// Cells merged into the one at their start, both ends included.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Merge {
+    pub start_column: usize,
+    pub start_row: usize,
+    pub end_column: usize,
+    pub end_row: usize,
+}
+
+impl Merge {
+    fn contains(&self, column: usize, row: usize) -> bool {
+        (self.start_column..=self.end_column).contains(&column)
+            && (self.start_row..=self.end_row).contains(&row)
+    }
+
+    fn overlaps(&self, other: &Merge) -> bool {
+        self.start_column <= other.end_column
+            && other.start_column <= self.end_column
+            && self.start_row <= other.end_row
+            && other.start_row <= self.end_row
+    }
+}
+
+/// A table, its properties in the order `table.new` takes them, and its cells by row.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TableObject {
+    pub id: usize,
+    pub columns: usize,
+    pub rows: usize,
+    pub properties: Vec<(&'static str, Value)>,
+    cells: Vec<Option<Cell>>,
+    merges: Vec<Merge>,
+}
+
+/// What fills a cell of the grid when rendering.
+enum Slot<'a> {
+    /// A cell, or the start of merged cells, spanning `columns` by `rows`; `cell` is `None`
+    /// until it's set.
+    Cell {
+        cell: Option<&'a Cell>,
+        columns: usize,
+        rows: usize,
+    },
+    /// Covered by the merge starting at `column`, `row`.
+    Covered { column: usize, row: usize },
+}
+
+impl TableObject {
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.properties
+            .iter()
+            .find(|(property, _)| *property == name)
+            .map(|(_, value)| value)
+    }
+
+    pub fn set(&mut self, name: &str, value: Value) {
+        if let Some((_, property)) = self
+            .properties
+            .iter_mut()
+            .find(|(property, _)| *property == name)
+        {
+            *property = value;
+        }
+    }
+
+    /// The cell at `column`, `row`, if it was set.
+    pub fn cell(&self, column: usize, row: usize) -> Option<&Cell> {
+        self.cells.get(row * self.columns + column)?.as_ref()
+    }
+
+    /// The cell at `column`, `row`, which must be inside the table.
+    pub fn cell_mut(&mut self, column: usize, row: usize) -> &mut Option<Cell> {
+        &mut self.cells[row * self.columns + column]
+    }
+
+    pub fn merges(&self) -> &[Merge] {
+        &self.merges
+    }
+
+    /// Merges the cells of `merge`, unless some of them are merged already.
+    pub fn merge(&mut self, merge: Merge) -> Result<(), String> {
+        match self.merges.iter().find(|other| other.overlaps(&merge)) {
+            Some(other) => Err(format!(
+                "cells {}:{} to {}:{} are already merged",
+                other.start_column, other.start_row, other.end_column, other.end_row
+            )),
+            None => {
+                self.merges.push(merge);
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes the cells in `range`, and the merges starting in it.
+    pub fn clear(&mut self, range: Merge) {
+        for row in range.start_row..=range.end_row {
+            for column in range.start_column..=range.end_column {
+                *self.cell_mut(column, row) = None;
+            }
+        }
+
+        self.merges
+            .retain(|merge| !range.contains(merge.start_column, merge.start_row));
+    }
+
+    fn slot(&self, column: usize, row: usize) -> Slot<'_> {
+        let merge = self.merges.iter().find(|merge| merge.contains(column, row));
+
+        match merge {
+            Some(merge) if (merge.start_column, merge.start_row) != (column, row) => {
+                Slot::Covered {
+                    column: merge.start_column,
+                    row: merge.start_row,
+                }
+            }
+            _ => Slot::Cell {
+                cell: self.cell(column, row),
+                columns: merge.map_or(1, |merge| merge.end_column - merge.start_column + 1),
+                rows: merge.map_or(1, |merge| merge.end_row - merge.start_row + 1),
+            },
+        }
+    }
+
+    /// Whether `column` and the next one are merged in `row`.
+    fn joined(&self, column: usize, row: usize) -> bool {
+        self.merges
+            .iter()
+            .any(|merge| merge.contains(column, row) && merge.contains(column + 1, row))
+    }
+
+    /// Renders the table as lines of text framed with box-drawing characters, with the colors
+    /// of its cells as terminal escapes when `colored`.
+    pub fn render_text(&self, colored: bool) -> String {
+        let (widths, heights) = self.dimensions();
+        let mut output = String::new();
+
+        output.push_str(&self.border(&widths, 0, ('┌', '┬', '┐')));
+
+        for (row, height) in heights.iter().enumerate() {
+            for line in 0..*height {
+                output.push('│');
+
+                for column in 0..self.columns {
+                    if let Some(width) = self.span_width(&widths, column, row) {
+                        output.push_str(&self.segment(&heights, width, column, row, line, colored));
+                    }
+
+                    if column + 1 == self.columns || !self.joined(column, row) {
+                        output.push('│');
+                    }
+                }
+
+                output.push('\n');
+            }
+        }
+
+        output.push_str(&self.border(&widths, self.rows - 1, ('└', '┴', '┘')));
+        output
+    }
+
+    /// Renders the table as an HTML `<table>` fragment.
+    pub fn render_html(&self) -> String {
+        let mut style = vec!["border-collapse: collapse".to_string()];
+
+        if let Some(color) = color(self.get("bgcolor")) {
+            style.push(format!("background-color: {color}"));
+        }
+
+        if let (Some(color), Some(width)) = (
+            color(self.get("frame_color")),
+            number(self.get("frame_width")),
+        ) {
+            style.push(format!("border: {width}px solid {color}"));
+        }
+
+        let position = match self.get("position") {
+            Some(Value::String(position)) => position.as_str(),
+            _ => "",
+        };
+        let mut output = format!(
+            "<table class=\"pine-table\" data-position=\"{}\" style=\"{}\">\n",
+            escape(position),
+            style.join("; ")
+        );
+
+        for row in 0..self.rows {
+            output.push_str("  <tr>\n");
+
+            for column in 0..self.columns {
+                match self.slot(column, row) {
+                    Slot::Covered { .. } => {}
+                    Slot::Cell {
+                        cell,
+                        columns,
+                        rows,
+                    } => output.push_str(&self.html_cell(cell, columns, rows)),
+                }
+            }
+
+            output.push_str("  </tr>\n");
+        }
+
+        output.push_str("</table>\n");
+        output
+    }
+
+    fn border_style(&self) -> Vec<String> {
+        match (
+            color(self.get("border_color")),
+            number(self.get("border_width")),
+        ) {
+            (Some(color), Some(width)) => vec![format!("border: {width}px solid {color}")],
+            _ => Vec::new(),
+        }
+    }
+
+    fn html_cell(&self, cell: Option<&Cell>, columns: usize, rows: usize) -> String {
+        let mut attributes = String::new();
+        let mut style = self.border_style();
+
+        if columns > 1 {
+            let _ = write!(attributes, " colspan=\"{columns}\"");
+        }
+
+        if rows > 1 {
+            let _ = write!(attributes, " rowspan=\"{rows}\"");
+        }
+
+        let cell = match cell {
+            Some(cell) => cell,
+            None => return format!("    <td{attributes} style=\"{}\"></td>\n", style.join("; ")),
+        };
+
+        if !cell.string("tooltip").is_empty() {
+            let _ = write!(attributes, " title=\"{}\"", escape(cell.string("tooltip")));
+        }
+
+        if let Some(color) = color(cell.get("text_color")) {
+            style.push(format!("color: {color}"));
+        }
+
+        if let Some(color) = color(cell.get("bgcolor")) {
+            style.push(format!("background-color: {color}"));
+        }
+
+        for (property, name) in [("width", "width"), ("height", "height")] {
+            if let Some(percent) = number(cell.get(property)) {
+                style.push(format!("{name}: {percent}%"));
+            }
+        }
+
+        style.push(format!(
+            "text-align: {}",
+            cell.string("text_halign").trim_start_matches("align_")
+        ));
+        style.push(format!(
+            "vertical-align: {}",
+            match cell.string("text_valign") {
+                "align_top" => "top",
+                "align_bottom" => "bottom",
+                _ => "middle",
+            }
+        ));
+
+        if let Some(size) = font_size(cell.string("text_size")) {
+            style.push(format!("font-size: {size}"));
+        }
+
+        format!(
+            "    <td{attributes} style=\"{}\">{}</td>\n",
+            style.join("; "),
+            cell.text()
+                .iter()
+                .map(|line| escape(line))
+                .collect::<Vec<_>>()
+                .join("<br>")
+        )
+    }
+
+    /// The widths of the columns and the heights of the rows in characters, grown where merged
+    /// cells need more room than the cells they cover.
+    fn dimensions(&self) -> (Vec<usize>, Vec<usize>) {
+        let mut widths = vec![0; self.columns];
+        let mut heights = vec![1; self.rows];
+        let cells = (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| (column, row)))
+            .filter_map(|(column, row)| match self.slot(column, row) {
+                Slot::Cell {
+                    cell: Some(cell),
+                    columns,
+                    rows,
+                } => {
+                    let text = cell.text();
+                    let width = text.iter().map(|line| line.chars().count()).max();
+
+                    Some((column, row, columns, rows, width.unwrap_or(0), text.len()))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let (singles, spans): (Vec<_>, Vec<_>) = cells
+            .into_iter()
+            .partition(|(_, _, columns, rows, ..)| (*columns, *rows) == (1, 1));
+
+        for (column, row, _, _, width, height) in singles {
+            widths[column] = widths[column].max(width);
+            heights[row] = heights[row].max(height);
+        }
+
+        for (column, row, columns, rows, width, height) in spans {
+            let available =
+                widths[column..column + columns].iter().sum::<usize>() + 3 * (columns - 1);
+            let last = column + columns - 1;
+
+            widths[last] += width.saturating_sub(available);
+
+            let available = heights[row..row + rows].iter().sum::<usize>();
+            let last = row + rows - 1;
+
+            heights[last] += height.saturating_sub(available);
+        }
+
+        (widths, heights)
+    }
+
+    /// The width of the text of the slot at `column`, `row`, or `None` when it's covered by the
+    /// cell on its left.
+    fn span_width(&self, widths: &[usize], column: usize, row: usize) -> Option<usize> {
+        let columns = match self.slot(column, row) {
+            Slot::Cell { columns, .. } => columns,
+            Slot::Covered {
+                column: start,
+                row: start_row,
+            } if start == column => match self.slot(start, start_row) {
+                Slot::Cell { columns, .. } => columns,
+                Slot::Covered { .. } => 1,
+            },
+            Slot::Covered { .. } => return None,
+        };
+
+        Some(widths[column..column + columns].iter().sum::<usize>() + 3 * (columns - 1))
+    }
+
+    /// Line `line` of row `row` of the slot at `column`, padded to `width` and colored.
+    fn segment(
+        &self,
+        heights: &[usize],
+        width: usize,
+        column: usize,
+        row: usize,
+        line: usize,
+        colored: bool,
+    ) -> String {
+        let (cell, start_row, rows) = match self.slot(column, row) {
+            Slot::Cell { cell, rows, .. } => (cell, row, rows),
+            Slot::Covered {
+                column: start,
+                row: start_row,
+            } => match self.slot(start, start_row) {
+                Slot::Cell { cell, rows, .. } => (cell, start_row, rows),
+                Slot::Covered { .. } => (None, row, 1),
+            },
+        };
+        let text = cell.map(Cell::text).unwrap_or_default();
+        let total = heights[start_row..start_row + rows].iter().sum::<usize>();
+        let top = match cell.map_or("", |cell| cell.string("text_valign")) {
+            "align_top" => 0,
+            "align_bottom" => total - text.len().min(total),
+            _ => (total - text.len().min(total)) / 2,
+        };
+        let index = heights[start_row..row].iter().sum::<usize>() + line;
+        let content = index
+            .checked_sub(top)
+            .and_then(|index| text.get(index))
+            .map_or("", String::as_str);
+        let padding = width - content.chars().count().min(width);
+        let (left, right) = match cell.map_or("", |cell| cell.string("text_halign")) {
+            "align_left" => (0, padding),
+            "align_right" => (padding, 0),
+            _ => (padding / 2, padding - padding / 2),
+        };
+        let segment = format!(" {}{content}{} ", " ".repeat(left), " ".repeat(right));
+
+        if !colored {
+            return segment;
+        }
+
+        let mut style = Style::new();
+
+        if let Some(color) = cell.and_then(|cell| color(cell.get("text_color"))) {
+            style = style.truecolor(color.red, color.green, color.blue);
+        }
+
+        if let Some(color) = cell
+            .and_then(|cell| color(cell.get("bgcolor")))
+            .or_else(|| color(self.get("bgcolor")))
+        {
+            style = style.on_truecolor(color.red, color.green, color.blue);
+        }
+
+        segment.style(style).to_string()
+    }
+
+    /// A horizontal border along row `row`, with `corners` at its ends and where columns meet.
+    fn border(&self, widths: &[usize], row: usize, corners: (char, char, char)) -> String {
+        let (start, junction, end) = corners;
+        let mut border = String::from(start);
+
+        for (column, width) in widths.iter().enumerate() {
+            border.push_str(&"─".repeat(width + 2));
+
+            if column + 1 < self.columns {
+                border.push(match self.joined(column, row) {
+                    true => '─',
+                    false => junction,
+                });
+            }
+        }
+
+        border.push(end);
+        border.push('\n');
+        border
+    }
+}
+
+/// A color property; na and fully transparent colors are none.
+fn color(value: Option<&Value>) -> Option<Color> {
+    match value {
+        Some(Value::Color(color)) if color.alpha > 0 => Some(*color),
+        _ => None,
+    }
+}
+
+/// A positive number property.
+fn number(value: Option<&Value>) -> Option<f64> {
+    value
+        .and_then(Value::as_float)
+        .filter(|number| *number > 0.0)
+}
+
+fn font_size(size: &str) -> Option<&'static str> {
+    match size {
+        "tiny" => Some("8px"),
+        "small" => Some("10px"),
+        "normal" => Some("12px"),
+        "large" => Some("16px"),
+        "huge" => Some("24px"),
+        _ => None,
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The tables of a run, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct Tables {
+    objects: Vec<TableObject>,
+    next_id: usize,
+}
+
+impl Tables {
+    pub fn create(
+        &mut self,
+        columns: usize,
+        rows: usize,
+        properties: Vec<(&'static str, Value)>,
+    ) -> Table {
+        let id = self.next_id;
+
+        self.next_id += 1;
+        self.objects.push(TableObject {
+            id,
+            columns,
+            rows,
+            properties,
+            cells: vec![None; columns * rows],
+            merges: Vec::new(),
+        });
+
+        Table { id }
+    }
+
+    /// The table behind `table`, or `None` once it's deleted.
+    pub fn get(&self, table: &Table) -> Option<&TableObject> {
+        self.objects.iter().find(|object| object.id == table.id)
+    }
+
+    pub fn get_mut(&mut self, table: &Table) -> Option<&mut TableObject> {
+        self.objects.iter_mut().find(|object| object.id == table.id)
+    }
+
+    pub fn delete(&mut self, table: &Table) {
+        self.objects.retain(|object| object.id != table.id);
+    }
+
+    /// Every live table, oldest first.
+    pub fn objects(&self) -> &[TableObject] {
+        &self.objects
+    }
+}
@@ -5,7 +5,8 @@ use crate::value::NativeFunction;
 use crate::value::Parameter;
 use crate::value::Visibility;
 use crate::FArguments;
-use crate::Type;
+use ast::datatype::DataType;
+use parse::Overload;
 use super::Value;
 
 #[derive(Default)]
@@ -32,7 +33,8 @@ pub struct FunctionBuilder {
     visibility: Visibility,
     name: String,
     parameters: Vec<Parameter>,
-    ty: Type,
+    ty: Option<DataType>,
+    overloads: Vec<Overload>,
 }
 
 impl FunctionBuilder {
@@ -44,37 +46,77 @@ impl FunctionBuilder {
             name: name.into(),
             parameters: Default::default(),
             ty: Default::default(),
+            overloads: Default::default(),
         }
     }
 
-    pub fn param<N: AsRef<str>, T: Into<Type>>(mut self, name: N, ty: T) -> Self {
+    pub fn param<N: AsRef<str>, T: Into<DataType>>(mut self, name: N, ty: T) -> Self {
         let name: &str = name.as_ref();
 
         self.parameters.push(Parameter {
             name: name.into(),
             nullable: false,
-            //ty: ty.into(),
+            ty: Some(ty.into()),
             default: None,
+            variadic: false,
         });
 
         self
     }
 
-    pub fn param_default<N: AsRef<str>, T: Into<Type>, V: Into<Value>>(mut self, name: N, ty: T, default: V) -> Self {
+    pub fn param_default<N: AsRef<str>, T: Into<DataType>, V: Into<Value>>(mut self, name: N, ty: T, default: V) -> Self {
         let name: &str = name.as_ref();
 
         self.parameters.push(Parameter {
             name: name.into(),
             nullable: false,
-            //ty: ty.into(),
+            ty: Some(ty.into()),
             default: Some(Box::new(default.into())),
+            variadic: false,
         });
 
         self
     }
 
-    pub fn returns<T: Into<Type>>(mut self, ty: T) -> Self {
-        //self.ty = ty.into();
+    /// Last parameter, receiving every remaining argument as an array.
+    pub fn rest<N: AsRef<str>, T: Into<DataType>>(mut self, name: N, ty: T) -> Self {
+        let name: &str = name.as_ref();
+
+        self.parameters.push(Parameter {
+            name: name.into(),
+            nullable: false,
+            ty: Some(ty.into()),
+            default: None,
+            variadic: true,
+        });
+
+        self
+    }
+
+    /// Parameters of a script function, whose defaults are already evaluated.
+    pub fn params(mut self, parameters: Vec<Parameter>) -> Self {
+        self.parameters.extend(parameters);
+
+        self
+    }
+
+    pub fn returns<T: Into<DataType>>(mut self, ty: T) -> Self {
+        self.ty = Some(ty.into());
+
+        self
+    }
+
+    /// Result type of calls whose arguments have the types `parameters`, for a function whose
+    /// result type depends on them, such as `math.abs`.
+    pub fn overload<P: Into<Vec<DataType>>, T: Into<DataType>>(
+        mut self,
+        parameters: P,
+        ty: T,
+    ) -> Self {
+        self.overloads.push(Overload {
+            parameters: parameters.into(),
+            returns: ty.into(),
+        });
 
         self
     }
@@ -82,7 +124,8 @@ impl FunctionBuilder {
     pub fn build<F: Fn(&mut FArguments) + 'static>(self, body: F) -> Value {
         Value::NativeFunction(NativeFunction {
             visibility: self.visibility,
-            //ty: self.ty,
+            ty: self.ty,
+            overloads: self.overloads,
             name: self.name,
             parameters: self.parameters,
             body: Arc::new(body),
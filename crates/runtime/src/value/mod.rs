@@ -1,8 +1,8 @@
 pub mod builders;
 pub mod native;
 
-use super::{environment::Scope, FArguments, Runtime};
-use owo_colors::{colors, Color};
+use super::{drawing::{Drawing, DrawingKind}, environment::Scope, table::Table, FArguments};
+use owo_colors::{colors, Color as _};
 use std::{
     collections::HashMap,
     fmt::{Display, Formatter},
@@ -116,6 +116,10 @@ pub enum ErrorCode {
     Reference = 0x1350,
     /// Required value has not been declared or user trying to access uninitialized variable
     Declaration = 0x1450,
+    /// `break` or `continue` used outside of a loop body
+    ControlFlow = 0x1550,
+    /// Index outside the bounds of an array
+    OutOfBounds = 0x1650,
     /// Caused for features that currently not implemented
     Implementing = 0x1950,
 }
@@ -222,8 +226,21 @@ impl Default for Visibility {
 pub struct Parameter {
     pub name: String,
     pub nullable: bool,
-   // pub ty: Type,
+    /// The declared type, if any.
+    pub ty: Option<DataType>,
     pub default: Option<Box<Value>>,
+    /// Collects the remaining arguments into an array; only valid as the last parameter.
+    pub variadic: bool,
+}
+
+impl Parameter {
+    /// `value` as an argument for the parameter: float parameters take ints as floats.
+    pub fn take(&self, value: Value) -> Value {
+        match &self.ty {
+            Some(ty) => value.promote(ty),
+            None => value,
+        }
+    }
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -255,10 +272,13 @@ pub struct Function {
 }
 
 use derivative::Derivative;
+use std::cell::{Ref, RefCell, RefMut};
+use std::rc::Rc;
 use std::sync::Arc;
 use ast::Body;
 use ast::types::Type;
-use parse::Span;
+use ast::datatype::DataType;
+use parse::{Overload, Span};
 use parse::util::ArraySize;
 
 #[derive(Derivative, Clone)]
@@ -267,7 +287,9 @@ pub struct NativeFunction {
     pub visibility: Visibility,
     pub name: String,
     pub parameters: Vec<Parameter>,
-   // pub ty: Type,
+    pub ty: Option<DataType>,
+    /// Result types of calls with particular argument types, tried ahead of `ty`.
+    pub overloads: Vec<Overload>,
 
     #[derivative(Debug = "ignore")]
     pub body: Arc<dyn Fn(&mut FArguments)>,
@@ -278,7 +300,8 @@ impl PartialEq for NativeFunction {
         self.visibility == other.visibility
             && self.name == other.name
             && self.parameters == other.parameters
-            //&& self.ty == other.ty
+            && self.ty == other.ty
+            && self.overloads == other.overloads
     }
 }
 
@@ -286,14 +309,98 @@ impl PartialEq for NativeFunction {
 pub struct Property {
     pub name: String,
     pub nullable: bool,
-    pub ty: Type,
+    pub ty: Option<DataType>,
     pub init: Option<Box<Value>>,
 }
 
+impl Property {
+    /// `value` as the field holds it: an int given to a float field becomes a float. `None`
+    /// when the value is not of the field's type; na fits any field.
+    pub fn coerce(&self, value: Value) -> Option<Value> {
+        let Some(ty) = &self.ty else {
+            return Some(value);
+        };
+        let value = value.promote(ty);
+
+        let fits = match (ty, &value) {
+            (_, Value::None | Value::Null) => true,
+            (DataType::Int, Value::Number(_))
+            | (DataType::Float, Value::Float(_))
+            | (DataType::Bool, Value::Boolean(_))
+            | (DataType::Color, Value::Color(_))
+            | (DataType::String, Value::String(_))
+            | (DataType::Table, Value::Table(_))
+            | (DataType::Array(_), Value::Array(..))
+            | (DataType::Matrix(_), Value::Matrix(_))
+            | (DataType::Map(..), Value::Map(_)) => true,
+            (DataType::Line, Value::Drawing(drawing)) => drawing.kind == DrawingKind::Line,
+            (DataType::Label, Value::Drawing(drawing)) => drawing.kind == DrawingKind::Label,
+            (DataType::Box, Value::Drawing(drawing)) => drawing.kind == DrawingKind::Box,
+            (DataType::LineFill, Value::Drawing(drawing)) => drawing.kind == DrawingKind::Linefill,
+            (DataType::Custom(name), Value::ClassInstance(instance)) => &instance.name == name,
+            (DataType::Custom(name), Value::EnumMember(member)) => &member.enumeration == name,
+            (DataType::UDF, _) => true,
+            _ => false,
+        };
+
+        fits.then_some(value)
+    }
+}
+
+/// A value of an enum, such as `Signal.buy`; displayed as its title.
 #[derive(PartialEq, Clone, Debug)]
 pub struct EnumMember {
+    pub enumeration: String,
     pub name: String,
-    pub init: Box<Value>,
+    pub title: String,
+}
+
+/// A color with its opacity as an alpha byte, `0xFF` being opaque. Scripts see the opacity as a
+/// transparency from 0 (opaque) to 100.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl Color {
+    pub fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
+        Self {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// Transparency from 0 to 100, rounded to a whole number as the alpha byte can't hold more.
+    pub fn transparency(&self) -> f64 {
+        ((255 - self.alpha) as f64 * 100.0 / 255.0).round()
+    }
+
+    /// The same color with a transparency from 0 to 100, clamped to that range.
+    pub fn with_transparency(self, transparency: f64) -> Self {
+        let alpha = (100.0 - transparency.clamp(0.0, 100.0)) * 255.0 / 100.0;
+
+        Self {
+            alpha: alpha.round() as u8,
+            ..self
+        }
+    }
+}
+
+/// `#RRGGBB`, or `#RRGGBBAA` when the color isn't opaque.
+impl Display for Color {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.red, self.green, self.blue)?;
+
+        match self.alpha {
+            0xff => Ok(()),
+            alpha => write!(f, "{alpha:02X}"),
+        }
+    }
 }
 
 #[derive(PartialEq, Clone, Debug)]
@@ -305,13 +412,23 @@ pub struct Field {
 #[derive(PartialEq, Clone, Debug)]
 pub enum Value {
     Reference(Vec<String>, Scope),
-    Array(Vec<Value>, ArraySize),
+    Array(Elements, ArraySize),
+    Matrix(Matrix),
+    Map(Map),
+    /// Several values returned at once, unpacked with `[a, b] = ...`.
+    Tuple(Vec<Value>),
     Object(HashMap<Value, Value>),
     Number(i64),
     Float(f64),
     Boolean(bool),
     String(String),
+    Color(Color),
+    /// A `line`, `label`, `box`, `linefill` or `polyline`.
+    Drawing(Drawing),
+    Table(Table),
     ReturnValue(Box<Value>),
+    Break,
+    Continue,
     ArrowFunction(ArrowFunction),
     NativeFunction(NativeFunction),
     Function(Function),
@@ -320,6 +437,7 @@ pub enum Value {
         ty: Type,
     },
     Enum(Enum),
+    EnumMember(EnumMember),
     Interface {
         name: String,
         extends: Vec<String>,
@@ -365,31 +483,338 @@ impl Enum {
     }
 }
 
-#[derive(PartialEq, Clone, Debug)]
+/// Instance of a user-defined type. Clones share the same fields, so an object assigned to
+/// several variables is mutated through all of them; `copy()` makes an independent one.
+#[derive(Clone, Debug)]
 pub struct ClassInstance {
     pub name: String,
-    pub fields: Vec<Field>,
+    pub fields: Rc<RefCell<Vec<Field>>>,
+}
+
+impl PartialEq for ClassInstance {
+    fn eq(&self, other: &ClassInstance) -> bool {
+        Rc::ptr_eq(&self.fields, &other.fields)
+    }
 }
 
 impl ClassInstance {
-    pub fn get_field_moved<N: AsRef<str>>(self, name: N) -> Option<Field> {
+    pub fn new<N: Into<String>>(name: N, fields: Vec<Field>) -> Self {
+        Self {
+            name: name.into(),
+            fields: Rc::new(RefCell::new(fields)),
+        }
+    }
+
+    pub fn get_field<N: AsRef<str>>(&self, name: N) -> Option<Value> {
         self.fields
-            .into_iter()
+            .borrow()
+            .iter()
             .find(|field| field.prop.name == name.as_ref())
+            .map(|field| field.value.clone())
     }
 
-    pub fn get_field<N: AsRef<str>>(&self, name: N) -> Option<&Field> {
+    pub fn property<N: AsRef<str>>(&self, name: N) -> Option<Property> {
         self.fields
+            .borrow()
             .iter()
             .find(|field| field.prop.name == name.as_ref())
+            .map(|field| field.prop.clone())
     }
 
-    pub fn get_field_mut<N: AsRef<str>>(&mut self, name: N) -> Option<&mut Field> {
-        self.fields
+    /// Returns `false` when the type has no such field.
+    pub fn set_field<N: AsRef<str>>(&self, name: N, value: Value) -> bool {
+        match self
+            .fields
+            .borrow_mut()
             .iter_mut()
             .find(|field| field.prop.name == name.as_ref())
+        {
+            Some(field) => {
+                field.value = value;
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Shallow copy: fields holding objects still point to the same objects.
+    pub fn copy(&self) -> Self {
+        Self::new(self.name.clone(), self.fields.borrow().clone())
+    }
+}
+
+/// Storage of an array. Clones share the same elements, so `array.push` on one variable is seen
+/// through every variable holding the array; `array.copy` makes an independent one.
+#[derive(Clone, Debug, Default)]
+pub struct Elements {
+    values: Rc<RefCell<Vec<Value>>>,
+    /// Type declared for the elements, such as `float` in `array.new<float>()`.
+    ty: Option<DataType>,
+}
+
+/// Arrays are equal when they hold equal elements.
+impl PartialEq for Elements {
+    fn eq(&self, other: &Elements) -> bool {
+        Rc::ptr_eq(&self.values, &other.values) || *self.read() == *other.read()
+    }
+}
+
+impl Elements {
+    pub fn new(elements: Vec<Value>) -> Self {
+        Self {
+            values: Rc::new(RefCell::new(elements)),
+            ty: None,
+        }
+    }
+
+    /// Elements of an array declared as `array<ty>`, promoted to `ty`.
+    pub fn typed(elements: Vec<Value>, ty: DataType) -> Self {
+        let elements = elements.into_iter().map(|element| element.promote(&ty)).collect();
+
+        Self {
+            values: Rc::new(RefCell::new(elements)),
+            ty: Some(ty),
+        }
+    }
+
+    /// `value` as the array holds it: an int put in an array of floats becomes a float.
+    pub fn promote(&self, value: Value) -> Value {
+        match &self.ty {
+            Some(ty) => value.promote(ty),
+            None => value,
+        }
+    }
+
+    /// Independent elements of the same type.
+    pub fn copy(&self) -> Self {
+        Self {
+            values: Rc::new(RefCell::new(self.to_vec())),
+            ty: self.ty.clone(),
+        }
+    }
+
+    pub fn read(&self) -> Ref<'_, Vec<Value>> {
+        self.values.borrow()
+    }
+
+    pub fn write(&self) -> RefMut<'_, Vec<Value>> {
+        self.values.borrow_mut()
+    }
+
+    pub fn to_vec(&self) -> Vec<Value> {
+        self.read().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.read().is_empty()
+    }
+}
+
+impl From<Vec<Value>> for Elements {
+    fn from(elements: Vec<Value>) -> Self {
+        Self::new(elements)
+    }
+}
+
+/// A dense matrix, stored row by row.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Grid {
+    pub rows: usize,
+    pub columns: usize,
+    pub elements: Vec<Value>,
+}
+
+impl Grid {
+    pub fn new(rows: usize, columns: usize, initial: Value) -> Self {
+        Self {
+            rows,
+            columns,
+            elements: vec![initial; rows * columns],
+        }
+    }
+
+    /// A grid from its rows, which must all have the same length.
+    pub fn from_rows(rows: Vec<Vec<Value>>) -> Self {
+        let columns = rows.first().map_or(0, Vec::len);
+
+        Self {
+            rows: rows.len(),
+            columns,
+            elements: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> &Value {
+        &self.elements[row * self.columns + column]
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, value: Value) {
+        self.elements[row * self.columns + column] = value;
+    }
+
+    pub fn row(&self, row: usize) -> Vec<Value> {
+        self.elements[row * self.columns..(row + 1) * self.columns].to_vec()
+    }
+
+    pub fn column(&self, column: usize) -> Vec<Value> {
+        (0..self.rows).map(|row| self.get(row, column).clone()).collect()
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<Value>> {
+        (0..self.rows).map(|row| self.row(row)).collect()
+    }
+}
+
+/// Storage of a matrix, shared between clones like [`Elements`]; `matrix.copy` makes an
+/// independent one.
+#[derive(Clone, Debug, Default)]
+pub struct Matrix(Rc<RefCell<Grid>>);
+
+/// Matrices are equal when they have the same shape and equal elements.
+impl PartialEq for Matrix {
+    fn eq(&self, other: &Matrix) -> bool {
+        Rc::ptr_eq(&self.0, &other.0) || *self.read() == *other.read()
+    }
+}
+
+impl Matrix {
+    pub fn new(grid: Grid) -> Self {
+        Self(Rc::new(RefCell::new(grid)))
+    }
+
+    pub fn read(&self) -> Ref<'_, Grid> {
+        self.0.borrow()
+    }
+
+    pub fn write(&self) -> RefMut<'_, Grid> {
+        self.0.borrow_mut()
+    }
+
+    pub fn to_grid(&self) -> Grid {
+        self.read().clone()
+    }
+}
+
+impl From<Grid> for Matrix {
+    fn from(grid: Grid) -> Self {
+        Self::new(grid)
     }
 }
+
+/// Entries of a map, iterated in insertion order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Entries {
+    keys: Vec<Value>,
+    values: HashMap<Value, Value>,
+    /// Key and value types declared by `map.new<key, value>()`.
+    types: Option<(DataType, DataType)>,
+}
+
+impl Entries {
+    /// No entries, for a map declared as `map<key, value>`.
+    pub fn typed(key: DataType, value: DataType) -> Self {
+        Self {
+            types: Some((key, value)),
+            ..Self::default()
+        }
+    }
+
+    pub fn get(&self, key: &Value) -> Option<&Value> {
+        self.values.get(key)
+    }
+
+    pub fn contains_key(&self, key: &Value) -> bool {
+        self.values.contains_key(key)
+    }
+
+    /// Sets the value of `key`, which keeps its position when it is already present, and
+    /// returns the previous value. An int key or value of a float one is stored as a float.
+    pub fn insert(&mut self, key: Value, value: Value) -> Option<Value> {
+        let (key, value) = match &self.types {
+            Some((key_type, value_type)) => (key.promote(key_type), value.promote(value_type)),
+            None => (key, value),
+        };
+
+        if !self.values.contains_key(&key) {
+            self.keys.push(key.clone());
+        }
+
+        self.values.insert(key, value)
+    }
+
+    pub fn remove(&mut self, key: &Value) -> Option<Value> {
+        let value = self.values.remove(key)?;
+
+        self.keys.retain(|other| other != key);
+
+        Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        self.keys.clear();
+        self.values.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn keys(&self) -> &[Value] {
+        &self.keys
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Value, &Value)> {
+        self.keys
+            .iter()
+            .filter_map(|key| Some((key, self.values.get(key)?)))
+    }
+}
+
+/// Storage of a map, shared between clones like [`Elements`]; `map.copy` makes an independent
+/// one.
+#[derive(Clone, Debug, Default)]
+pub struct Map(Rc<RefCell<Entries>>);
+
+/// Maps are equal when they hold equal entries in the same order.
+impl PartialEq for Map {
+    fn eq(&self, other: &Map) -> bool {
+        Rc::ptr_eq(&self.0, &other.0) || *self.read() == *other.read()
+    }
+}
+
+impl Map {
+    pub fn new(entries: Entries) -> Self {
+        Self(Rc::new(RefCell::new(entries)))
+    }
+
+    pub fn read(&self) -> Ref<'_, Entries> {
+        self.0.borrow()
+    }
+
+    pub fn write(&self) -> RefMut<'_, Entries> {
+        self.0.borrow_mut()
+    }
+
+    pub fn to_entries(&self) -> Entries {
+        self.read().clone()
+    }
+}
+
+impl From<Entries> for Map {
+    fn from(entries: Entries) -> Self {
+        Self::new(entries)
+    }
+}
+
 //
 // impl Function {
 //     pub fn call(
@@ -513,7 +938,11 @@ impl<K: Into<Value>, V: Into<Value>> From<HashMap<K, V>> for Value {
 impl<T: Into<Value>> From<Vec<T>> for Value {
     fn from(value: Vec<T>) -> Self {
         Value::Array(
-            value.into_iter().map(|element| element.into()).collect(),
+            value
+                .into_iter()
+                .map(|element| element.into())
+                .collect::<Vec<_>>()
+                .into(),
             ArraySize::Dynamic,
         )
     }
@@ -552,6 +981,45 @@ impl Display for Value {
             Value::Array(elements, _) => {
                 let mut list = &mut f.debug_list();
 
+                for element in elements.read().iter() {
+                    list = match alternate {
+                        true => list.entry(&format_args!("{element:#}")),
+                        false => list.entry(&format_args!("{element}")),
+                    }
+                }
+
+                list.finish()
+            }
+            Value::Map(map) => {
+                let mut entries = &mut f.debug_map();
+
+                for (key, value) in map.read().iter() {
+                    entries = match alternate {
+                        true => entries.entry(&format_args!("{key:#}"), &format_args!("{value:#}")),
+                        false => entries.entry(&format_args!("{key}"), &format_args!("{value}")),
+                    }
+                }
+
+                entries.finish()
+            }
+            Value::Matrix(matrix) => {
+                let grid = matrix.read();
+                let mut list = &mut f.debug_list();
+
+                for row in grid.to_rows() {
+                    let row = Value::Tuple(row);
+
+                    list = match alternate {
+                        true => list.entry(&format_args!("{row:#}")),
+                        false => list.entry(&format_args!("{row}")),
+                    }
+                }
+
+                list.finish()
+            }
+            Value::Tuple(elements) => {
+                let mut list = &mut f.debug_list();
+
                 for element in elements {
                     list = match alternate {
                         true => list.entry(&format_args!("{element:#}")),
@@ -582,13 +1050,17 @@ impl Display for Value {
             Value::Float(float) => float.fmt(f),
             Value::Boolean(boolean) => boolean.fmt(f),
             Value::String(string) => write!(f, "{string:?}"),
+            Value::Color(color) => color.fmt(f),
+            Value::Drawing(drawing) => drawing.fmt(f),
+            Value::Table(table) => table.fmt(f),
             Value::ReturnValue(value) => value.fmt(f),
+            Value::Break => write!(f, "break"),
+            Value::Continue => write!(f, "continue"),
             Value::Function(function) => write!(
                 f,
-                "[Function {}({}) => {}]",
+                "[Function {}({})]",
                 function.name,
-                function.parameters.len(),
-                function.ty
+                function.parameters.len()
             ),
             Value::Interface {
                 name,
@@ -637,7 +1109,7 @@ impl Display for Value {
                 structure.finish()
             }
             Value::Null => write!(f, "null"),
-            Value::None => write!(f, ""),
+            Value::None => write!(f, "na"),
             Value::Error(span, code, message) => write!(
                 f,
                 "[{code:?}Exception]:{}:{}: {message}",
@@ -650,11 +1122,12 @@ impl Display for Value {
                 let mut structure = &mut f.debug_struct(name);
 
                 for member in members {
-                    structure = structure.field(&member.name, &member.init);
+                    structure = structure.field(&member.name, &member.title);
                 }
 
                 structure.finish()
             }
+            Value::EnumMember(member) => write!(f, "{}", member.title),
             Value::TypeAlias { name, ty } => f.debug_tuple(name).field(ty).finish(),
             Value::NativeFunction(func) => write!(
                 f,
@@ -662,7 +1135,18 @@ impl Display for Value {
                 func.name,
                 func.parameters.len()
             ),
-            Value::ClassInstance(_) => todo!(),
+            Value::ClassInstance(instance) => {
+                let mut structure = &mut f.debug_struct(&instance.name);
+
+                for field in instance.fields.borrow().iter() {
+                    structure = match alternate {
+                        true => structure.field(&field.prop.name, &format_args!("{:#}", field.value)),
+                        false => structure.field(&field.prop.name, &format_args!("{}", field.value)),
+                    }
+                }
+
+                structure.finish()
+            }
             Value::Reference(reference, _) => write!(f, "[Reference({})]", reference.join(".")),
         }
     }
@@ -697,10 +1181,52 @@ impl Value {
         matches!(self, Value::ReturnValue(_))
     }
 
+    pub fn is_loop_control(&self) -> bool {
+        matches!(self, Value::Break | Value::Continue)
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, Value::Error(..))
+    }
+
+    /// A float result; NaN and infinities are na.
+    pub fn finite(number: f64) -> Value {
+        match number.is_finite() {
+            true => Value::Float(number),
+            false => Value::None,
+        }
+    }
+
+    /// `self` as a value of type `ty` holds it: an int becomes a float where a float is expected.
+    pub fn promote(self, ty: &DataType) -> Value {
+        match (ty, self) {
+            (DataType::Float, Value::Number(number)) => Value::Float(number as f64),
+            (_, value) => value,
+        }
+    }
+
+    /// Numeric value of an int or float, for operations that promote ints to floats.
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Value::Number(number) => Some(*number as f64),
+            Value::Float(float) => Some(*float),
+            _ => None,
+        }
+    }
+
     pub fn is_none(&self) -> bool {
         matches!(self, Value::None)
     }
 
+    /// `na` of any type: `na` itself, `null`, or a float that is NaN.
+    pub fn is_na(&self) -> bool {
+        match self {
+            Value::None | Value::Null => true,
+            Value::Float(float) => float.is_nan(),
+            _ => false,
+        }
+    }
+
     // pub fn is_type_of(&self, ty: &Type) -> bool {
     //     match ty {
     //         Type::UnionOrIntersectionOrPrimaryType(ty) => match ty {
@@ -798,75 +1324,39 @@ impl Value {
     //     }
     // }
 
-    pub fn value_type_of(&self) -> Type {
+    pub fn value_type_of(&self) -> Option<DataType> {
         match self {
-            Value::Array(elements, size) => {
-                let elements = elements
-                    .iter()
-                    .map(|element| element.value_type_of())
-                    .collect::<Vec<_>>();
-
-                let ty = if elements.iter().all(|ty| ty == &elements[0]) {
-                    elements
-                        .get(0)
-                        .cloned()
-                        .unwrap_or(PredefinedType::Any.into())
-                } else {
-                    panic!("all elements in array must be one type");
-                };
-
-                if let Type::UnionOrIntersectionOrPrimaryType(
-                    UnionOrIntersectionOrPrimaryType::IntersectionOrPrimaryType(
-                        IntersectionOrPrimaryType::PrimaryType(ty),
-                    ),
-                ) = ty
-                {
-                    PrimaryType::ArrayType(Box::new(ty), *size).into()
-                } else {
-                    panic!("can't infer type");
-                }
-            }
-            Value::Object(_) => todo!(),
-            Value::Number(_) => PredefinedType::Number.into(),
-            Value::Float(_) => PredefinedType::Float.into(),
-            Value::Boolean(_) => PredefinedType::Boolean.into(),
-            Value::String(_) => PredefinedType::String.into(),
+            Value::Array(elements, _) => elements
+                .read()
+                .first()
+                .and_then(|element| element.value_type_of())
+                .map(|ty| DataType::Array(Box::new(ty))),
+            Value::Number(_) => Some(DataType::Int),
+            Value::Float(_) => Some(DataType::Float),
+            Value::Boolean(_) => Some(DataType::Bool),
+            Value::String(_) => Some(DataType::String),
             Value::ReturnValue(value) => value.value_type_of(),
-            Value::ArrowFunction(func) => PredefinedType::Any.into(),
-            Value::Function(_) => todo!(),
-            Value::Interface {
-                name,
-                extends,
-                signatures,
-            } => todo!(),
-            Value::Class {
-                name,
-                extends,
-                implements,
-                constructors,
-                fields,
-                methods,
-            } => todo!(),
-            Value::Null => PredefinedType::Null.into(),
-            Value::None => PredefinedType::Void.into(),
-            Value::Error(span, code, message) => PredefinedType::Void.into(),
-            Value::Enum(_) => todo!(),
-            Value::TypeAlias { name, ty } => todo!(),
-            Value::NativeFunction(_) => todo!(),
-            Value::ClassInstance(_) => PrimaryType::ThisType.into(),
-            Value::Reference(..) => todo!(),
+            _ => None,
         }
     }
 
     pub fn type_of(&self) -> &str {
         match self {
             Value::Array(..) => "array",
+            Value::Matrix(_) => "matrix",
+            Value::Map(_) => "map",
+            Value::Tuple(_) => "tuple",
             Value::Object(_) => "object",
             Value::Number(_) => "number",
             Value::Float(_) => "float",
             Value::Boolean(_) => "boolean",
             Value::String(_) => "string",
+            Value::Color(_) => "color",
+            Value::Drawing(drawing) => drawing.kind.name(),
+            Value::Table(_) => "table",
             Value::ReturnValue(value) => value.type_of(),
+            Value::Break => "break",
+            Value::Continue => "continue",
             Value::Function(_) => "Function",
             Value::Interface { name, .. } => name,
             Value::Class { name, .. } => name,
@@ -875,9 +1365,10 @@ impl Value {
             Value::Error { .. } => "Exception",
             Value::ArrowFunction(_) => "LinearFunction",
             Value::Enum(Enum { name, .. }) => name,
+            Value::EnumMember(member) => &member.enumeration,
             Value::TypeAlias { name, .. } => name,
             Value::NativeFunction(_) => todo!(),
-            Value::ClassInstance(_) => todo!(),
+            Value::ClassInstance(instance) => &instance.name,
             Value::Reference(..) => todo!(),
         }
     }
@@ -889,20 +1380,16 @@ impl Hash for Value {
     fn hash<H: Hasher>(&self, state: &mut H) {
         match self {
             Value::Number(number) => number.hash(state),
+            // `0.0 == -0.0`, so both must hash alike.
+            Value::Float(float) if *float == 0.0 => 0.0_f64.to_bits().hash(state),
+            Value::Float(float) => float.to_bits().hash(state),
             Value::Boolean(boolean) => boolean.hash(state),
             Value::String(string) => string.hash(state),
+            Value::Color(color) => color.hash(state),
+            Value::Drawing(drawing) => drawing.hash(state),
+            Value::Table(table) => table.hash(state),
+            Value::EnumMember(member) => member.name.hash(state),
             _ => "".hash(state),
         }
     }
 }
-
-impl From<Literal> for Value {
-    fn from(literal: Literal) -> Self {
-        match literal {
-            Literal::String(string) => Value::String(string.value),
-            Literal::Number(number) => Value::Number(number.value),
-            Literal::Float(float) => Value::Float(float.value),
-            Literal::Boolean(boolean) => Value::Boolean(boolean.value),
-        }
-    }
-}
@@ -1,4 +1,4 @@
-use super::Value;
+use super::{builders::ObjectBuilder, Value};
 
 pub trait NativeObject {
     fn build_object(&'static self) -> Value;
@@ -29,5 +29,15 @@ impl Module {
 
         self.exports.push((name.into(), value))
     }
+
+    /// The exports as a namespace object, so scripts can call `module.name(...)`.
+    pub fn to_object(&self) -> Value {
+        self.exports
+            .iter()
+            .fold(ObjectBuilder::default(), |object, (name, value)| {
+                object.prop(name, value.clone())
+            })
+            .build()
+    }
 }
 
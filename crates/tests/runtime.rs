@@ -1,50 +1,1522 @@
-// use std::{fs, io};
-//
-// use tsr_lexer::{globals::Span, Lexer};
-// use tsr_parser::{ast::PredefinedType, Parser};
-// use tsr_runtime::{
-//     api::{reflection::Reflection, util::Util},
-//     value::{builders::ObjectBuilder, Value},
-//     FunctionBuilder, Runtime,
-// };
-//
-// #[test]
-// fn main() -> io::Result<()> {
-//     let path = "main.tsx";
-//     let input = fs::read_to_string(path)?;
-//     let code = input.as_bytes();
-//
-//     let (_, tokens) = Lexer::lex_tokens(code.into()).unwrap();
-//     let (_, ast) = Parser::parse_tokens(&tokens).unwrap();
-//     let mut runtime = Runtime::default();
-//
-//     runtime.set_variable(
-//         "console",
-//         Span::default().wrap(
-//             ObjectBuilder::default()
-//                 .prop(
-//                     "log",
-//                     FunctionBuilder::new("log")
-//                         .param("data", PredefinedType::Any)
-//                         .returns(PredefinedType::Void)
-//                         .build(|args| {
-//                             if let Some(data) = args.get("data") {
-//                                 match data {
-//                                     Value::String(data) => println!("{data}"),
-//                                     data => println!("{data:#}"),
-//                                 }
-//                             }
-//                         }),
-//                 )
-//                 .build(),
-//         ),
-//     );
-//
-//     runtime.add_module(&Reflection);
-//     runtime.add_module(&Util);
-//
-//     println!("{}", runtime.eval_program(ast).format(path, &input));
-//     // println!("{:#?}", runtime.get_context());
-//
-//     Ok(())
-// }
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ast::datatype::DataType;
+use parse::config::Config;
+use parse::eval_ast::parse_str;
+use parse::test::TempDir;
+use parse::util::ArraySize;
+use parse::{DiagnosticKind, ParsingCtx, SourceFile};
+use runtime::drawing::DrawingKind;
+use runtime::input::InputKind;
+use runtime::metadata::ScriptMetadata;
+use runtime::value::native::{Module, NativeModule};
+use runtime::value::{ErrorCode, Value};
+use runtime::{FunctionBuilder, Runtime};
+
+fn run_with_config(mut runtime: Runtime, source: SourceFile, config: Config) -> (Runtime, Value) {
+    let config = Config {
+        overloads: runtime.overloads(),
+        ..config
+    };
+    let mut parsing_ctx = ParsingCtx::new(&config);
+
+    parsing_ctx.add_file(&source);
+
+    let root = parse_str(&mut parsing_ctx, &config).expect("script should parse");
+    let value = runtime.eval_app(root);
+
+    (runtime, value)
+}
+
+fn run_with(runtime: Runtime, source: SourceFile) -> (Runtime, Value) {
+    run_with_config(runtime, source, Config::default())
+}
+
+fn run(source: SourceFile) -> (Runtime, Value) {
+    run_with(Runtime::default(), source)
+}
+
+fn run_file(path: &str) -> (Runtime, Value) {
+    run(SourceFile::from_file(path.to_string()).unwrap())
+}
+
+fn run_str(content: &str) -> (Runtime, Value) {
+    run(SourceFile::from_str("root.ps", content).unwrap())
+}
+
+fn variable(runtime: &Runtime, name: &str) -> Option<Value> {
+    runtime
+        .get_context()
+        .lock()
+        .unwrap()
+        .get(name, vec!["root".into()])
+        .map(|variable| variable.value.clone())
+}
+
+#[test]
+fn for_in_over_host_array() {
+    let printed = Rc::new(RefCell::new(vec![]));
+    let runtime = Runtime::default();
+    let elements = [1, 2, 3].map(Value::Number).to_vec();
+
+    runtime.set_variable("a", Value::Array(elements.into(), ArraySize::Dynamic));
+    runtime.set_variable(
+        "print",
+        FunctionBuilder::new("print")
+            .param("value", DataType::Int)
+            .build({
+                let printed = printed.clone();
+
+                move |args| printed.borrow_mut().push(args.get_number("value"))
+            }),
+    );
+
+    let (_, value) = run_with(runtime, SourceFile::from_file("script/for.ps".into()).unwrap());
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(*printed.borrow(), [1, 2, 3]);
+}
+
+#[test]
+fn while_over_host_variables() {
+    let runtime = Runtime::default();
+    let context = runtime.get_context();
+
+    runtime.set_variable("i", Value::Number(0));
+    runtime.set_variable("x", Value::Number(3));
+    // Stands for a host function with side effects, counting its calls in `i`.
+    runtime.set_variable(
+        "genv",
+        FunctionBuilder::new("genv")
+            .param("x", DataType::Int)
+            .param("y", DataType::Int)
+            .build(move |args| {
+                let area = args.get_number("x") * args.get_number("y");
+                let mut context = context.lock().unwrap();
+                let calls = match context.get("i", vec!["root".into()]) {
+                    Some(variable) => variable.value.clone(),
+                    None => Value::None,
+                };
+
+                if let Value::Number(calls) = calls {
+                    context.set(&["i"], vec!["root".into()], Value::Number(calls + 1));
+                }
+
+                args.returns(Value::Number(area));
+            }),
+    );
+
+    let (runtime, value) =
+        run_with(runtime, SourceFile::from_file("script/while.ps".into()).unwrap());
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "i"), Some(Value::Number(3)));
+}
+
+#[test]
+fn for_in_with_break_and_continue() {
+    let printed = Rc::new(RefCell::new(vec![]));
+    let runtime = Runtime::default();
+
+    runtime.set_variable(
+        "print",
+        FunctionBuilder::new("print")
+            .param("value", DataType::Int)
+            .build({
+                let printed = printed.clone();
+
+                move |args| printed.borrow_mut().push(args.get_number("value"))
+            }),
+    );
+
+    let (runtime, value) = run_with(
+        runtime,
+        SourceFile::from_file("script/for_break.ps".into()).unwrap(),
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(*printed.borrow(), [1, 3, 4]);
+    assert_eq!(variable(&runtime, "i"), None);
+}
+
+#[test]
+fn if_else_chain() {
+    let (runtime, _) = run_str(
+        "x = 5\nsize = 0\nif x < 3\n    size := 1\nelse if x < 10\n    size := 2\nelse\n    size := 3\n",
+    );
+
+    assert_eq!(variable(&runtime, "size"), Some(Value::Number(2)));
+}
+
+#[test]
+fn if_as_expression() {
+    let (runtime, _) = run_str("x = 1\nlabel = if x > 0\n    \"up\"\nelse\n    \"down\"\n");
+
+    assert_eq!(
+        variable(&runtime, "label"),
+        Some(Value::String("up".into()))
+    );
+}
+
+#[test]
+fn nested_loop_break_only_exits_inner_loop() {
+    let (runtime, _) = run_str(
+        "count = 0\nfor i in [int 1, 2, 3]\n    for j in [int 1, 2, 3]\n        if j == 2\n            break\n        count += 1\n",
+    );
+
+    assert_eq!(variable(&runtime, "count"), Some(Value::Number(3)));
+}
+
+#[test]
+fn break_outside_loop_is_an_error() {
+    let (_, value) = run_str("if true\n    break\n");
+
+    assert!(matches!(value, Value::Error(_, ErrorCode::ControlFlow, _)));
+}
+
+#[test]
+fn for_range_is_inclusive() {
+    let (runtime, _) = run_str("total = 0\nfor i = 0 to 4\n    total += i\n");
+
+    assert_eq!(variable(&runtime, "total"), Some(Value::Number(10)));
+}
+
+#[test]
+fn for_range_counts_down() {
+    let (runtime, _) = run_str(
+        "count = 0\nfor i = 10 to 0 by -2\n    count += 1\ndown = 0\nfor i = 3 to 1\n    down := down * 10 + i\n",
+    );
+
+    assert_eq!(variable(&runtime, "count"), Some(Value::Number(6)));
+    assert_eq!(variable(&runtime, "down"), Some(Value::Number(321)));
+}
+
+#[test]
+fn for_range_step_away_from_end_never_runs() {
+    let (runtime, _) = run_str("count = 0\nfor i = 0 to 5 by -1\n    count += 1\n");
+
+    assert_eq!(variable(&runtime, "count"), Some(Value::Number(0)));
+}
+
+#[test]
+fn for_range_returns_last_value() {
+    let (runtime, _) = run_str("last = for i = 1 to 3\n    i * 2\n");
+
+    assert_eq!(variable(&runtime, "last"), Some(Value::Number(6)));
+}
+
+#[test]
+fn switch_with_subject() {
+    let (runtime, _) = run_str(
+        "kind = \"b\"\nscore = switch kind\n    \"a\" => 1\n    \"b\" =>\n        x = 2\n        x * 10\n    => 0\n",
+    );
+
+    assert_eq!(variable(&runtime, "score"), Some(Value::Number(20)));
+}
+
+#[test]
+fn switch_without_subject_falls_back_to_default() {
+    let (runtime, _) = run_str(
+        "x = 5\nsize = 0\nswitch\n    x < 0 => size := 1\n    x > 10 => size := 2\n    => size := 3\n",
+    );
+
+    assert_eq!(variable(&runtime, "size"), Some(Value::Number(3)));
+}
+
+#[test]
+fn switch_without_match_is_na() {
+    let (runtime, _) = run_str("x = 5\nr = switch x\n    1 => 1\n");
+
+    assert_eq!(variable(&runtime, "r"), Some(Value::None));
+}
+
+#[test]
+fn conditional_operator_selects_one_branch() {
+    let (runtime, _) =
+        run_str("x = 5\nsign = x > 0 ? 1 : x < 0 ? -1 : 0\nlabel = x > 10 ? \"big\" : \"small\"\n");
+
+    assert_eq!(variable(&runtime, "sign"), Some(Value::Number(1)));
+    assert_eq!(
+        variable(&runtime, "label"),
+        Some(Value::String("small".into()))
+    );
+}
+
+#[test]
+fn condition_of_wrong_type_points_at_the_condition() {
+    let source = "text = str.tostring(1)\nflag = 0\nif text\n    flag := 1\n";
+    let (_, value) = run_str(source);
+
+    assert!(
+        matches!(&value, Value::Error(span, ErrorCode::Type, _) if span.line == 3),
+        "{value:?}"
+    );
+
+    let report = value.format("root.ps", source);
+
+    assert!(report.contains("root.ps:3:"), "{report}");
+    assert!(report.contains("if text"), "{report}");
+    assert!(report.contains("bool expected"), "{report}");
+}
+
+#[test]
+fn number_is_not_a_condition() {
+    let runtime = Runtime::default();
+
+    // A host variable, whose type the checker doesn't know.
+    runtime.set_variable("count", Value::Number(1));
+
+    let source = "flag = 0\nwhile count\n    flag := 1\n    break\n";
+    let (runtime, value) = run_with(runtime, SourceFile::from_str("root.ps", source).unwrap());
+
+    assert!(
+        matches!(&value, Value::Error(span, ErrorCode::Type, message)
+            if span.line == 2 && message == "bool expected but number given"),
+        "{value:?}"
+    );
+    assert_eq!(variable(&runtime, "flag"), Some(Value::Number(0)));
+}
+
+#[test]
+fn declaration_in_block_shadows_outer_variable() {
+    let (runtime, _) =
+        run_str("size = 1\nif true\n    size = 2\nflag = 0\nif true\n    flag := 1\n");
+
+    assert_eq!(variable(&runtime, "size"), Some(Value::Number(1)));
+    assert_eq!(variable(&runtime, "flag"), Some(Value::Number(1)));
+}
+
+#[test]
+fn compound_assignment_operators() {
+    let (runtime, _) = run_str("x = 10\nx += 5\nx -= 3\nx *= 2\nx /= 4\ny = 1.5\ny *= 2\n");
+
+    assert_eq!(variable(&runtime, "x"), Some(Value::Number(6)));
+    assert_eq!(variable(&runtime, "y"), Some(Value::Float(3.0)));
+}
+
+#[test]
+fn reassignment_in_while_body_updates_outer_variables() {
+    let (runtime, value) = run_file("script/while_reassign.ps");
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "i"), Some(Value::Number(3)));
+    assert_eq!(variable(&runtime, "area"), Some(Value::Number(18)));
+}
+
+#[test]
+fn reassigning_undeclared_variable_is_rejected() {
+    let config = Config {
+        quiet: true,
+        ..Config::default()
+    };
+    let mut parsing_ctx = ParsingCtx::new(&config);
+
+    parsing_ctx.add_file(&SourceFile::from_str("root.ps", "total := 1\n").unwrap());
+
+    let diagnostic = parse_str(&mut parsing_ctx, &config).unwrap_err();
+
+    assert!(matches!(
+        diagnostic.get_kind(),
+        DiagnosticKind::UndeclaredAssignment(name) if name == "total"
+    ));
+}
+
+#[test]
+fn destructure_tuple_returned_by_function() {
+    let (runtime, value) = run_str(
+        "bands(basis, width) =>\n    [basis - width, basis, basis + width]\n[lowerBand, middle, upperBand] = bands(10, 2)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "lowerBand"), Some(Value::Number(8)));
+    assert_eq!(variable(&runtime, "middle"), Some(Value::Number(10)));
+    assert_eq!(variable(&runtime, "upperBand"), Some(Value::Number(12)));
+}
+
+/// Stands for a host's `ta` namespace, with a Bollinger Bands indicator returning
+/// `[middle, upper, lower]` around its source.
+struct Bands;
+
+impl NativeModule for Bands {
+    fn build_module(&'static self) -> Module {
+        let mut module = Module::new("ta");
+
+        module.export(
+            "bb",
+            FunctionBuilder::new("bb")
+                .param("source", DataType::Int)
+                .param("width", DataType::Int)
+                .returns(DataType::Tuple(vec![DataType::Int; 3]))
+                .build(|args| {
+                    let source = args.get_number("source");
+                    let width = args.get_number("width");
+
+                    args.returns(Value::Tuple(vec![
+                        Value::Number(source),
+                        Value::Number(source + width),
+                        Value::Number(source - width),
+                    ]))
+                }),
+        );
+
+        module
+    }
+}
+
+#[test]
+fn destructure_tuple_returned_by_builtin() {
+    let mut runtime = Runtime::default();
+
+    runtime.add_module(&Bands);
+
+    let source = SourceFile::from_str("root.ps", "[middle, upper, lower] = ta.bb(10, 2)\n");
+    let (runtime, value) = run_with(runtime, source.unwrap());
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "upper"), Some(Value::Number(12)));
+    assert_eq!(variable(&runtime, "lower"), Some(Value::Number(8)));
+
+    let config = Config {
+        quiet: true,
+        overloads: runtime.overloads(),
+        ..Config::default()
+    };
+    let mut parsing_ctx = ParsingCtx::new(&config);
+
+    parsing_ctx
+        .add_file(&SourceFile::from_str("root.ps", "[upper, lower] = ta.bb(10, 2)\n").unwrap());
+
+    let diagnostic = parse_str(&mut parsing_ctx, &config).unwrap_err();
+
+    assert!(matches!(
+        diagnostic.get_kind(),
+        DiagnosticKind::TupleArityMismatch(3, 2)
+    ));
+}
+
+#[test]
+fn destructure_tuple_with_wrong_arity_at_runtime() {
+    let (_, value) = run_str("x = 1\n[a, b] = x > 0 ? [1, 2, 3] : [1, 2]\n");
+
+    assert!(matches!(value, Value::Error(_, ErrorCode::Type, _)));
+}
+
+const PIVOT: &str = "type Pivot\n    float price = 0.0\n    int bar\n";
+
+#[test]
+fn user_type_fields_take_arguments_then_defaults() {
+    let (runtime, value) = run_str(&format!(
+        "{PIVOT}first = Pivot.new(10, 2)\nsecond = Pivot.new()\nprice = first.price\nbar = first.bar\ndefault = second.price\nmissing = second.bar\n"
+    ));
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "price"), Some(Value::Float(10.0)));
+    assert_eq!(variable(&runtime, "bar"), Some(Value::Number(2)));
+    assert_eq!(variable(&runtime, "default"), Some(Value::Float(0.0)));
+    assert_eq!(variable(&runtime, "missing"), Some(Value::None));
+}
+
+#[test]
+fn user_type_objects_are_shared_until_copied() {
+    let (runtime, value) = run_str(&format!(
+        "{PIVOT}a = Pivot.new(1.0)\nb = a\nb.price := 5.0\nc = a.copy()\nc.price += 4.0\nshared = a.price\ncopied = c.price\n"
+    ));
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "shared"), Some(Value::Float(5.0)));
+    assert_eq!(variable(&runtime, "copied"), Some(Value::Float(9.0)));
+}
+
+#[test]
+fn method_called_with_dot_syntax_mutates_receiver() {
+    let (runtime, value) = run_str(&format!(
+        "{PIVOT}method bump(Pivot this, float amount) =>\n    this.price += amount\np = Pivot.new(1.0)\nresult = p.bump(2.0)\nagain = bump(p, 1.0)\nprice = p.price\n"
+    ));
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "result"), Some(Value::Float(3.0)));
+    assert_eq!(variable(&runtime, "price"), Some(Value::Float(4.0)));
+}
+
+#[test]
+fn arguments_bind_to_parameters_and_fields_by_name() {
+    let (runtime, value) = run_str(&format!(
+        "{PIVOT}scale(x, factor = 2, offset = 0) => x * factor + offset\nscaled = scale(5, offset = 1)\nswapped = scale(factor = 3, x = 4)\np = Pivot.new(bar = 7)\nprice = p.price\nbar = p.bar\nrounded = math.round(2.567, precision = 2)\n"
+    ));
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "scaled"), Some(Value::Number(11)));
+    assert_eq!(variable(&runtime, "swapped"), Some(Value::Number(12)));
+    assert_eq!(variable(&runtime, "price"), Some(Value::Float(0.0)));
+    assert_eq!(variable(&runtime, "bar"), Some(Value::Number(7)));
+    assert_eq!(variable(&runtime, "rounded"), Some(Value::Float(2.57)));
+
+    for (call, message) in [
+        ("scale(1, size = 2)", "no parameter is named `size`"),
+        ("scale(1, x = 2)", "`x` is given more than once"),
+        ("scale(factor = 2)", "argument `x` is missing"),
+        (
+            "Pivot.new(1.0, price = 2.0)",
+            "field `price` of Pivot is given more than once",
+        ),
+    ] {
+        let (_, value) = run_str(&format!(
+            "{PIVOT}scale(x, factor = 2, offset = 0) => x * factor + offset\nresult = {call}\n"
+        ));
+
+        assert!(
+            matches!(&value, Value::Error(span, ErrorCode::Type, found)
+                if span.line == 5 && found == message),
+            "{call}: {value:?}"
+        );
+    }
+}
+
+#[test]
+fn positional_argument_after_named_one_is_a_syntax_error() {
+    let config = Config {
+        quiet: true,
+        ..Config::default()
+    };
+    let mut parsing_ctx = ParsingCtx::new(&config);
+
+    parsing_ctx.add_file(
+        &SourceFile::from_str("root.ps", "length = input.int(title = \"Length\", 14)\n").unwrap(),
+    );
+
+    let diagnostic = parse_str(&mut parsing_ctx, &config).unwrap_err();
+
+    assert!(matches!(
+        diagnostic.get_kind(),
+        DiagnosticKind::SyntaxError(_)
+    ));
+    assert_eq!(diagnostic.span.line, 1);
+}
+
+#[test]
+fn field_of_wrong_type_is_an_error() {
+    // The checker doesn't know what `describe` returns, so the string reaches the runtime.
+    let (_, value) = run_str(&format!(
+        "{PIVOT}describe(x) => str.tostring(x)\ntext = describe(1)\np = Pivot.new(text)\n"
+    ));
+
+    assert!(matches!(value, Value::Error(_, ErrorCode::Type, _)));
+
+    let (runtime, value) = run_str(&format!(
+        "{PIVOT}describe(x) => str.tostring(x)\ntext = describe(1)\np = Pivot.new(1.0)\np.price := text\n"
+    ));
+
+    assert!(matches!(value, Value::Error(_, ErrorCode::Type, _)));
+    assert!(matches!(
+        variable(&runtime, "p"),
+        Some(Value::ClassInstance(p)) if p.get_field("price") == Some(Value::Float(1.0))
+    ));
+}
+
+#[test]
+fn float_parameter_takes_an_int_as_a_float() {
+    let (runtime, value) = run_str("widen(float x) => x\ny = widen(3)\nz = math.abs(-3)\n");
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "y"), Some(Value::Float(3.0)));
+    assert_eq!(variable(&runtime, "z"), Some(Value::Number(3)));
+}
+
+#[test]
+fn float_variable_holds_an_int_as_a_float() {
+    let (runtime, value) = run_str("float x = 1\nhalf = x / 2\nfloat y = 0\ny := 3\n");
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "x"), Some(Value::Float(1.0)));
+    assert_eq!(variable(&runtime, "half"), Some(Value::Float(0.5)));
+    assert_eq!(variable(&runtime, "y"), Some(Value::Float(3.0)));
+}
+
+#[test]
+fn field_of_na_object_is_an_error() {
+    let (_, value) = run_str("type Node\n    Node next\nn = Node.new()\nx = n.next.next\n");
+
+    assert!(matches!(value, Value::Error(_, ErrorCode::Reference, _)));
+}
+
+const SIGNAL: &str = "enum Signal\n    buy = \"Buy signal\"\n    sell = \"Sell signal\"\n    neutral\n";
+
+#[test]
+fn enum_members_are_comparable_values() {
+    let (runtime, value) = run_str(&format!(
+        "{SIGNAL}s = Signal.sell\nsame = s == Signal.sell\nscore = switch s\n    Signal.buy => 1\n    Signal.sell => -1\n    => 0\n"
+    ));
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "same"), Some(Value::Boolean(true)));
+    assert_eq!(variable(&runtime, "score"), Some(Value::Number(-1)));
+
+    let s = variable(&runtime, "s").unwrap();
+
+    assert_eq!(s.type_of(), "Signal");
+    assert_eq!(s.to_string(), "Sell signal");
+}
+
+#[test]
+fn enum_member_without_title_is_displayed_as_its_name() {
+    let (runtime, _) = run_str(&format!("{SIGNAL}s = Signal.neutral\n"));
+
+    assert_eq!(variable(&runtime, "s").unwrap().to_string(), "neutral");
+}
+
+#[test]
+fn input_enum_returns_default_member() {
+    let (runtime, value) = run_str(&format!(
+        "{SIGNAL}mode = input.enum(Signal.sell, \"Mode\", [Signal.buy, Signal.sell])\n"
+    ));
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "mode").unwrap().to_string(), "Sell signal");
+}
+
+#[test]
+fn input_enum_default_must_be_an_option() {
+    let (_, value) = run_str(&format!(
+        "{SIGNAL}mode = input.enum(Signal.neutral, \"Mode\", [Signal.buy, Signal.sell])\n"
+    ));
+
+    assert!(matches!(value, Value::Error(_, ErrorCode::Type, _)));
+}
+
+#[test]
+fn method_call_on_builtin_type_calls_declared_method() {
+    let (runtime, value) = run_str(
+        "method scale(float this, float k) =>\n    this * k\nx = 2.0\ny = x.scale(3.0)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "y"), Some(Value::Float(6.0)));
+}
+
+#[test]
+fn method_call_does_not_call_plain_functions() {
+    let (_, value) = run_str("twice(x) => x * 2\nx = 2.0\nz = x.twice()\n");
+
+    assert!(matches!(value, Value::Error(_, ErrorCode::Reference, _)));
+
+    // A function named like a namespace function doesn't hijack the method call.
+    let (runtime, value) = run_str(
+        "push(arr, v) => 42\na = array.new_float(0)\na.push(1.5)\nsize = a.size()\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "size"), Some(Value::Number(1)));
+}
+
+#[test]
+fn method_call_on_builtin_type_falls_back_to_namespace() {
+    let (runtime, value) =
+        run_str("src = -1.5\nmagnitude = src.abs()\nbull = #26A69A\nred = bull.r()\n");
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "magnitude"), Some(Value::Float(1.5)));
+    assert_eq!(variable(&runtime, "red"), Some(Value::Float(38.0)));
+}
+
+#[test]
+fn unknown_method_on_builtin_type_is_an_error() {
+    let (_, value) = run_str("x = 2.0\ny = x.nothing()\n");
+
+    match value {
+        Value::Error(_, ErrorCode::Reference, message) => {
+            assert!(message.contains("`math.nothing`"), "{message}")
+        }
+        value => panic!("expected a reference error, got {value}"),
+    }
+}
+
+#[test]
+fn indicator_method_on_number_is_an_error() {
+    // There is no `ta` namespace, so `src.sma(14)` finds no `math.sma` to call.
+    let (_, value) = run_str("src = 1.0\naverage = src.sma(14)\n");
+
+    match value {
+        Value::Error(span, ErrorCode::Reference, message) => {
+            assert_eq!(span.line, 2);
+            assert_eq!(
+                message,
+                "float has no method `sma` and `math.sma` doesn't exist"
+            );
+        }
+        value => panic!("expected a reference error, got {value}"),
+    }
+}
+
+#[test]
+fn arrays_are_shared_between_variables() {
+    let (runtime, value) = run_str(
+        "a = array.new_float(0)\nb = a\narray.push(a, 1.5)\nb.push(2.5)\na.unshift(0.5)\nsize = array.size(b)\nfirst = a.get(0)\nlast = b.get(-1)\npopped = a.pop()\ncopied = a.copy()\ncopied.clear()\nremaining = a.size()\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "size"), Some(Value::Number(3)));
+    assert_eq!(variable(&runtime, "first"), Some(Value::Float(0.5)));
+    assert_eq!(variable(&runtime, "last"), Some(Value::Float(2.5)));
+    assert_eq!(variable(&runtime, "popped"), Some(Value::Float(2.5)));
+    assert_eq!(variable(&runtime, "remaining"), Some(Value::Number(2)));
+}
+
+#[test]
+fn array_of_floats_holds_ints_put_in_it_as_floats() {
+    let (runtime, value) = run_str(
+        "a = array.new<float>(2, 0)\na.push(1)\nfirst = a.get(0)\npushed = a.get(2)\nb = a.copy()\nb.set(0, 3)\nb.unshift(4)\nset = b.get(1)\nunshifted = b.get(0)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "first"), Some(Value::Float(0.0)));
+    assert_eq!(variable(&runtime, "pushed"), Some(Value::Float(1.0)));
+    assert_eq!(variable(&runtime, "set"), Some(Value::Float(3.0)));
+    assert_eq!(variable(&runtime, "unshifted"), Some(Value::Float(4.0)));
+}
+
+#[test]
+fn array_construction_and_sorting() {
+    let (runtime, value) = run_str(
+        "filled = array.new<int>(3, 7).join(\",\")\nb = array.from(3, 1, 2)\nb.sort()\nsorted = b.join(\",\")\nindices = array.sort_indices(array.from(3, 1, 2), order.descending).join(\",\")\nfound = b.indexof(2)\nhas = b.includes(5)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "filled"), Some(Value::String("7,7,7".into())));
+    assert_eq!(variable(&runtime, "sorted"), Some(Value::String("1,2,3".into())));
+    assert_eq!(variable(&runtime, "indices"), Some(Value::String("0,2,1".into())));
+    assert_eq!(variable(&runtime, "found"), Some(Value::Number(1)));
+    assert_eq!(variable(&runtime, "has"), Some(Value::Boolean(false)));
+}
+
+#[test]
+fn array_statistics() {
+    let (runtime, value) = run_str(
+        "a = array.from(4, 1, 3, 2)\ns = a.sum()\navg = a.avg()\nmed = a.median()\nsd = a.stdev()\nrank = a.percentile_nearest_rank(50)\nlinear = a.percentile_linear_interpolation(50)\nhighest = a.max()\nsecond = a.min(1)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "s"), Some(Value::Number(10)));
+    assert_eq!(variable(&runtime, "avg"), Some(Value::Float(2.5)));
+    assert_eq!(variable(&runtime, "med"), Some(Value::Float(2.5)));
+    assert_eq!(variable(&runtime, "sd"), Some(Value::Float(1.25_f64.sqrt())));
+    assert_eq!(variable(&runtime, "rank"), Some(Value::Float(2.0)));
+    assert_eq!(variable(&runtime, "linear"), Some(Value::Float(2.5)));
+    assert_eq!(variable(&runtime, "highest"), Some(Value::Number(4)));
+    assert_eq!(variable(&runtime, "second"), Some(Value::Number(2)));
+}
+
+#[test]
+fn array_index_out_of_bounds_is_reported_at_the_call() {
+    let (_, value) = run_str("a = array.from(1, 2)\nx = a.get(5)\n");
+
+    match value {
+        Value::Error(span, ErrorCode::OutOfBounds, _) => assert_eq!(span.line, 2),
+        value => panic!("expected an out of bounds error, got {value}"),
+    }
+}
+
+#[test]
+fn matrices_are_shared_until_copied() {
+    let (runtime, value) = run_str(
+        "m = matrix.new<int>(2, 2, 0)\nalias = m\ncopy = m.copy()\nalias.set(0, 1, 5)\nm.add_row(1, array.from(7, 8))\nt = m.transpose()\nx = m.get(0, 1)\ny = copy.get(0, 1)\nrows = m.rows()\ncorner = t.get(1, 2)\nremoved = m.remove_col(0)\ncolumns = m.columns()\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "x"), Some(Value::Number(5)));
+    assert_eq!(variable(&runtime, "y"), Some(Value::Number(0)));
+    assert_eq!(variable(&runtime, "rows"), Some(Value::Number(3)));
+    assert_eq!(variable(&runtime, "corner"), Some(Value::Number(0)));
+    assert_eq!(variable(&runtime, "columns"), Some(Value::Number(1)));
+    assert_eq!(
+        variable(&runtime, "removed").map(|removed| removed.to_string()),
+        Some("[0, 7, 0]".to_string())
+    );
+}
+
+#[test]
+fn matrix_linear_algebra() {
+    let (runtime, value) = run_str(
+        "m = matrix.new<float>(2, 2, 1.0)\nm.set(0, 0, 2.0)\nm.set(1, 1, 2.0)\ndet = m.det()\nproduct = m.mult(m.inv())\nidentity = product.is_identity()\nvalues = m.eigenvalues()\nlargest = values.get(0)\nsmallest = values.get(1)\nrank = matrix.new<float>(2, 3, 1.0).rank()\nwide = matrix.new<float>(1, 2, 1.0)\npinv = wide.pinv()\nhalf = pinv.get(1, 0)\nsquared = m.pow(2).get(0, 1)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+
+    let float = |name: &str| match variable(&runtime, name) {
+        Some(Value::Float(float)) => float,
+        value => panic!("{name} should be a float, got {value:?}"),
+    };
+
+    assert!((float("det") - 3.0).abs() < 1e-12);
+    assert_eq!(variable(&runtime, "identity"), Some(Value::Boolean(true)));
+    assert!((float("largest") - 3.0).abs() < 1e-12);
+    assert!((float("smallest") - 1.0).abs() < 1e-12);
+    assert_eq!(variable(&runtime, "rank"), Some(Value::Number(1)));
+    assert!((float("half") - 0.5).abs() < 1e-12);
+    assert!((float("squared") - 4.0).abs() < 1e-12);
+}
+
+#[test]
+fn pseudo_inverse_of_rank_deficient_ill_conditioned_matrix() {
+    let (runtime, value) = run_str(
+        "m = matrix.new<float>(3, 3, 0.0)\nm.set(0, 0, 1.0)\nm.set(1, 0, 1.0)\nm.set(0, 1, 1e-9)\nm.set(1, 1, -1e-9)\np = m.pinv()\nfirst = p.get(0, 0)\nlarge = p.get(1, 0)\nnegative = p.get(1, 1)\nzero = p.get(2, 2)\nsquare = matrix.new<float>(2, 2, 1.0)\nsquare.set(0, 0, 2.0)\nsquare.set(1, 1, 2.0)\ninverse = square.pinv().get(0, 1)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+
+    let float = |name: &str| match variable(&runtime, name) {
+        Some(Value::Float(float)) => float,
+        value => panic!("{name} should be a float, got {value:?}"),
+    };
+
+    assert!((float("first") - 0.5).abs() < 1e-12);
+    assert!((float("large") / 5e8 - 1.0).abs() < 1e-9);
+    assert!((float("negative") / -5e8 - 1.0).abs() < 1e-9);
+    assert_eq!(float("zero"), 0.0);
+    assert!((float("inverse") + 1.0 / 3.0).abs() < 1e-12);
+}
+
+#[test]
+fn covariance_matrix_computed_in_script() {
+    let (runtime, value) = run_str(
+        "r = matrix.new<float>()\nr.add_row(0, array.from(1.0, 2.0))\nr.add_row(1, array.from(2.0, 4.0))\nr.add_row(2, array.from(3.0, 6.0))\nr.add_row(3, array.from(4.0, 9.0))\nn = r.rows()\nfor j = 0 to 1\n    mean = r.col(j).avg()\n    for i = 0 to n - 1\n        r.set(i, j, r.get(i, j) - mean)\ncov = r.transpose().mult(r).mult(1.0 / (n - 1))\nsymmetric = cov.is_symmetric()\nweights = cov.inv().mult(array.from(1.0, 1.0))\nvx = cov.get(0, 0)\ncxy = cov.get(0, 1)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "symmetric"), Some(Value::Boolean(true)));
+    assert!(
+        matches!(variable(&runtime, "vx"), Some(Value::Float(vx)) if (vx - 5.0 / 3.0).abs() < 1e-12)
+    );
+    assert!(
+        matches!(variable(&runtime, "cxy"), Some(Value::Float(cxy)) if (cxy - 11.5 / 3.0).abs() < 1e-12)
+    );
+    assert!(
+        matches!(variable(&runtime, "weights"), Some(Value::Array(weights, _)) if weights.len() == 2)
+    );
+}
+
+#[test]
+fn matrix_dimension_mismatch_is_an_error() {
+    let (_, value) = run_str("a = matrix.new<float>(2, 3, 1.0)\nb = a.mult(a)\n");
+
+    assert!(matches!(value, Value::Error(_, ErrorCode::Type, _)), "{value}");
+}
+
+#[test]
+fn map_keeps_insertion_order() {
+    let (runtime, value) = run_str(
+        "m = map.new<string, float>()\nm.put(\"b\", 2.0)\nm.put(\"a\", 1.0)\nprevious = m.put(\"b\", 3.0)\nkeys = m.keys()\nvalues = m.values()\nremoved = m.remove(\"a\")\nsize = m.size()\nhas = m.contains(\"a\")\nmissing = m.get(\"a\")\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(
+        variable(&runtime, "keys").map(|keys| keys.to_string()),
+        Some("[\"b\", \"a\"]".to_string())
+    );
+    assert_eq!(
+        variable(&runtime, "values").map(|values| values.to_string()),
+        Some("[3, 1]".to_string())
+    );
+    assert_eq!(variable(&runtime, "previous"), Some(Value::Float(2.0)));
+    assert_eq!(variable(&runtime, "removed"), Some(Value::Float(1.0)));
+    assert_eq!(variable(&runtime, "size"), Some(Value::Number(1)));
+    assert_eq!(variable(&runtime, "has"), Some(Value::Boolean(false)));
+    assert_eq!(variable(&runtime, "missing"), Some(Value::None));
+}
+
+#[test]
+fn maps_are_shared_until_copied_and_accept_float_keys() {
+    let (runtime, value) = run_str(
+        "m = map.new<float, int>()\nalias = m\ncopy = m.copy()\nalias.put(0.5, 1)\nm.put(-0.0, 2)\nhalf = m.get(0.5)\nzero = m.get(0.0)\ncopied = copy.size()\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "half"), Some(Value::Number(1)));
+    assert_eq!(variable(&runtime, "zero"), Some(Value::Number(2)));
+    assert_eq!(variable(&runtime, "copied"), Some(Value::Number(0)));
+}
+
+#[test]
+fn map_of_floats_holds_ints_put_in_it_as_floats() {
+    let (runtime, value) = run_str(
+        "m = map.new<string, float>()\nm.put(\"a\", 1)\nput = m.get(\"a\")\ncopy = m.copy()\ncopy.put(\"b\", 2)\ncopied = copy.get(\"b\")\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "put"), Some(Value::Float(1.0)));
+    assert_eq!(variable(&runtime, "copied"), Some(Value::Float(2.0)));
+}
+
+#[test]
+fn nan_map_key_is_rejected_as_na() {
+    let (_, value) = run_str(
+        "a = 1e308 * 10.0\nb = a - a\nm = map.new<float, float>()\nm.put(b, 1.0)\nk = m.values()\n",
+    );
+
+    match value {
+        Value::Error(_, ErrorCode::Type, message) => {
+            assert!(message.contains("na"), "{message}")
+        }
+        value => panic!("expected a type error, got {value}"),
+    }
+}
+
+#[test]
+fn na_propagates_through_arithmetic_and_comparisons_are_false() {
+    let (runtime, value) = run_str(
+        "x = na\nsum = x + 1\nscaled = 2.0 * x\nless = x < 1\nequal = x == na\ndifferent = x != 1\nchecked = na(sum)\nfilled = nz(sum)\nfallback = nz(x, 5.0)\nkept = nz(3)\nbranch = x > 0 ? 1 : -1\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "sum"), Some(Value::None));
+    assert_eq!(variable(&runtime, "scaled"), Some(Value::None));
+    assert_eq!(variable(&runtime, "less"), Some(Value::Boolean(false)));
+    assert_eq!(variable(&runtime, "equal"), Some(Value::Boolean(false)));
+    assert_eq!(variable(&runtime, "different"), Some(Value::Boolean(false)));
+    assert_eq!(variable(&runtime, "checked"), Some(Value::Boolean(true)));
+    assert_eq!(variable(&runtime, "filled"), Some(Value::Number(0)));
+    assert_eq!(variable(&runtime, "fallback"), Some(Value::Float(5.0)));
+    assert_eq!(variable(&runtime, "kept"), Some(Value::Number(3)));
+    assert_eq!(variable(&runtime, "branch"), Some(Value::Number(-1)));
+}
+
+#[test]
+fn fixnan_repeats_the_last_value_seen_at_its_call_site() {
+    let (runtime, value) = run_str(
+        "prices = array.from(na, 1.0, na, 3.0, na)\nfixed = array.new<float>()\nfor price in prices\n    array.push(fixed, fixnan(price))\nother = fixnan(na)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(
+        variable(&runtime, "fixed").map(|fixed| fixed.to_string()),
+        Some("[na, 1, 1, 3, 3]".to_string())
+    );
+    assert_eq!(variable(&runtime, "other"), Some(Value::None));
+}
+
+#[test]
+fn ints_promote_to_floats_and_division_keeps_fractions() {
+    let (runtime, value) = run_str(
+        "half = 5 / 2\nexact = 6 / 3\nmixed = 1 + 0.5\nsmall = 1e-3\nwhole = 2.0\nzero = 1 / 0\nzero_float = 1.5 / 0\noverflow = 9223372036854775807 + 1\ntruncated = int(-2.7)\npromoted = float(3)\nprice = int(10.99) * 2\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "half"), Some(Value::Float(2.5)));
+    assert_eq!(variable(&runtime, "exact"), Some(Value::Number(2)));
+    assert_eq!(variable(&runtime, "mixed"), Some(Value::Float(1.5)));
+    assert_eq!(variable(&runtime, "small"), Some(Value::Float(0.001)));
+    assert_eq!(variable(&runtime, "whole"), Some(Value::Float(2.0)));
+    assert_eq!(variable(&runtime, "zero"), Some(Value::None));
+    assert_eq!(variable(&runtime, "zero_float"), Some(Value::None));
+    assert_eq!(variable(&runtime, "overflow"), Some(Value::None));
+    assert_eq!(variable(&runtime, "truncated"), Some(Value::Number(-2)));
+    assert_eq!(variable(&runtime, "promoted"), Some(Value::Float(3.0)));
+    assert_eq!(variable(&runtime, "price"), Some(Value::Number(20)));
+}
+
+#[test]
+fn float_results_that_are_not_finite_are_na() {
+    let (runtime, value) = run_str(
+        "product = 1e308 * 10.0\nsum = 1e308 + 1e308\ndifference = -1e308 - 1e308\nquotient = 1e308 / 1e-308\nkept = 1e308 / 10\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "product"), Some(Value::None));
+    assert_eq!(variable(&runtime, "sum"), Some(Value::None));
+    assert_eq!(variable(&runtime, "difference"), Some(Value::None));
+    assert_eq!(variable(&runtime, "quotient"), Some(Value::None));
+    assert_eq!(variable(&runtime, "kept"), Some(Value::Float(1e307)));
+}
+
+#[test]
+fn math_functions_keep_ints_and_propagate_na() {
+    let (runtime, value) = run_str(
+        "absolute = math.abs(-3)\nabsolute_float = math.abs(-2.5)\nceiling = math.ceil(1.2)\nrounded = math.round(2.567, 2)\nrounded_int = math.round(2.5)\ntick = math.round_to_mintick(1.234)\nroot = math.sqrt(16)\nnegative_log = math.log(-1)\nmissing = math.sqrt(na)\nhighest = math.max(1, 5, 3)\nlowest = math.min(1, 0.5)\nmax_na = math.max(1, na)\naverage = math.avg(1, 2)\nsign = math.sign(-4.0)\ndegrees = math.todegrees(math.pi)\npower = math.pow(2, 10)\nnegative = -7\nmagnitude = negative.abs()\nsums = array.new<float>()\nfor i = 1 to 4\n    array.push(sums, math.sum(i, 3))\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "absolute"), Some(Value::Number(3)));
+    assert_eq!(variable(&runtime, "absolute_float"), Some(Value::Float(2.5)));
+    assert_eq!(variable(&runtime, "ceiling"), Some(Value::Number(2)));
+    assert_eq!(variable(&runtime, "rounded"), Some(Value::Float(2.57)));
+    assert_eq!(variable(&runtime, "rounded_int"), Some(Value::Number(3)));
+    assert_eq!(variable(&runtime, "tick"), Some(Value::Float(1.23)));
+    assert_eq!(variable(&runtime, "root"), Some(Value::Float(4.0)));
+    assert_eq!(variable(&runtime, "negative_log"), Some(Value::None));
+    assert_eq!(variable(&runtime, "missing"), Some(Value::None));
+    assert_eq!(variable(&runtime, "highest"), Some(Value::Number(5)));
+    assert_eq!(variable(&runtime, "lowest"), Some(Value::Float(0.5)));
+    assert_eq!(variable(&runtime, "max_na"), Some(Value::None));
+    assert_eq!(variable(&runtime, "average"), Some(Value::Float(1.5)));
+    assert_eq!(variable(&runtime, "sign"), Some(Value::Float(-1.0)));
+    assert_eq!(variable(&runtime, "degrees"), Some(Value::Float(180.0)));
+    assert_eq!(variable(&runtime, "power"), Some(Value::Float(1024.0)));
+    assert_eq!(variable(&runtime, "magnitude"), Some(Value::Number(7)));
+    assert_eq!(
+        variable(&runtime, "sums").map(|sums| sums.to_string()),
+        Some("[na, na, 6, 9]".to_string())
+    );
+}
+
+#[test]
+fn mintick_comes_from_the_host() {
+    let script = "tick = math.round_to_mintick(1.3)\ntext = str.tostring(1.3, format.mintick)\n";
+    let (runtime, value) = run_str(script);
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "tick"), Some(Value::Float(1.3)));
+    assert_eq!(
+        variable(&runtime, "text"),
+        Some(Value::String("1.30".into()))
+    );
+
+    let mut runtime = Runtime::default();
+
+    runtime.set_mintick(0.25);
+
+    let (runtime, value) = run_with(runtime, SourceFile::from_str("root.ps", script).unwrap());
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "tick"), Some(Value::Float(1.25)));
+    assert_eq!(
+        variable(&runtime, "text"),
+        Some(Value::String("1.25".into()))
+    );
+
+    let mut runtime = Runtime::default();
+
+    runtime.set_mintick(1.0);
+
+    let (runtime, value) = run_with(runtime, SourceFile::from_str("root.ps", script).unwrap());
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "tick"), Some(Value::Float(1.0)));
+    assert_eq!(variable(&runtime, "text"), Some(Value::String("1".into())));
+}
+
+#[test]
+fn seeded_random_numbers_repeat_between_runs() {
+    let script = "values = array.new<float>()\nfor i = 1 to 3\n    array.push(values, math.random(10, 20, 42))\n";
+    let (first, _) = run_str(script);
+    let (second, _) = run_str(script);
+    let values = variable(&first, "values").unwrap();
+
+    assert_eq!(Some(values.clone()), variable(&second, "values"));
+
+    match values {
+        Value::Array(elements, _) => {
+            let numbers = elements.to_vec();
+
+            assert_eq!(numbers.len(), 3);
+            assert_ne!(numbers[0], numbers[1]);
+            assert!(numbers
+                .iter()
+                .all(|number| matches!(number, Value::Float(n) if (10.0..20.0).contains(n))));
+        }
+        value => panic!("array expected, found {value}"),
+    }
+}
+
+#[test]
+fn str_functions_and_concatenation() {
+    let (runtime, value) = run_str(
+        "name = 'BTC' + \"/\" + \"USD\"\nname += \"T\"\nlength = str.length(name)\nfound = str.contains(name, \"USD\")\nposition = name.pos(\"/\")\nmissing = str.pos(name, \"EUR\")\nbase = str.substring(name, 0, 3)\nquote = str.substring(name, 4)\nreplaced = str.replace(\"a-b-c\", \"-\", \"+\", 1)\nall = str.replace_all(\"a-b-c\", \"-\", \"+\")\nparts = str.split(\"a,b,c\", \",\")\nupper = str.upper(\"abc\")\ntrimmed = str.trim(\"  x \")\nstarts = name.startswith(\"BTC\")\nnumber = str.tonumber(\"1.5\")\nnot_number = str.tonumber(\"abc\")\nescaped = \"a\\tb\"\nna_length = str.length(na)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "name"), Some(Value::String("BTC/USDT".into())));
+    assert_eq!(variable(&runtime, "length"), Some(Value::Number(8)));
+    assert_eq!(variable(&runtime, "found"), Some(Value::Boolean(true)));
+    assert_eq!(variable(&runtime, "position"), Some(Value::Number(3)));
+    assert_eq!(variable(&runtime, "missing"), Some(Value::None));
+    assert_eq!(variable(&runtime, "base"), Some(Value::String("BTC".into())));
+    assert_eq!(variable(&runtime, "quote"), Some(Value::String("USDT".into())));
+    assert_eq!(variable(&runtime, "replaced"), Some(Value::String("a-b+c".into())));
+    assert_eq!(variable(&runtime, "all"), Some(Value::String("a+b+c".into())));
+    assert_eq!(
+        variable(&runtime, "parts").map(|parts| parts.to_string()),
+        Some(r#"["a", "b", "c"]"#.to_string())
+    );
+    assert_eq!(variable(&runtime, "upper"), Some(Value::String("ABC".into())));
+    assert_eq!(variable(&runtime, "trimmed"), Some(Value::String("x".into())));
+    assert_eq!(variable(&runtime, "starts"), Some(Value::Boolean(true)));
+    assert_eq!(variable(&runtime, "number"), Some(Value::Float(1.5)));
+    assert_eq!(variable(&runtime, "not_number"), Some(Value::None));
+    assert_eq!(variable(&runtime, "escaped"), Some(Value::String("a\tb".into())));
+    assert_eq!(variable(&runtime, "na_length"), Some(Value::None));
+}
+
+#[test]
+fn str_tostring_and_format_apply_masks() {
+    let (runtime, value) = run_str(
+        "plain = str.tostring(0.1 + 0.2)\nwhole = str.tostring(42)\nmasked = str.tostring(3.14159, \"#.##\")\npadded = str.tostring(2.5, \"0.000\")\ngrouped = str.tostring(1234567.891, \"#,##0.0\")\npercent = str.tostring(12.345, format.percent)\nvolume = str.tostring(1234567, format.volume)\nmissing = str.tostring(na)\nmessage = str.format(\"{0} is {1,number,#.##} ('{2}')\", \"close\", 101.256, true)\ngrouping = str.format(\"{0}\", 1234.5)\nratio = str.format(\"{0,number,percent}\", 0.25)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+
+    for (name, expected) in [
+        ("plain", "0.3"),
+        ("whole", "42"),
+        ("masked", "3.14"),
+        ("padded", "2.500"),
+        ("grouped", "1,234,567.9"),
+        ("percent", "12.35%"),
+        ("volume", "1.235M"),
+        ("missing", "NaN"),
+        ("message", "close is 101.26 ({2})"),
+        ("grouping", "1,234.5"),
+        ("ratio", "25%"),
+    ] {
+        assert_eq!(
+            variable(&runtime, name),
+            Some(Value::String(expected.into())),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn color_literals_and_the_color_namespace() {
+    let (runtime, value) = run_str(
+        "# a comment\nbull = #26A69A # teal\nbear = #EF535080\nfaded = color.new(color.red, 50)\ncustom = color.rgb(255, 128, 0)\nred = color.r(bear)\ntransparency = color.t(faded)\nopaque = color.t(bull)\nmiddle = color.from_gradient(50, 0, 100, #000000, #FFFFFF)\nabove = color.from_gradient(150, 0, 100, #000000, #FFFFFF)\nmissing = color.new(na, 50)\nsame = bull == #26a69a\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+
+    for (name, expected) in [
+        ("bull", "#26A69A"),
+        ("bear", "#EF535080"),
+        ("faded", "#FF525280"),
+        ("custom", "#FF8000"),
+        ("middle", "#808080"),
+        ("above", "#FFFFFF"),
+    ] {
+        assert_eq!(
+            variable(&runtime, name).map(|color| color.to_string()),
+            Some(expected.to_string()),
+            "{name}"
+        );
+    }
+
+    assert_eq!(variable(&runtime, "red"), Some(Value::Float(239.0)));
+    assert_eq!(variable(&runtime, "transparency"), Some(Value::Float(50.0)));
+    assert_eq!(variable(&runtime, "opaque"), Some(Value::Float(0.0)));
+    assert_eq!(variable(&runtime, "missing"), Some(Value::None));
+    assert_eq!(variable(&runtime, "same"), Some(Value::Boolean(true)));
+}
+
+#[test]
+fn hex_after_an_operand_is_a_comment() {
+    let (runtime, value) = run_str("x = 1 #facade comment\ny = x + 1 #c0ffee\n");
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "y"), Some(Value::Number(2)));
+}
+
+#[test]
+fn plots_keep_the_color_of_each_call() {
+    let (runtime, value) = run_str(
+        "for i = 1 to 2\n    plot(i * 1.5, \"level\", i > 1 ? #26A69A : color.new(color.red, 50), 2)\nplot(na)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+
+    let plots = runtime.plots();
+    let points = |index: usize| {
+        plots[index]
+            .points
+            .iter()
+            .map(|point| {
+                (
+                    point.value.clone(),
+                    point.color.map(|color| color.to_string()),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(plots.len(), 2);
+    assert_eq!(plots[0].title, "level");
+    assert_eq!(plots[0].linewidth, 2);
+    assert_eq!(
+        points(0),
+        vec![
+            (Value::Float(1.5), Some("#FF525280".to_string())),
+            (Value::Float(3.0), Some("#26A69A".to_string())),
+        ]
+    );
+    assert_eq!(points(1), vec![(Value::None, Some("#2196F3".to_string()))]);
+}
+
+#[test]
+fn drawing_objects_keep_their_properties_and_respect_limits() {
+    let (runtime, value) = run_str(
+        "first = line.new(0, 1.0, 10, 2.0)\nline.set_xy1(first, 2, 3.0)\nline.set_color(first, color.red)\nmiddle = line.get_price(first, 6)\ncopy = line.copy(first)\nline.delete(first)\nx1 = line.get_x1(first)\ncopied = copy.get_x1()\nlabels = label.new(1, 2.0, \"hi\")\nlabel.set_text(labels, \"bye\")\ntext = label.get_text(labels)\nupper = line.new(0, 2.0, 1, 2.0)\nfill = linefill.new(copy, upper, color.green)\nline.delete(copy)\nfills = array.size(linefill.all())\nlines = array.size(line.all())\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "middle"), Some(Value::Float(2.5)));
+    assert_eq!(variable(&runtime, "x1"), Some(Value::None));
+    assert_eq!(variable(&runtime, "copied"), Some(Value::Number(2)));
+    assert_eq!(
+        variable(&runtime, "text"),
+        Some(Value::String("bye".into()))
+    );
+    assert_eq!(variable(&runtime, "fills"), Some(Value::Number(0)));
+    assert_eq!(variable(&runtime, "lines"), Some(Value::Number(1)));
+
+    let drawings = runtime.drawings();
+
+    assert_eq!(drawings.len(), 2);
+    assert_eq!(drawings[0].kind, DrawingKind::Label);
+    assert_eq!(
+        drawings[0].get("color").map(ToString::to_string),
+        Some("#2196F3".to_string())
+    );
+
+    let mut runtime = Runtime::default();
+
+    runtime.set_drawing_limit(DrawingKind::Label, 4);
+
+    let source = SourceFile::from_str(
+        "root.ps",
+        "for i = 1 to 5\n    label.new(i, 1.0, str.tostring(i))\n",
+    )
+    .unwrap();
+    let (runtime, value) = run_with(runtime, source);
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(
+        runtime
+            .drawings()
+            .iter()
+            .filter_map(|label| label.get("x").cloned())
+            .collect::<Vec<_>>(),
+        (2..=5).map(Value::Number).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn tables_render_to_text_and_html() {
+    let (runtime, value) = run_str(
+        "t = table.new(position.top_right, 3, 3, color.white)\ntable.cell(t, 0, 0, \"Symbol\")\ntable.cell(t, 1, 0, \"Signal\")\ntable.merge_cells(t, 1, 0, 2, 0)\ntable.cell(t, 0, 1, \"BTC\", 0, 0, color.black, text.align_left)\ntable.cell(t, 1, 1, \"buy\")\ntable.cell_set_bgcolor(t, 1, 1, color.green)\ntable.cell(t, 2, 1, \"<1%\")\ntable.cell(t, 0, 2, \"ETH\")\ntable.cell_set_text(t, 1, 2, \"sell\")\ntable.cell(t, 2, 2, \"x\")\ntable.clear(t, 2, 2)\nother = table.new(position.bottom_left, 1, 1)\ntable.delete(other)\ncount = array.size(table.all())\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "count"), Some(Value::Number(1)));
+
+    let tables = runtime.tables();
+
+    assert_eq!(
+        tables[0].render_text(false),
+        "┌────────┬────────────┐\n│ Symbol │   Signal   │\n│ BTC    │ buy  │ <1% │\n│  ETH   │ sell │     │\n└────────┴──────┴─────┘\n"
+    );
+
+    let html = tables[0].render_html();
+
+    assert_eq!(
+        html.lines().nth(2),
+        Some("    <td style=\"color: #363A45; text-align: center; vertical-align: middle; font-size: 12px\">Symbol</td>")
+    );
+    assert!(html.contains(" colspan=\"2\" "));
+    assert!(html.contains(">&lt;1%</td>"));
+    assert!(tables[0].render_text(true).contains("38;2;54;58;69"));
+
+    let (_, value) = run_str(
+        "t = table.new(position.top_left, 2, 2)\ntable.cell(t, 2, 0, \"x\")\n",
+    );
+
+    assert!(matches!(value, Value::Error(_, ErrorCode::OutOfBounds, _)));
+}
+
+#[test]
+fn script_inputs_are_collected_before_running_and_can_be_overridden() {
+    let source = SourceFile::from_str(
+        "root.ps",
+        &format!(
+            "{SIGNAL}length = input.int(14, \"Length\", minval=1, maxval = 100)\nfactor = input.float(2, \"Factor\")\nshow = input.bool(true, \"Show\")\ncaption = input.string(\"a\", \"Caption\", [\"a\", \"b\"])\nresolution = input.timeframe(\"D\", \"Timeframe\")\nhours = input.session(\"0930-1600\", \"Session\")\ntint = input.color(color.red, \"Tint\")\nmode = input.enum(Signal.buy, \"Mode\")\ndoubled = length * 2\n"
+        ),
+    )
+    .unwrap();
+    let config = Config::default();
+    let mut parsing_ctx = ParsingCtx::new(&config);
+
+    parsing_ctx.add_file(&source);
+
+    let root = parse_str(&mut parsing_ctx, &config).expect("script should parse");
+    let mut inputs = Runtime::script_inputs(&root);
+
+    assert_eq!(
+        inputs
+            .inputs()
+            .iter()
+            .map(|input| (input.name.clone().unwrap(), input.kind))
+            .collect::<Vec<_>>(),
+        [
+            ("length", InputKind::Int),
+            ("factor", InputKind::Float),
+            ("show", InputKind::Bool),
+            ("caption", InputKind::String),
+            ("resolution", InputKind::Timeframe),
+            ("hours", InputKind::Session),
+            ("tint", InputKind::Color),
+            ("mode", InputKind::Enum),
+        ]
+        .map(|(name, kind)| (name.to_string(), kind))
+    );
+    assert_eq!(inputs.get("Length").unwrap().minval, Some(1.0));
+    assert_eq!(inputs.get("Length").unwrap().maxval, Some(100.0));
+    assert_eq!(inputs.get("factor").unwrap().default, Value::Float(2.0));
+
+    for (name, text) in [
+        ("length", "500"),
+        ("length", "1.5"),
+        ("caption", "c"),
+        ("resolution", "5X"),
+        ("hours", "0930"),
+        ("tint", "red"),
+        ("missing", "1"),
+    ] {
+        assert!(inputs.set_str(name, text).is_err(), "{name}={text}");
+    }
+
+    inputs
+        .apply_args([
+            "--input",
+            "length=21",
+            "--input=caption=b",
+            "--verbose",
+            "--input=mode=sell",
+            "--input=tint=#00FF0080",
+        ])
+        .unwrap();
+    inputs.set("show", Value::Boolean(false)).unwrap();
+
+    let mut runtime = Runtime::default();
+
+    runtime.set_inputs(inputs);
+
+    let value = runtime.eval_app(root);
+
+    assert!(!value.is_error(), "{value}");
+
+    for (name, expected) in [
+        ("doubled", "42"),
+        ("factor", "2"),
+        ("show", "false"),
+        ("caption", "\"b\""),
+        ("tint", "#00FF0080"),
+        ("mode", "Sell signal"),
+    ] {
+        assert_eq!(
+            variable(&runtime, name).map(|value| value.to_string()),
+            Some(expected.to_string()),
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn declarations_set_the_script_metadata() {
+    let (runtime, value) = run_str(
+        "indicator(\"Relative Strength\", \"RSI\", false, format.price, 2, max_lines_count = 2)\nfor i = 0 to 4\n    line.new(i, 1, i + 1, 2)\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+
+    let metadata = runtime.metadata().unwrap();
+
+    assert_eq!(metadata.title, "Relative Strength");
+    assert_eq!(metadata.shorttitle.as_deref(), Some("RSI"));
+    assert_eq!((metadata.format.as_str(), metadata.precision), ("price", Some(2)));
+    assert!(!metadata.is_strategy());
+    assert_eq!(runtime.drawings().len(), 2);
+
+    // Only strategies have an account.
+    let (_, value) = run_str("indicator(\"I\")\nx = strategy.equity\n");
+
+    assert!(matches!(value, Value::Error(_, ErrorCode::Reference, _)));
+
+    let (runtime, value) = run_str(
+        "strategy(\"S\", overlay = true, initial_capital = 5000, default_qty_type = strategy.percent_of_equity, commission_type = strategy.commission.cash_per_order)\nequity = strategy.equity\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "equity"), Some(Value::Float(5000.0)));
+
+    let strategy = runtime.metadata().and_then(|metadata| metadata.strategy).unwrap();
+
+    assert_eq!(strategy.default_qty_type, "percent_of_equity");
+    assert_eq!(strategy.commission_type, "cash_per_order");
+    assert_eq!(strategy.pyramiding, 0);
+
+    for script in [
+        "indicator(\"I\", precision = 20)\n",
+        "indicator(\"I\", overlay = 1)\n",
+        "strategy(\"S\", commission_type = \"free\")\n",
+        "strategy(\"S\", initial_capital = 0)\n",
+    ] {
+        let (_, value) = run_str(script);
+
+        assert!(
+            matches!(value, Value::Error(_, ErrorCode::Declaration, _)),
+            "{script}"
+        );
+    }
+}
+
+#[test]
+fn script_metadata_is_read_before_running() {
+    let config = Config::default();
+    let mut parsing_ctx = ParsingCtx::new(&config);
+
+    parsing_ctx.add_file(
+        &SourceFile::from_str("root.ps", "x = 1\nlibrary(\"Helpers\", true)\n").unwrap(),
+    );
+
+    let root = parse_str(&mut parsing_ctx, &config).expect("script should parse");
+    let metadata: ScriptMetadata = Runtime::script_metadata(&root).unwrap();
+
+    assert_eq!(metadata.kind.name(), "library");
+    assert_eq!(metadata.title, "Helpers");
+    assert!(metadata.overlay);
+}
+
+#[test]
+fn annotations_document_the_following_declarations() {
+    let config = Config::default();
+    let mut parsing_ctx = ParsingCtx::new(&config);
+
+    parsing_ctx.add_file(
+        &SourceFile::from_str(
+            "root.ps",
+            "//@version=6\n//@description Moving averages.\nlibrary(\"Averages\")\n\n// Not documented.\nhelper(x) => x\n\n//@function Mean of two numbers.\n//@param a First number.\n//@param b Second number.\n//@returns The mean.\n//@example m = mean(1, 3)\nmean(a, b) =>\n    // The sum is halved.\n    (a + b) / 2\n//@type A point.\n//@field x Horizontal.\ntype Point\n    float x\nresult = mean(1, 3) // 2\n",
+        )
+        .unwrap(),
+    );
+
+    let root = parse_str(&mut parsing_ctx, &config).expect("script should parse");
+
+    assert_eq!(root.r#mod.version(), Some(6));
+
+    let documented = root
+        .r#mod
+        .documented()
+        .map(|item| (item.name(), item.docs().cloned()))
+        .collect::<Vec<_>>();
+    let docs = |name: &str| {
+        documented
+            .iter()
+            .find(|(item, _)| *item == name)
+            .and_then(|(_, docs)| docs.clone())
+    };
+
+    assert_eq!(documented.len(), 4);
+    assert_eq!(
+        docs("library").and_then(|docs| docs.description).as_deref(),
+        Some("Moving averages.")
+    );
+    assert_eq!(docs("helper"), None);
+
+    let mean = docs("mean").unwrap();
+
+    assert_eq!(mean.description.as_deref(), Some("Mean of two numbers."));
+    assert_eq!(mean.param("b"), Some("Second number."));
+    assert_eq!(mean.returns.as_deref(), Some("The mean."));
+    assert_eq!(mean.examples, ["m = mean(1, 3)"]);
+    assert_eq!(docs("Point").unwrap().field("x"), Some("Horizontal."));
+
+    let mut runtime = Runtime::default();
+
+    assert!(!runtime.eval_app(root).is_error());
+    assert_eq!(variable(&runtime, "result"), Some(Value::Number(2)));
+}
+
+const GEOMETRY: &str = "//@version=6\nlibrary(\"Geometry\")\n\nexport type Point\n    float x = 0.0\n    float y = 0.0\n\nsquare(x) => x * x\n\nexport distance(Point a, Point b) =>\n    math.sqrt(square(a.x - b.x) + square(a.y - b.y))\n\nexport method shifted(Point this, float dx) =>\n    Point.new(this.x + dx, this.y)\n";
+
+#[test]
+fn imported_library_runs_its_exports() {
+    let library_dir = TempDir::new("libraries", &[("alice/Geometry/1.ps", GEOMETRY)]);
+    let config = Config {
+        library_dir: library_dir.path().to_path_buf(),
+        ..Config::default()
+    };
+    let (runtime, value) = run_with_config(
+        Runtime::default(),
+        SourceFile::from_str(
+            "root.ps",
+            "indicator(\"Uses Geometry\")\nimport alice/Geometry/1 as geo\nimport alice/Geometry/1 as plane {distance}\na = geo.Point.new()\nb = geo.Point.new(3.0, 4.0)\nd = geo.distance(a, b)\ne = distance(b, a)\nx = b.shifted(1.0).x\n",
+        )
+        .unwrap(),
+        config,
+    );
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "d"), Some(Value::Float(5.0)));
+    assert_eq!(variable(&runtime, "e"), Some(Value::Float(5.0)));
+    assert_eq!(variable(&runtime, "x"), Some(Value::Float(4.0)));
+    // The library's own names stay in the library.
+    assert_eq!(variable(&runtime, "square"), None);
+    assert_eq!(runtime.metadata().unwrap().title, "Uses Geometry");
+}
+
+fn run_std(content: &str) -> (Runtime, Value) {
+    let config = Config {
+        std: true,
+        ..Config::default()
+    };
+
+    let source = SourceFile::from_str("root.ps", content).unwrap();
+
+    run_with_config(Runtime::default(), source, config)
+}
+
+#[test]
+fn prelude_is_declared_with_std() {
+    let (runtime, value) =
+        run_std("x = clamp(15, 0, 10)\ny = lerp(0.0, 10.0, 0.25)\nz = between(5, 1, 3)\n");
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "x"), Some(Value::Number(10)));
+    assert_eq!(variable(&runtime, "y"), Some(Value::Float(2.5)));
+    assert_eq!(variable(&runtime, "z"), Some(Value::Boolean(false)));
+}
+
+#[test]
+fn script_declarations_shadow_the_prelude() {
+    let (runtime, value) =
+        run_std("clamp(x, a, b) => 99\nx = clamp(1, 2, 3)\nlerp = 5\ny = lerp + 1\n");
+
+    assert!(!value.is_error(), "{value}");
+    assert_eq!(variable(&runtime, "x"), Some(Value::Number(99)));
+    assert_eq!(variable(&runtime, "y"), Some(Value::Number(6)));
+}
+
+#[test]
+fn standard_library_helpers() {
+    let (runtime, value) = run_std(
+        "mapped = remap(5.0, 0.0, 10.0, 100.0, 200.0)\nflat = inverse_lerp(1.0, 1.0, 2.0)\nclose = nearly_equal(0.1 + 0.2, 0.3, 0.000001)\nrounded = round_to(7.3, 0.5)\nchange = percent_change(50.0, 75.0)\nsums = cumulative_sum(array.from(1.0, 2.0, 3.0))\nlast_sum = array.last(sums)\nscaled = normalized(array.from(2.0, 4.0, 6.0))\nmiddle = array.get(scaled, 1)\nscores = zscores(array.from(1.0, 1.0))\nproduct = dot(array.from(1.0, 2.0, 3.0), array.from(4.0, 5.0))\nline = repeat(\"-\", 3)\nleft = pad_left(\"7\", 3, \"0\")\nright = pad_right(\"ab\", 4, \" \")\n",
+    );
+
+    assert!(!value.is_error(), "{value}");
+
+    for (name, expected) in [
+        ("mapped", Value::Float(150.0)),
+        ("flat", Value::None),
+        ("close", Value::Boolean(true)),
+        ("rounded", Value::Float(7.5)),
+        ("change", Value::Float(50.0)),
+        ("last_sum", Value::Float(6.0)),
+        ("middle", Value::Float(0.5)),
+        ("product", Value::Float(14.0)),
+        ("line", Value::String("---".into())),
+        ("left", Value::String("007".into())),
+        ("right", Value::String("ab  ".into())),
+    ] {
+        assert_eq!(variable(&runtime, name), Some(expected), "{name}");
+    }
+
+    let scores = variable(&runtime, "scores").unwrap();
+
+    assert_eq!(scores.to_string(), "[na, na]");
+}